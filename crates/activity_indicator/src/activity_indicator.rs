@@ -219,6 +219,37 @@ impl ActivityIndicator {
             };
         }
 
+        // Show any git operation talking to a remote.
+        let project = self.project.read(cx);
+        let mut git_remote_operations = project.git_remote_operations();
+        if let Some(operation) = git_remote_operations.next() {
+            let mut message = format!("{}...", operation.title);
+            if let Some(progress_message) = operation.message.as_ref() {
+                message.push(' ');
+                message.push_str(progress_message);
+            }
+
+            let additional_operation_count = git_remote_operations.count();
+            if additional_operation_count > 0 {
+                write!(&mut message, " + {} more", additional_operation_count).unwrap();
+            }
+
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server installation info.
         let mut downloading = SmallVec::<[_; 3]>::new();
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
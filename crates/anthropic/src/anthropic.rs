@@ -87,6 +87,12 @@ impl Model {
     }
 }
 
+/// The beta features requests opt into: tool use and prompt caching.
+const ANTHROPIC_BETA: &str = "tools-2024-04-04,prompt-caching-2024-07-31";
+
+/// The number of cache breakpoints a request can have at most.
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
 pub async fn complete(
     client: &dyn HttpClient,
     api_url: &str,
@@ -98,7 +104,7 @@ pub async fn complete(
         .method(Method::POST)
         .uri(uri)
         .header("Anthropic-Version", "2023-06-01")
-        .header("Anthropic-Beta", "tools-2024-04-04")
+        .header("Anthropic-Beta", ANTHROPIC_BETA)
         .header("X-Api-Key", api_key)
         .header("Content-Type", "application/json");
 
@@ -139,7 +145,7 @@ pub async fn stream_completion(
         .method(Method::POST)
         .uri(uri)
         .header("Anthropic-Version", "2023-06-01")
-        .header("Anthropic-Beta", "tools-2024-04-04")
+        .header("Anthropic-Beta", ANTHROPIC_BETA)
         .header("X-Api-Key", api_key)
         .header("Content-Type", "application/json");
     if let Some(low_speed_timeout) = low_speed_timeout {
@@ -193,7 +199,7 @@ pub fn extract_text_from_events(
         match response {
             Ok(response) => match response {
                 Event::ContentBlockStart { content_block, .. } => match content_block {
-                    Content::Text { text } => Some(Ok(text)),
+                    Content::Text { text, .. } => Some(Ok(text)),
                     _ => None,
                 },
                 Event::ContentBlockDelta { delta, .. } => match delta {
@@ -208,7 +214,7 @@ pub fn extract_text_from_events(
     })
 }
 
-fn api_error_to_err(
+pub fn api_error_to_err(
     ApiError {
         error_type,
         message,
@@ -223,7 +229,7 @@ pub struct Message {
     pub content: Vec<Content>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Role {
     User,
@@ -234,9 +240,17 @@ pub enum Role {
 #[serde(tag = "type")]
 pub enum Content {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     #[serde(rename = "image")]
-    Image { source: ImageSource },
+    Image {
+        source: ImageSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
@@ -247,9 +261,42 @@ pub enum Content {
     ToolResult {
         tool_use_id: String,
         content: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
     },
 }
 
+impl Content {
+    /// Marks the end of a prefix of the request that's cached, so that later requests
+    /// starting with the same prefix reuse it.
+    pub fn set_cache_control(&mut self, new_cache_control: CacheControl) {
+        match self {
+            Content::Text { cache_control, .. } | Content::Image { cache_control, .. } => {
+                *cache_control = Some(new_cache_control)
+            }
+            Content::ToolUse { .. } | Content::ToolResult { .. } => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheControl {
+    #[serde(rename = "type")]
+    pub cache_type: CacheControlType,
+}
+
+impl CacheControl {
+    pub const EPHEMERAL: Self = Self {
+        cache_type: CacheControlType::Ephemeral,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheControlType {
+    Ephemeral,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImageSource {
     #[serde(rename = "type")]
@@ -314,6 +361,12 @@ pub struct Usage {
     pub input_tokens: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_tokens: Option<u32>,
+    /// The input tokens written to the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+    /// The input tokens read from the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,6 +9,7 @@ mod prompts;
 mod slash_command;
 mod streaming_diff;
 mod terminal_inline_assistant;
+mod tools;
 
 pub use assistant_panel::{AssistantPanel, AssistantPanelEvent};
 use assistant_settings::AssistantSettings;
@@ -29,9 +30,9 @@ use semantic_index::{CloudEmbeddingProvider, SemanticIndex};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
 use slash_command::{
-    active_command, default_command, diagnostics_command, docs_command, fetch_command,
-    file_command, now_command, project_command, prompt_command, search_command, symbols_command,
-    tabs_command, term_command,
+    active_command, default_command, diagnostics_command, diff_command, docs_command,
+    fetch_command, file_command, log_command, now_command, project_command, prompt_command,
+    search_command, symbols_command, tabs_command, term_command,
 };
 use std::sync::Arc;
 pub(crate) use streaming_diff::*;
@@ -40,6 +41,7 @@ actions!(
     assistant,
     [
         Assist,
+        AssistWithTools,
         Split,
         CycleMessageRole,
         QuoteSelection,
@@ -260,6 +262,8 @@ fn register_slash_commands(cx: &mut AppContext) {
     slash_command_registry.register_command(now_command::NowSlashCommand, true);
     slash_command_registry.register_command(diagnostics_command::DiagnosticsSlashCommand, true);
     slash_command_registry.register_command(docs_command::DocsSlashCommand, true);
+    slash_command_registry.register_command(diff_command::DiffSlashCommand, true);
+    slash_command_registry.register_command(log_command::LogSlashCommand, true);
     slash_command_registry.register_command(fetch_command::FetchSlashCommand, false);
 }
 
@@ -8,11 +8,13 @@ use crate::{
         SlashCommandCompletionProvider, SlashCommandRegistry,
     },
     terminal_inline_assistant::TerminalInlineAssistant,
-    Assist, ConfirmCommand, Context, ContextEvent, ContextId, ContextStore, CycleMessageRole,
-    DebugEditSteps, DeployHistory, DeployPromptLibrary, EditStep, EditStepOperations,
-    EditSuggestionGroup, InlineAssist, InlineAssistId, InlineAssistant, InsertIntoEditor,
-    MessageStatus, ModelSelector, PendingSlashCommand, PendingSlashCommandStatus, QuoteSelection,
-    RemoteContextMetadata, SavedContextMetadata, Split, ToggleFocus, ToggleModelSelector,
+    tools::ContextTools,
+    Assist, AssistWithTools, ConfirmCommand, Context, ContextEvent, ContextId, ContextImage,
+    ContextStore, CycleMessageRole, DebugEditSteps, DeployHistory, DeployPromptLibrary, EditStep,
+    EditStepOperations, EditSuggestionGroup, InlineAssist, InlineAssistId, InlineAssistant,
+    InsertIntoEditor, MessageAnchor, MessageStatus, ModelSelector, PendingSlashCommand,
+    PendingSlashCommandStatus, QuoteSelection, RemoteContextMetadata, SavedContextMetadata, Split,
+    ToggleFocus, ToggleModelSelector, ToolUseAnchor, ToolUseStatus,
 };
 use crate::{ContextStoreEvent, ShowConfiguration};
 use anyhow::{anyhow, Result};
@@ -22,8 +24,8 @@ use collections::{BTreeSet, HashMap, HashSet};
 use editor::{
     actions::{FoldAt, MoveToEndOfLine, Newline, ShowCompletions, UnfoldAt},
     display_map::{
-        BlockDisposition, BlockProperties, BlockStyle, Crease, CustomBlockId, RenderBlock,
-        ToDisplayPoint,
+        BlockContext, BlockDisposition, BlockProperties, BlockStyle, Crease, CustomBlockId,
+        RenderBlock, ToDisplayPoint,
     },
     scroll::{Autoscroll, AutoscrollStrategy, ScrollAnchor},
     Anchor, Editor, EditorEvent, ExcerptRange, MultiBuffer, RowExt, ToOffset as _, ToPoint,
@@ -31,13 +33,14 @@ use editor::{
 use editor::{display_map::CreaseId, FoldPlaceholder};
 use fs::Fs;
 use gpui::{
-    div, percentage, point, svg, Action, Animation, AnimationExt, AnyElement, AnyView, AppContext,
-    AsyncWindowContext, ClipboardItem, Context as _, DismissEvent, Empty, Entity, EventEmitter,
-    FocusHandle, FocusableView, InteractiveElement, IntoElement, Model, ParentElement, Pixels,
-    Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Task,
-    TextStyleRefinement, Transformation, UpdateGlobal, View, ViewContext, VisualContext, WeakView,
-    WindowContext,
+    div, img, percentage, point, px, svg, Action, Animation, AnimationExt, AnyElement, AnyView,
+    AppContext, AsyncWindowContext, ClipboardItem, Context as _, DismissEvent, Empty, Entity,
+    EventEmitter, ExternalPaths, FocusHandle, FocusableView, Img, InteractiveElement, IntoElement,
+    Model, ParentElement, Pixels, Render, SharedString, StatefulInteractiveElement, Styled,
+    Subscription, Task, TextStyleRefinement, Transformation, UpdateGlobal, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
+use image_viewer::ImageView;
 use indexed_docs::IndexedDocsStore;
 use language::{
     language_settings::SoftWrap, Buffer, Capability, LanguageRegistry, LspAdapterDelegate, Point,
@@ -53,9 +56,11 @@ use settings::Settings;
 use std::{
     borrow::Cow,
     cmp::{self, Ordering},
+    ffi::OsStr,
     fmt::Write,
-    ops::Range,
-    path::PathBuf,
+    future::Future,
+    ops::{ControlFlow, Range},
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -351,6 +356,33 @@ impl AssistantPanel {
             pane.set_can_navigate(true, cx);
             pane.display_nav_history_buttons(None);
             pane.set_should_display_tab_bar(|_| true);
+            pane.set_custom_drop_handle(cx, |pane, dropped_item, cx| {
+                let Some(paths) = dropped_item.downcast_ref::<ExternalPaths>() else {
+                    return ControlFlow::Continue(());
+                };
+                let image_paths = paths
+                    .paths()
+                    .iter()
+                    .filter(|path| is_image_path(path))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let Some(context_editor) = pane
+                    .active_item()
+                    .and_then(|item| item.downcast::<ContextEditor>())
+                else {
+                    return ControlFlow::Continue(());
+                };
+                if image_paths.is_empty() {
+                    return ControlFlow::Continue(());
+                }
+
+                context_editor.update(cx, |context_editor, cx| {
+                    for path in image_paths {
+                        context_editor.insert_image_from_path(path, cx);
+                    }
+                });
+                ControlFlow::Break(())
+            });
             pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
                 h_flex()
                     .gap(Spacing::Small.rems(cx))
@@ -1285,6 +1317,8 @@ pub struct ContextEditor {
     lsp_adapter_delegate: Option<Arc<dyn LspAdapterDelegate>>,
     editor: View<Editor>,
     blocks: HashSet<CustomBlockId>,
+    image_blocks: HashSet<CustomBlockId>,
+    tool_use_blocks: HashSet<CustomBlockId>,
     scroll_position: Option<ScrollPosition>,
     remote_id: Option<workspace::ViewId>,
     pending_slash_command_creases: HashMap<Range<language::Anchor>, CreaseId>,
@@ -1296,6 +1330,8 @@ pub struct ContextEditor {
 
 const DEFAULT_TAB_TITLE: &str = "New Context";
 const MAX_TAB_TITLE_LEN: usize = 16;
+/// The number of lines an image takes up in a context editor.
+const IMAGE_BLOCK_HEIGHT: u32 = 12;
 
 impl ContextEditor {
     fn for_context(
@@ -1339,6 +1375,8 @@ impl ContextEditor {
             editor,
             lsp_adapter_delegate,
             blocks: Default::default(),
+            image_blocks: Default::default(),
+            tool_use_blocks: Default::default(),
             scroll_position: None,
             remote_id: None,
             fs,
@@ -1351,6 +1389,8 @@ impl ContextEditor {
             assistant_panel,
         };
         this.update_message_headers(cx);
+        this.update_image_blocks(cx);
+        this.update_tool_use_blocks(cx);
         this.insert_slash_command_output_sections(sections, cx);
         this
     }
@@ -1405,28 +1445,43 @@ impl ContextEditor {
 
     fn send_to_model(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(user_message) = self.context.update(cx, |context, cx| context.assist(cx)) {
-            let new_selection = {
-                let cursor = user_message
-                    .start
-                    .to_offset(self.context.read(cx).buffer().read(cx));
-                cursor..cursor
-            };
-            self.editor.update(cx, |editor, cx| {
-                editor.change_selections(
-                    Some(Autoscroll::Strategy(AutoscrollStrategy::Fit)),
-                    cx,
-                    |selections| selections.select_ranges([new_selection]),
-                );
-            });
-            // Avoid scrolling to the new cursor position so the assistant's output is stable.
-            cx.defer(|this, _| this.scroll_position = None);
+            self.select_queued_reply(user_message, cx);
         }
     }
 
+    fn assist_with_tools(&mut self, _: &AssistWithTools, cx: &mut ViewContext<Self>) {
+        let tools = ContextTools::builtin(self.workspace.clone(), cx.window_handle());
+        if let Some(user_message) = self
+            .context
+            .update(cx, |context, cx| context.assist_with_tools(tools, cx))
+        {
+            self.select_queued_reply(user_message, cx);
+        }
+    }
+
+    /// Moves the cursor to the user's reply queued after the assistant's response.
+    fn select_queued_reply(&mut self, user_message: MessageAnchor, cx: &mut ViewContext<Self>) {
+        let new_selection = {
+            let cursor = user_message
+                .start
+                .to_offset(self.context.read(cx).buffer().read(cx));
+            cursor..cursor
+        };
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                Some(Autoscroll::Strategy(AutoscrollStrategy::Fit)),
+                cx,
+                |selections| selections.select_ranges([new_selection]),
+            );
+        });
+        // Avoid scrolling to the new cursor position so the assistant's output is stable.
+        cx.defer(|this, _| this.scroll_position = None);
+    }
+
     fn cancel_last_assist(&mut self, _: &editor::actions::Cancel, cx: &mut ViewContext<Self>) {
         if !self
             .context
-            .update(cx, |context, _| context.cancel_last_assist())
+            .update(cx, |context, cx| context.cancel_last_assist(cx))
         {
             cx.propagate();
         }
@@ -1604,6 +1659,18 @@ impl ContextEditor {
                     context.save(Some(Duration::from_millis(500)), self.fs.clone(), cx);
                 });
             }
+            ContextEvent::ImagesChanged => {
+                self.update_image_blocks(cx);
+                self.context.update(cx, |context, cx| {
+                    context.save(Some(Duration::from_millis(500)), self.fs.clone(), cx);
+                });
+            }
+            ContextEvent::ToolUsesChanged => {
+                self.update_tool_use_blocks(cx);
+                self.context.update(cx, |context, cx| {
+                    context.save(Some(Duration::from_millis(500)), self.fs.clone(), cx);
+                });
+            }
             ContextEvent::EditStepsChanged => {
                 cx.notify();
             }
@@ -1922,140 +1989,15 @@ impl ContextEditor {
         let assistant_panel = self.assistant_panel.clone();
         cx.spawn(|this, mut cx| async move {
             let edit_suggestions = edit_suggestions.await;
-
-            let mut assist_ids = Vec::new();
-            let editor = if edit_suggestions.is_empty() {
+            let Some((editor, assist_ids)) = open_editor_for_edit_suggestions(
+                edit_suggestions,
+                workspace,
+                project,
+                assistant_panel,
+                &mut cx,
+            )?
+            else {
                 return Ok(());
-            } else if edit_suggestions.len() == 1
-                && edit_suggestions.values().next().unwrap().len() == 1
-            {
-                // If there's only one buffer and one suggestion group, open it directly
-                let (buffer, suggestion_groups) = edit_suggestions.into_iter().next().unwrap();
-                let suggestion_group = suggestion_groups.into_iter().next().unwrap();
-                let editor = workspace.update(&mut cx, |workspace, cx| {
-                    let active_pane = workspace.active_pane().clone();
-                    workspace.open_project_item::<Editor>(active_pane, buffer, false, false, cx)
-                })?;
-
-                cx.update(|cx| {
-                    for suggestion in suggestion_group.suggestions {
-                        let description = suggestion.description.unwrap_or_else(|| "Delete".into());
-
-                        let range = {
-                            let multibuffer = editor.read(cx).buffer().read(cx).read(cx);
-                            let (&excerpt_id, _, _) = multibuffer.as_singleton().unwrap();
-                            multibuffer
-                                .anchor_in_excerpt(excerpt_id, suggestion.range.start)
-                                .unwrap()
-                                ..multibuffer
-                                    .anchor_in_excerpt(excerpt_id, suggestion.range.end)
-                                    .unwrap()
-                        };
-
-                        InlineAssistant::update_global(cx, |assistant, cx| {
-                            let suggestion_id = assistant.suggest_assist(
-                                &editor,
-                                range,
-                                description,
-                                suggestion.initial_insertion,
-                                Some(workspace.clone()),
-                                assistant_panel.upgrade().as_ref(),
-                                cx,
-                            );
-                            assist_ids.push(suggestion_id);
-                        });
-                    }
-
-                    // Scroll the editor to the suggested assist
-                    editor.update(cx, |editor, cx| {
-                        let multibuffer = editor.buffer().read(cx).snapshot(cx);
-                        let (&excerpt_id, _, buffer) = multibuffer.as_singleton().unwrap();
-                        let anchor = if suggestion_group.context_range.start.to_offset(buffer) == 0
-                        {
-                            Anchor::min()
-                        } else {
-                            multibuffer
-                                .anchor_in_excerpt(excerpt_id, suggestion_group.context_range.start)
-                                .unwrap()
-                        };
-
-                        editor.set_scroll_anchor(
-                            ScrollAnchor {
-                                offset: gpui::Point::default(),
-                                anchor,
-                            },
-                            cx,
-                        );
-                    });
-                })?;
-
-                editor
-            } else {
-                // If there are multiple buffers or suggestion groups, create a multibuffer
-                let mut inline_assist_suggestions = Vec::new();
-                let multibuffer = cx.new_model(|cx| {
-                    let replica_id = project.read(cx).replica_id();
-                    let mut multibuffer = MultiBuffer::new(replica_id, Capability::ReadWrite);
-                    for (buffer, suggestion_groups) in edit_suggestions {
-                        let excerpt_ids = multibuffer.push_excerpts(
-                            buffer,
-                            suggestion_groups
-                                .iter()
-                                .map(|suggestion_group| ExcerptRange {
-                                    context: suggestion_group.context_range.clone(),
-                                    primary: None,
-                                }),
-                            cx,
-                        );
-
-                        for (excerpt_id, suggestion_group) in
-                            excerpt_ids.into_iter().zip(suggestion_groups)
-                        {
-                            for suggestion in suggestion_group.suggestions {
-                                let description =
-                                    suggestion.description.unwrap_or_else(|| "Delete".into());
-                                let range = {
-                                    let multibuffer = multibuffer.read(cx);
-                                    multibuffer
-                                        .anchor_in_excerpt(excerpt_id, suggestion.range.start)
-                                        .unwrap()
-                                        ..multibuffer
-                                            .anchor_in_excerpt(excerpt_id, suggestion.range.end)
-                                            .unwrap()
-                                };
-                                inline_assist_suggestions.push((
-                                    range,
-                                    description,
-                                    suggestion.initial_insertion,
-                                ));
-                            }
-                        }
-                    }
-                    multibuffer
-                })?;
-
-                let editor = cx
-                    .new_view(|cx| Editor::for_multibuffer(multibuffer, Some(project), true, cx))?;
-                cx.update(|cx| {
-                    InlineAssistant::update_global(cx, |assistant, cx| {
-                        for (range, description, initial_insertion) in inline_assist_suggestions {
-                            assist_ids.push(assistant.suggest_assist(
-                                &editor,
-                                range,
-                                description,
-                                initial_insertion,
-                                Some(workspace.clone()),
-                                assistant_panel.upgrade().as_ref(),
-                                cx,
-                            ));
-                        }
-                    })
-                })?;
-                workspace.update(&mut cx, |workspace, cx| {
-                    workspace.add_item_to_active_pane(Box::new(editor.clone()), None, false, cx)
-                })?;
-
-                editor
             };
 
             this.update(&mut cx, |this, _cx| {
@@ -2180,6 +2122,143 @@ impl ContextEditor {
         });
     }
 
+    fn update_image_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let old_blocks = std::mem::take(&mut self.image_blocks);
+            let new_blocks = self
+                .context
+                .read(cx)
+                .image_anchors()
+                .iter()
+                .filter_map(|image_anchor| {
+                    let anchor = image_anchor.anchor;
+                    let image = image_anchor.image.clone();
+                    let context = self.context.clone();
+                    Some(BlockProperties {
+                        position: buffer.anchor_in_excerpt(excerpt_id, anchor)?,
+                        height: IMAGE_BLOCK_HEIGHT,
+                        style: BlockStyle::Sticky,
+                        render: Box::new(move |cx| {
+                            // Fit the image within the block, without scaling it up.
+                            let max_height = cx.line_height * IMAGE_BLOCK_HEIGHT as f32;
+                            let max_width = cx.max_width - cx.gutter_dimensions.full_width();
+                            let width = px(image.image.width as f32);
+                            let height = px(image.image.height as f32);
+                            let scale = (max_width / width).min(max_height / height).min(1.);
+
+                            h_flex()
+                                .id(cx.block_id)
+                                .pl(cx.gutter_dimensions.full_width())
+                                .h(max_height)
+                                .w_full()
+                                .items_start()
+                                .gap_1()
+                                .child(
+                                    img(image.render_image.clone())
+                                        .w(width * scale)
+                                        .h(height * scale),
+                                )
+                                .child(
+                                    IconButton::new("remove-image", IconName::Close)
+                                        .icon_size(IconSize::Small)
+                                        .tooltip(|cx| Tooltip::text("Remove Image", cx))
+                                        .on_click({
+                                            let context = context.clone();
+                                            move |_, cx| {
+                                                context.update(cx, |context, cx| {
+                                                    context.remove_image(anchor, cx)
+                                                })
+                                            }
+                                        }),
+                                )
+                                .into_any_element()
+                        }),
+                        disposition: BlockDisposition::Above,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            editor.remove_blocks(old_blocks, None, cx);
+            let ids = editor.insert_blocks(new_blocks, None, cx);
+            self.image_blocks = HashSet::from_iter(ids);
+        });
+    }
+
+    fn update_tool_use_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let old_blocks = std::mem::take(&mut self.tool_use_blocks);
+            let new_blocks = self
+                .context
+                .read(cx)
+                .tool_uses()
+                .iter()
+                .filter_map(|tool_use| {
+                    let tool_use = tool_use.clone();
+                    let context = self.context.clone();
+                    Some(BlockProperties {
+                        position: buffer.anchor_in_excerpt(excerpt_id, tool_use.anchor)?,
+                        height: 2,
+                        style: BlockStyle::Sticky,
+                        render: Box::new(move |cx| render_tool_use(&tool_use, context.clone(), cx)),
+                        disposition: BlockDisposition::Below,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            editor.remove_blocks(old_blocks, None, cx);
+            let ids = editor.insert_blocks(new_blocks, None, cx);
+            self.tool_use_blocks = HashSet::from_iter(ids);
+        });
+    }
+
+    fn paste(&mut self, _: &editor::actions::Paste, cx: &mut ViewContext<Self>) {
+        // Some applications put a rendering of the text they copy on the clipboard too, in
+        // which case the text is pasted.
+        if cx.read_from_clipboard().is_none() {
+            if let Some(bytes) = cx.read_image_from_clipboard() {
+                self.insert_image(async move { Ok(bytes) }, cx);
+                return;
+            }
+        }
+
+        cx.propagate();
+    }
+
+    fn insert_image_from_path(&mut self, path: PathBuf, cx: &mut ViewContext<Self>) {
+        let fs = self.fs.clone();
+        self.insert_image(async move { fs.load_bytes(&path).await }, cx);
+    }
+
+    /// Inserts an image on a line of its own at the cursor, once it's loaded and decoded.
+    fn insert_image(
+        &mut self,
+        bytes: impl 'static + Send + Future<Output = Result<Vec<u8>>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let image = cx.background_executor().spawn(async move {
+            let bytes = bytes.await?;
+            ContextImage::from_bytes(&bytes)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let image = image.await?;
+            this.update(&mut cx, |this, cx| {
+                let offset = this.editor.update(cx, |editor, cx| {
+                    editor.insert("\n", cx);
+                    editor.selections.newest::<usize>(cx).head()
+                });
+                this.context.update(cx, |context, cx| {
+                    let anchor = context.buffer().read(cx).anchor_before(offset);
+                    context.insert_image(image, anchor, cx);
+                });
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+
     fn insert_selection(
         workspace: &mut Workspace,
         _: &InsertIntoEditor,
@@ -2224,6 +2303,26 @@ impl ContextEditor {
         let Some(panel) = workspace.panel::<AssistantPanel>(cx) else {
             return;
         };
+
+        // Quoting an image attaches it to the context.
+        if let Some(image_view) = workspace.active_item_as::<ImageView>(cx) {
+            let path = image_view.read(cx).path().to_path_buf();
+            if !panel.focus_handle(cx).contains_focused(cx) {
+                workspace.toggle_panel_focus::<AssistantPanel>(cx);
+            }
+            panel.update(cx, |_, cx| {
+                cx.defer(move |panel, cx| {
+                    if let Some(context) = panel
+                        .active_context_editor(cx)
+                        .or_else(|| panel.new_context(cx))
+                    {
+                        context.update(cx, |context, cx| context.insert_image_from_path(path, cx));
+                    };
+                });
+            });
+            return;
+        }
+
         let Some(editor) = workspace
             .active_item(cx)
             .and_then(|item| item.act_as::<Editor>(cx))
@@ -2433,9 +2532,11 @@ impl Render for ContextEditor {
             .capture_action(cx.listener(ContextEditor::cancel_last_assist))
             .capture_action(cx.listener(ContextEditor::save))
             .capture_action(cx.listener(ContextEditor::copy))
+            .capture_action(cx.listener(ContextEditor::paste))
             .capture_action(cx.listener(ContextEditor::cycle_message_role))
             .capture_action(cx.listener(ContextEditor::confirm_command))
             .on_action(cx.listener(ContextEditor::assist))
+            .on_action(cx.listener(ContextEditor::assist_with_tools))
             .on_action(cx.listener(ContextEditor::split))
             .on_action(cx.listener(ContextEditor::debug_edit_steps))
             .size_full()
@@ -2780,6 +2881,46 @@ impl ContextEditorToolbarItem {
             })
     }
 
+    fn render_spent_tokens(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        let context = self
+            .active_context_editor
+            .as_ref()?
+            .upgrade()?
+            .read(cx)
+            .context
+            .read(cx);
+        let usage = context.total_token_usage();
+        if usage.total_tokens() == 0 {
+            return None;
+        }
+        let total_tokens = humanize_token_count(usage.total_tokens() as usize);
+        let (label, cost_description) = match context.estimated_cost(cx) {
+            Some(cost) => (
+                format!("{total_tokens} spent (~{cost:.2})"),
+                format!("An estimated cost of {cost:.4}."),
+            ),
+            None => (
+                format!("{total_tokens} spent"),
+                "Set `language_models.model_prices` to estimate the cost.".to_string(),
+            ),
+        };
+        let description = format!(
+            "{} input, {} output, {} written to the cache, {} read from it. {cost_description}",
+            humanize_token_count(usage.input_tokens as usize),
+            humanize_token_count(usage.output_tokens as usize),
+            humanize_token_count(usage.cache_creation_input_tokens as usize),
+            humanize_token_count(usage.cache_read_input_tokens as usize),
+        );
+        Some(
+            div()
+                .id("spent-tokens")
+                .tooltip(move |cx| {
+                    Tooltip::with_meta("Tokens Spent", None, description.clone(), cx)
+                })
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+        )
+    }
+
     fn render_remaining_tokens(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
         let context = &self
             .active_context_editor
@@ -2805,9 +2946,26 @@ impl ContextEditorToolbarItem {
                 (color, token_count, max_token_count)
             }
         };
+        let last_token_usage = context.read(cx).last_token_usage();
         Some(
             h_flex()
+                .id("remaining-tokens")
                 .gap_0p5()
+                .when_some(last_token_usage, |this, usage| {
+                    this.tooltip(move |cx| {
+                        Tooltip::with_meta(
+                            "Last Response",
+                            None,
+                            format!(
+                                "{} input tokens read from the cache, {} written to it, {} uncached",
+                                humanize_token_count(usage.cache_read_input_tokens as usize),
+                                humanize_token_count(usage.cache_creation_input_tokens as usize),
+                                humanize_token_count(usage.input_tokens as usize),
+                            ),
+                            cx,
+                        )
+                    })
+                })
                 .child(
                     Label::new(humanize_token_count(token_count))
                         .size(LabelSize::Small)
@@ -2885,6 +3043,7 @@ impl Render for ContextEditorToolbarItem {
                 )
                 .with_handle(self.model_selector_menu_handle.clone()),
             )
+            .children(self.render_spent_tokens(cx))
             .children(self.render_remaining_tokens(cx))
             .child(self.render_inject_context_menu(cx));
 
@@ -3256,6 +3415,231 @@ impl Item for ConfigurationView {
 
 type ToggleFold = Arc<dyn Fn(bool, &mut WindowContext) + Send + Sync>;
 
+/// Opens an editor suggesting the given edits as inline assists, returning it along with the
+/// ids of the assists. A single group of suggestions is shown in the buffer it applies to,
+/// and several in a multibuffer.
+pub(crate) fn open_editor_for_edit_suggestions(
+    edit_suggestions: HashMap<Model<Buffer>, Vec<EditSuggestionGroup>>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    assistant_panel: WeakView<AssistantPanel>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<(View<Editor>, Vec<InlineAssistId>)>> {
+    let mut assist_ids = Vec::new();
+    let editor = if edit_suggestions.is_empty() {
+        return Ok(None);
+    } else if edit_suggestions.len() == 1 && edit_suggestions.values().next().unwrap().len() == 1 {
+        // If there's only one buffer and one suggestion group, open it directly
+        let (buffer, suggestion_groups) = edit_suggestions.into_iter().next().unwrap();
+        let suggestion_group = suggestion_groups.into_iter().next().unwrap();
+        let editor = workspace.update(cx, |workspace, cx| {
+            let active_pane = workspace.active_pane().clone();
+            workspace.open_project_item::<Editor>(active_pane, buffer, false, false, cx)
+        })?;
+
+        cx.update(|cx| {
+            for suggestion in suggestion_group.suggestions {
+                let description = suggestion.description.unwrap_or_else(|| "Delete".into());
+
+                let range = {
+                    let multibuffer = editor.read(cx).buffer().read(cx).read(cx);
+                    let (&excerpt_id, _, _) = multibuffer.as_singleton().unwrap();
+                    multibuffer
+                        .anchor_in_excerpt(excerpt_id, suggestion.range.start)
+                        .unwrap()
+                        ..multibuffer
+                            .anchor_in_excerpt(excerpt_id, suggestion.range.end)
+                            .unwrap()
+                };
+
+                InlineAssistant::update_global(cx, |assistant, cx| {
+                    let suggestion_id = assistant.suggest_assist(
+                        &editor,
+                        range,
+                        description,
+                        suggestion.initial_insertion,
+                        Some(workspace.clone()),
+                        assistant_panel.upgrade().as_ref(),
+                        cx,
+                    );
+                    assist_ids.push(suggestion_id);
+                });
+            }
+
+            // Scroll the editor to the suggested assist
+            editor.update(cx, |editor, cx| {
+                let multibuffer = editor.buffer().read(cx).snapshot(cx);
+                let (&excerpt_id, _, buffer) = multibuffer.as_singleton().unwrap();
+                let anchor = if suggestion_group.context_range.start.to_offset(buffer) == 0 {
+                    Anchor::min()
+                } else {
+                    multibuffer
+                        .anchor_in_excerpt(excerpt_id, suggestion_group.context_range.start)
+                        .unwrap()
+                };
+
+                editor.set_scroll_anchor(
+                    ScrollAnchor {
+                        offset: gpui::Point::default(),
+                        anchor,
+                    },
+                    cx,
+                );
+            });
+        })?;
+
+        editor
+    } else {
+        // If there are multiple buffers or suggestion groups, create a multibuffer
+        let mut inline_assist_suggestions = Vec::new();
+        let multibuffer = cx.new_model(|cx| {
+            let replica_id = project.read(cx).replica_id();
+            let mut multibuffer = MultiBuffer::new(replica_id, Capability::ReadWrite);
+            for (buffer, suggestion_groups) in edit_suggestions {
+                let excerpt_ids = multibuffer.push_excerpts(
+                    buffer,
+                    suggestion_groups
+                        .iter()
+                        .map(|suggestion_group| ExcerptRange {
+                            context: suggestion_group.context_range.clone(),
+                            primary: None,
+                        }),
+                    cx,
+                );
+
+                for (excerpt_id, suggestion_group) in excerpt_ids.into_iter().zip(suggestion_groups)
+                {
+                    for suggestion in suggestion_group.suggestions {
+                        let description = suggestion.description.unwrap_or_else(|| "Delete".into());
+                        let range = {
+                            let multibuffer = multibuffer.read(cx);
+                            multibuffer
+                                .anchor_in_excerpt(excerpt_id, suggestion.range.start)
+                                .unwrap()
+                                ..multibuffer
+                                    .anchor_in_excerpt(excerpt_id, suggestion.range.end)
+                                    .unwrap()
+                        };
+                        inline_assist_suggestions.push((
+                            range,
+                            description,
+                            suggestion.initial_insertion,
+                        ));
+                    }
+                }
+            }
+            multibuffer
+        })?;
+
+        let editor =
+            cx.new_view(|cx| Editor::for_multibuffer(multibuffer, Some(project), true, cx))?;
+        cx.update(|cx| {
+            InlineAssistant::update_global(cx, |assistant, cx| {
+                for (range, description, initial_insertion) in inline_assist_suggestions {
+                    assist_ids.push(assistant.suggest_assist(
+                        &editor,
+                        range,
+                        description,
+                        initial_insertion,
+                        Some(workspace.clone()),
+                        assistant_panel.upgrade().as_ref(),
+                        cx,
+                    ));
+                }
+            })
+        })?;
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, false, cx)
+        })?;
+
+        editor
+    };
+
+    Ok(Some((editor, assist_ids)))
+}
+
+/// The length after which the input of a tool call is cut in its block.
+const MAX_TOOL_INPUT_LEN: usize = 80;
+
+fn render_tool_use(
+    tool_use: &ToolUseAnchor,
+    context: Model<Context>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let id = tool_use.tool_use.id.clone();
+    let mut input = tool_use.tool_use.input.to_string();
+    if input.len() > MAX_TOOL_INPUT_LEN {
+        let mut end = MAX_TOOL_INPUT_LEN;
+        while !input.is_char_boundary(end) {
+            end -= 1;
+        }
+        input.truncate(end);
+        input.push('…');
+    }
+
+    let status = match &tool_use.status {
+        ToolUseStatus::PendingApproval => h_flex()
+            .gap_1()
+            .child(Button::new("allow-tool-use", "Allow").on_click({
+                let context = context.clone();
+                let id = id.clone();
+                move |_, cx| context.update(cx, |context, _| context.respond_to_tool_use(&id, true))
+            }))
+            .child(
+                Button::new("always-allow-tool-use", "Always Allow")
+                    .tooltip(|cx| Tooltip::text("Allow Until the Context Is Closed", cx))
+                    .on_click({
+                        let context = context.clone();
+                        let id = id.clone();
+                        move |_, cx| {
+                            context.update(cx, |context, _| context.approve_tool_for_session(&id))
+                        }
+                    }),
+            )
+            .child(Button::new("deny-tool-use", "Deny").on_click(move |_, cx| {
+                context.update(cx, |context, _| context.respond_to_tool_use(&id, false))
+            }))
+            .into_any_element(),
+        ToolUseStatus::Running => Label::new("Running…")
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+            .into_any_element(),
+        ToolUseStatus::Finished(result) => {
+            let (icon, color, label) = if result.is_error {
+                (IconName::XCircle, Color::Error, "Failed")
+            } else {
+                (IconName::Check, Color::Success, "Done")
+            };
+            h_flex()
+                .id("tool-use-result")
+                .gap_1()
+                .child(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(Label::new(label).size(LabelSize::Small).color(color))
+                .tooltip({
+                    let content = result.content.clone();
+                    move |cx| Tooltip::text(content.clone(), cx)
+                })
+                .into_any_element()
+        }
+    };
+
+    h_flex()
+        .id(cx.block_id)
+        .pl(cx.gutter_dimensions.full_width())
+        .h(cx.line_height * 2.)
+        .w_full()
+        .gap_2()
+        .child(
+            Icon::new(IconName::Code)
+                .size(IconSize::Small)
+                .color(Color::Muted),
+        )
+        .child(Label::new(tool_use.tool_use.name.clone()).size(LabelSize::Small))
+        .child(Label::new(input).size(LabelSize::Small).color(Color::Muted))
+        .child(status)
+        .into_any_element()
+}
+
 fn render_slash_command_output_toggle(
     row: MultiBufferRow,
     is_folded: bool,
@@ -3372,6 +3756,16 @@ fn make_lsp_adapter_delegate(
     })
 }
 
+/// Whether a file is an image that can be attached to a context, which excludes SVGs.
+fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map_or(false, |extension| {
+            let extension = extension.to_lowercase();
+            extension != "svg" && Img::extensions().contains(&extension.as_str())
+        })
+}
+
 fn slash_command_error_block_renderer(message: String) -> RenderBlock {
     Box::new(move |_| {
         div()
@@ -1,6 +1,6 @@
 use crate::{
-    prompt_library::PromptStore, slash_command::SlashCommandLine, InitialInsertion, MessageId,
-    MessageStatus,
+    prompt_library::PromptStore, slash_command::SlashCommandLine, tools::ContextTools,
+    InitialInsertion, MessageId, MessageStatus,
 };
 use anyhow::{anyhow, Context as _, Result};
 use assistant_slash_command::{
@@ -8,25 +8,32 @@ use assistant_slash_command::{
 };
 use client::{self, proto, telemetry::Telemetry};
 use clock::ReplicaId;
-use collections::{HashMap, HashSet};
+use collections::{BTreeMap, HashMap, HashSet};
 use fs::{Fs, RemoveOptions};
 use futures::{
+    channel::oneshot,
     future::{self, Shared},
     FutureExt, StreamExt,
 };
-use gpui::{AppContext, Context as _, EventEmitter, Model, ModelContext, Subscription, Task};
+use gpui::{
+    AppContext, Context as _, EventEmitter, ImageData, Model, ModelContext, Subscription, Task,
+};
+use image::{Frame, ImageFormat};
 use language::{
     AnchorRangeExt, Bias, Buffer, LanguageRegistry, OffsetRangeExt, ParseStatus, Point, ToOffset,
 };
 use language_model::{
-    LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, LanguageModelTool,
-    Role,
+    settings::AllLanguageModelSettings, LanguageModelCompletionEvent, LanguageModelId,
+    LanguageModelImage, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
+    LanguageModelTool, LanguageModelToolResult, LanguageModelToolUse, MessageContent, Role,
+    TokenUsage,
 };
 use open_ai::Model as OpenAiModel;
 use paths::contexts_dir;
 use project::Project;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use settings::Settings;
 use std::{
     cmp,
     fmt::Debug,
@@ -276,6 +283,8 @@ impl ContextOperation {
 #[derive(Debug, Clone)]
 pub enum ContextEvent {
     MessagesEdited,
+    ImagesChanged,
+    ToolUsesChanged,
     SummaryChanged,
     EditStepsChanged,
     StreamedCompletion,
@@ -322,14 +331,128 @@ pub struct Message {
 }
 
 impl Message {
-    fn to_request_message(&self, buffer: &Buffer) -> LanguageModelRequestMessage {
+    /// Converts the message into a request message, interleaving its text with the images
+    /// and the finished tool calls anchored within it.
+    fn to_request_message(
+        &self,
+        buffer: &Buffer,
+        image_anchors: &[ImageAnchor],
+        tool_uses: &[ToolUseAnchor],
+    ) -> LanguageModelRequestMessage {
+        // An attachment at the very end of the buffer belongs to the last message.
+        let in_message = |offset: usize| {
+            self.offset_range.contains(&offset)
+                || (offset == self.offset_range.end && offset == buffer.len())
+        };
+        let mut attachments = Vec::new();
+        for image_anchor in image_anchors {
+            let offset = image_anchor.anchor.to_offset(buffer);
+            if in_message(offset) {
+                attachments.push((
+                    offset,
+                    MessageContent::Image(image_anchor.image.image.clone()),
+                ));
+            }
+        }
+        // The calls made at the same position are answered together, after all of them.
+        let finished_tool_uses = tool_uses
+            .iter()
+            .filter_map(|tool_use| match &tool_use.status {
+                ToolUseStatus::Finished(result) => {
+                    Some((tool_use.anchor.to_offset(buffer), tool_use, result))
+                }
+                _ => None,
+            })
+            .filter(|(offset, _, _)| in_message(*offset))
+            .collect::<Vec<_>>();
+        for (offset, tool_use, _) in &finished_tool_uses {
+            attachments.push((*offset, MessageContent::ToolUse(tool_use.tool_use.clone())));
+        }
+        for (offset, _, result) in finished_tool_uses {
+            attachments.push((offset, MessageContent::ToolResult(result.clone())));
+        }
+        attachments.sort_by_key(|(offset, _)| *offset);
+
+        let mut content = Vec::new();
+        let mut offset = self.offset_range.start;
+        for (attachment_offset, attachment) in attachments {
+            if attachment_offset > offset {
+                content.push(MessageContent::Text(
+                    buffer.text_for_range(offset..attachment_offset).collect(),
+                ));
+            }
+            content.push(attachment);
+            offset = attachment_offset;
+        }
+        if offset < self.offset_range.end || content.is_empty() {
+            content.push(MessageContent::Text(
+                buffer
+                    .text_for_range(offset..self.offset_range.end)
+                    .collect(),
+            ));
+        }
+
         LanguageModelRequestMessage {
             role: self.role,
-            content: buffer.text_for_range(self.offset_range.clone()).collect(),
+            content,
+            cache: false,
+        }
+    }
+}
+
+/// An image attached to a context, along with its decoded pixels for display.
+#[derive(Clone)]
+pub struct ContextImage {
+    pub image: LanguageModelImage,
+    pub render_image: Arc<ImageData>,
+}
+
+impl ContextImage {
+    /// Creates an image from its encoded bytes, downscaling it to the size it's sent with.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::new(LanguageModelImage::from_bytes(bytes)?)
+    }
+
+    fn new(image: LanguageModelImage) -> Result<Self> {
+        let mut data =
+            image::load_from_memory_with_format(&image.to_bytes()?, ImageFormat::Png)?.into_rgba8();
+        // Convert from RGBA to BGRA.
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
         }
+        Ok(Self {
+            image,
+            render_image: Arc::new(ImageData::new(vec![Frame::new(data)])),
+        })
     }
 }
 
+/// An image positioned in the text of a context.
+#[derive(Clone)]
+pub struct ImageAnchor {
+    pub anchor: language::Anchor,
+    pub image: ContextImage,
+}
+
+/// A call the model made to one of the assistant's tools, positioned after the text that
+/// preceded it.
+#[derive(Clone, Debug)]
+pub struct ToolUseAnchor {
+    pub anchor: language::Anchor,
+    pub tool_use: LanguageModelToolUse,
+    pub status: ToolUseStatus,
+}
+
+#[derive(Clone, Debug)]
+pub enum ToolUseStatus {
+    PendingApproval,
+    Running,
+    Finished(LanguageModelToolResult),
+}
+
+/// The number of times the model can call tools in a row before it's stopped.
+const MAX_TOOL_ROUNDS: usize = 25;
+
 struct PendingCompletion {
     id: usize,
     _task: Task<()>,
@@ -367,7 +490,16 @@ impl EditStep {
         let Some(EditStepOperations::Ready(operations)) = &self.operations else {
             return Task::ready(HashMap::default());
         };
+        Self::edit_suggestions_for_operations(operations, project, cx)
+    }
 
+    /// Resolves edit operations to the ranges they apply to, grouped by buffer. Operations that
+    /// can't be resolved are skipped.
+    pub(crate) fn edit_suggestions_for_operations(
+        operations: &[EditOperation],
+        project: &Model<Project>,
+        cx: &AppContext,
+    ) -> Task<HashMap<Model<Buffer>, Vec<EditSuggestionGroup>>> {
         let suggestion_tasks: Vec<_> = operations
             .iter()
             .map(|operation| operation.edit_suggestion(project.clone(), cx))
@@ -739,12 +871,25 @@ pub struct Context {
     slash_command_output_sections: Vec<SlashCommandOutputSection<language::Anchor>>,
     message_anchors: Vec<MessageAnchor>,
     messages_metadata: HashMap<MessageId, MessageMetadata>,
+    /// The images of the context, ordered by their position. They're saved with the context
+    /// but, unlike its text, aren't shared with collaborators.
+    image_anchors: Vec<ImageAnchor>,
+    /// The calls the model made to tools, ordered by their position. Like images, they're
+    /// local to this replica.
+    tool_uses: Vec<ToolUseAnchor>,
+    pending_tool_approvals: HashMap<String, oneshot::Sender<bool>>,
+    /// The tools the user allowed to run without asking again, until the context is closed.
+    approved_tools: HashSet<String>,
     summary: Option<ContextSummary>,
     pending_summary: Task<Option<()>>,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
     token_count: Option<usize>,
     pending_token_count: Task<Option<()>>,
+    /// The tokens used by the last completion, for the providers that report them.
+    last_token_usage: Option<TokenUsage>,
+    /// The tokens spent by all the completions of the context, by model.
+    token_usage: BTreeMap<String, TokenUsage>,
     pending_save: Task<Result<()>>,
     path: Option<PathBuf>,
     _subscriptions: Vec<Subscription>,
@@ -799,6 +944,10 @@ impl Context {
             operations: Vec::new(),
             message_anchors: Default::default(),
             messages_metadata: Default::default(),
+            image_anchors: Vec::new(),
+            tool_uses: Vec::new(),
+            pending_tool_approvals: HashMap::default(),
+            approved_tools: HashSet::default(),
             pending_slash_commands: Vec::new(),
             finished_slash_commands: HashSet::default(),
             slash_command_output_sections: Vec::new(),
@@ -809,6 +958,8 @@ impl Context {
             pending_completions: Default::default(),
             token_count: None,
             pending_token_count: Task::ready(None),
+            last_token_usage: None,
+            token_usage: BTreeMap::default(),
             _subscriptions: vec![cx.subscribe(&buffer, Self::handle_buffer_event)],
             pending_save: Task::ready(Ok(())),
             path: None,
@@ -877,12 +1028,33 @@ impl Context {
                     }
                 })
                 .collect(),
+            images: self
+                .image_anchors
+                .iter()
+                .map(|image_anchor| SavedImage {
+                    offset: image_anchor.anchor.to_offset(buffer),
+                    image: image_anchor.image.image.clone(),
+                })
+                .collect(),
+            tool_uses: self
+                .tool_uses
+                .iter()
+                .filter_map(|tool_use| match &tool_use.status {
+                    ToolUseStatus::Finished(result) => Some(SavedToolUse {
+                        offset: tool_use.anchor.to_offset(buffer),
+                        tool_use: tool_use.tool_use.clone(),
+                        result: result.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            token_usage: self.token_usage.clone(),
         }
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn deserialize(
-        saved_context: SavedContext,
+        mut saved_context: SavedContext,
         path: PathBuf,
         language_registry: Arc<LanguageRegistry>,
         telemetry: Option<Arc<Telemetry>>,
@@ -901,8 +1073,26 @@ impl Context {
         this.buffer.update(cx, |buffer, cx| {
             buffer.set_text(saved_context.text.as_str(), cx)
         });
+        let images = mem::take(&mut saved_context.images);
+        let tool_uses = mem::take(&mut saved_context.tool_uses);
+        this.token_usage = mem::take(&mut saved_context.token_usage);
         let operations = saved_context.into_ops(&this.buffer, cx);
         this.apply_ops(operations, cx).unwrap();
+        for saved_image in images {
+            if let Some(image) = ContextImage::new(saved_image.image).log_err() {
+                let anchor = this.buffer.read(cx).anchor_before(saved_image.offset);
+                this.insert_image(image, anchor, cx);
+            }
+        }
+        for saved_tool_use in tool_uses {
+            let anchor = this.buffer.read(cx).anchor_before(saved_tool_use.offset);
+            this.insert_tool_use(
+                anchor,
+                saved_tool_use.tool_use,
+                ToolUseStatus::Finished(saved_tool_use.result),
+                cx,
+            );
+        }
         this
     }
 
@@ -1133,6 +1323,107 @@ impl Context {
         self.summary.as_ref()
     }
 
+    pub fn image_anchors(&self) -> &[ImageAnchor] {
+        &self.image_anchors
+    }
+
+    pub fn insert_image(
+        &mut self,
+        image: ContextImage,
+        anchor: language::Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx);
+        let ix = self
+            .image_anchors
+            .binary_search_by(|probe| probe.anchor.cmp(&anchor, buffer))
+            .unwrap_or_else(|ix| ix);
+        self.image_anchors.insert(ix, ImageAnchor { anchor, image });
+        cx.emit(ContextEvent::ImagesChanged);
+    }
+
+    pub fn remove_image(&mut self, anchor: language::Anchor, cx: &mut ModelContext<Self>) {
+        self.image_anchors
+            .retain(|image_anchor| image_anchor.anchor != anchor);
+        cx.emit(ContextEvent::ImagesChanged);
+    }
+
+    pub fn tool_uses(&self) -> &[ToolUseAnchor] {
+        &self.tool_uses
+    }
+
+    fn insert_tool_use(
+        &mut self,
+        anchor: language::Anchor,
+        tool_use: LanguageModelToolUse,
+        status: ToolUseStatus,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx);
+        // Calls made at the same position stay in the order they were made.
+        let ix = self
+            .tool_uses
+            .partition_point(|probe| probe.anchor.cmp(&anchor, buffer).is_le());
+        self.tool_uses.insert(
+            ix,
+            ToolUseAnchor {
+                anchor,
+                tool_use,
+                status,
+            },
+        );
+        cx.emit(ContextEvent::ToolUsesChanged);
+    }
+
+    fn set_tool_use_status(
+        &mut self,
+        tool_use_id: &str,
+        status: ToolUseStatus,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(tool_use) = self
+            .tool_uses
+            .iter_mut()
+            .find(|tool_use| tool_use.tool_use.id == tool_use_id)
+        {
+            tool_use.status = status;
+            cx.emit(ContextEvent::ToolUsesChanged);
+        }
+    }
+
+    /// Allows or denies a tool call that's waiting for the user's approval.
+    pub fn respond_to_tool_use(&mut self, tool_use_id: &str, approved: bool) {
+        if let Some(tx) = self.pending_tool_approvals.remove(tool_use_id) {
+            tx.send(approved).ok();
+        }
+    }
+
+    /// Allows a tool call, along with every other call to the same tool until the context
+    /// is closed.
+    pub fn approve_tool_for_session(&mut self, tool_use_id: &str) {
+        let Some(name) = self
+            .tool_uses
+            .iter()
+            .find(|tool_use| tool_use.tool_use.id == tool_use_id)
+            .map(|tool_use| tool_use.tool_use.name.clone())
+        else {
+            return;
+        };
+        let pending_ids = self
+            .tool_uses
+            .iter()
+            .filter(|tool_use| {
+                tool_use.tool_use.name == name
+                    && matches!(tool_use.status, ToolUseStatus::PendingApproval)
+            })
+            .map(|tool_use| tool_use.tool_use.id.clone())
+            .collect::<Vec<_>>();
+        for id in pending_ids {
+            self.respond_to_tool_use(&id, true);
+        }
+        self.approved_tools.insert(name);
+    }
+
     pub fn edit_steps(&self) -> &[EditStep] {
         &self.edit_steps
     }
@@ -1181,6 +1472,43 @@ impl Context {
         self.token_count
     }
 
+    pub(crate) fn last_token_usage(&self) -> Option<TokenUsage> {
+        self.last_token_usage
+    }
+
+    /// The tokens spent by the completions of the context, by the ID of the model that
+    /// produced them.
+    pub(crate) fn token_usage(&self) -> &BTreeMap<String, TokenUsage> {
+        &self.token_usage
+    }
+
+    pub(crate) fn total_token_usage(&self) -> TokenUsage {
+        self.token_usage
+            .values()
+            .fold(TokenUsage::default(), |total, usage| total + *usage)
+    }
+
+    /// The estimated cost of the context's completions, using the model prices from the
+    /// settings. Returns `None` when the price of a model that was used isn't known.
+    pub(crate) fn estimated_cost(&self, cx: &AppContext) -> Option<f64> {
+        let prices = &AllLanguageModelSettings::get_global(cx).model_prices;
+        self.token_usage
+            .iter()
+            .map(|(model_id, usage)| Some(prices.get(model_id)?.cost(usage)))
+            .sum()
+    }
+
+    fn record_token_usage(
+        &mut self,
+        model_id: &LanguageModelId,
+        usage: TokenUsage,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let total = self.token_usage.entry(model_id.0.to_string()).or_default();
+        *total = *total + usage;
+        cx.notify();
+    }
+
     pub(crate) fn count_remaining_tokens(&mut self, cx: &mut ModelContext<Self>) {
         let request = self.to_completion_request(cx);
         let Some(model) = LanguageModelRegistry::read_global(cx).active_model() else {
@@ -1391,7 +1719,8 @@ impl Context {
 
                 request.messages.push(LanguageModelRequestMessage {
                     role: Role::User,
-                    content: prompt,
+                    content: vec![prompt.into()],
+                    cache: false,
                 });
 
                 let tool_use = model.use_tool::<EditTool>(request, &cx).await?;
@@ -1601,39 +1930,28 @@ impl Context {
                 let stream_completion = async {
                     let request_start = Instant::now();
                     let mut chunks = stream.await?;
+                    let mut reported_usage = TokenUsage::default();
 
-                    while let Some(chunk) = chunks.next().await {
+                    while let Some(event) = chunks.next().await {
                         if response_latency.is_none() {
                             response_latency = Some(request_start.elapsed());
                         }
-                        let chunk = chunk?;
+                        let chunk = match event? {
+                            LanguageModelCompletionEvent::Text(chunk) => chunk,
+                            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                                // Each update reports the usage of the whole completion so far.
+                                let new_usage = usage - reported_usage;
+                                reported_usage = usage;
+                                this.update(&mut cx, |this, cx| {
+                                    this.last_token_usage = Some(usage);
+                                    this.record_token_usage(&model.id(), new_usage, cx);
+                                })?;
+                                continue;
+                            }
+                        };
 
                         this.update(&mut cx, |this, cx| {
-                            let message_ix = this
-                                .message_anchors
-                                .iter()
-                                .position(|message| message.id == assistant_message_id)?;
-                            let message_range = this.buffer.update(cx, |buffer, cx| {
-                                let message_start_offset =
-                                    this.message_anchors[message_ix].start.to_offset(buffer);
-                                let message_old_end_offset = this.message_anchors[message_ix + 1..]
-                                    .iter()
-                                    .find(|message| message.start.is_valid(buffer))
-                                    .map_or(buffer.len(), |message| {
-                                        message.start.to_offset(buffer).saturating_sub(1)
-                                    });
-                                let message_new_end_offset = message_old_end_offset + chunk.len();
-                                buffer.edit(
-                                    [(message_old_end_offset..message_old_end_offset, chunk)],
-                                    None,
-                                    cx,
-                                );
-                                message_start_offset..message_new_end_offset
-                            });
-                            this.parse_edit_steps_in_range(message_range, cx);
-                            cx.emit(ContextEvent::StreamedCompletion);
-
-                            Some(())
+                            this.append_to_message(assistant_message_id, chunk, cx);
                         })?;
                         smol::future::yield_now().await;
                     }
@@ -1685,11 +2003,263 @@ impl Context {
         Some(user_message)
     }
 
+    /// Appends text to the end of a message, returning the range the message now spans.
+    fn append_to_message(
+        &mut self,
+        message_id: MessageId,
+        text: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<Range<usize>> {
+        let message_ix = self
+            .message_anchors
+            .iter()
+            .position(|message| message.id == message_id)?;
+        let message_range = self.buffer.update(cx, |buffer, cx| {
+            let message_start_offset = self.message_anchors[message_ix].start.to_offset(buffer);
+            let message_old_end_offset = self.message_anchors[message_ix + 1..]
+                .iter()
+                .find(|message| message.start.is_valid(buffer))
+                .map_or(buffer.len(), |message| {
+                    message.start.to_offset(buffer).saturating_sub(1)
+                });
+            let message_new_end_offset = message_old_end_offset + text.len();
+            buffer.edit(
+                [(message_old_end_offset..message_old_end_offset, text)],
+                None,
+                cx,
+            );
+            message_start_offset..message_new_end_offset
+        });
+        self.parse_edit_steps_in_range(message_range.clone(), cx);
+        cx.emit(ContextEvent::StreamedCompletion);
+        Some(message_range)
+    }
+
+    /// Requests a response from a model that can call the assistant's tools. The calls it
+    /// makes are run, once the user approves them for the tools that need it, and their
+    /// results are sent back until the model responds without calling any.
+    pub(crate) fn assist_with_tools(
+        &mut self,
+        tools: ContextTools,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<MessageAnchor> {
+        let provider = LanguageModelRegistry::read_global(cx).active_provider()?;
+        let model = LanguageModelRegistry::read_global(cx).active_model()?;
+        let last_message_id = self.message_anchors.iter().rev().find_map(|message| {
+            message
+                .start
+                .is_valid(self.buffer.read(cx))
+                .then_some(message.id)
+        })?;
+
+        if !provider.is_authenticated(cx) {
+            log::info!("completion provider has no credentials");
+            return None;
+        }
+
+        let mut request = self.to_completion_request(cx);
+        let assistant_message = self
+            .insert_message_after(last_message_id, Role::Assistant, MessageStatus::Pending, cx)
+            .unwrap();
+
+        // Queue up the user's next reply.
+        let user_message = self
+            .insert_message_after(assistant_message.id, Role::User, MessageStatus::Done, cx)
+            .unwrap();
+
+        let task = cx.spawn({
+            |this, mut cx| async move {
+                let assistant_message_id = assistant_message.id;
+                let mut response_latency = None;
+                let use_tools = async {
+                    let request_start = Instant::now();
+                    for round in 0..MAX_TOOL_ROUNDS {
+                        let response = model.use_tools(request, tools.definitions(), &cx).await?;
+                        response_latency.get_or_insert_with(|| request_start.elapsed());
+
+                        let tool_uses = this.update(&mut cx, |this, cx| {
+                            if let Some(usage) = response.usage {
+                                this.last_token_usage = Some(usage);
+                                this.record_token_usage(&model.id(), usage, cx);
+                            }
+                            let mut text = response.text;
+                            if round > 0 && !text.is_empty() {
+                                text.insert_str(0, "\n\n");
+                            }
+                            let Some(message_range) =
+                                this.append_to_message(assistant_message_id, text, cx)
+                            else {
+                                return Vec::new();
+                            };
+                            let anchor = this.buffer.read(cx).anchor_before(message_range.end);
+
+                            let mut tool_uses = Vec::new();
+                            for tool_use in response.tool_uses {
+                                let approval = if tools.needs_approval(&tool_use.name)
+                                    && !this.approved_tools.contains(&tool_use.name)
+                                {
+                                    let (tx, rx) = oneshot::channel();
+                                    this.pending_tool_approvals.insert(tool_use.id.clone(), tx);
+                                    Some(rx)
+                                } else {
+                                    None
+                                };
+                                let status = if approval.is_some() {
+                                    ToolUseStatus::PendingApproval
+                                } else {
+                                    ToolUseStatus::Running
+                                };
+                                this.insert_tool_use(anchor, tool_use.clone(), status, cx);
+                                tool_uses.push((tool_use, approval));
+                            }
+                            tool_uses
+                        })?;
+                        if tool_uses.is_empty() {
+                            return anyhow::Ok(());
+                        }
+
+                        // The calls are run one at a time, as they may depend on each other.
+                        for (tool_use, approval) in tool_uses {
+                            let approved = match approval {
+                                // The approval is dropped when the context is closed.
+                                Some(approval) => approval.await.unwrap_or(false),
+                                None => true,
+                            };
+                            let result = if approved {
+                                this.update(&mut cx, |this, cx| {
+                                    this.set_tool_use_status(
+                                        &tool_use.id,
+                                        ToolUseStatus::Running,
+                                        cx,
+                                    )
+                                })?;
+                                match tools.run(&tool_use, &mut cx).await {
+                                    Ok(content) => LanguageModelToolResult {
+                                        tool_use_id: tool_use.id.clone(),
+                                        content,
+                                        is_error: false,
+                                    },
+                                    Err(error) => LanguageModelToolResult {
+                                        tool_use_id: tool_use.id.clone(),
+                                        content: format!("{error:#}"),
+                                        is_error: true,
+                                    },
+                                }
+                            } else {
+                                LanguageModelToolResult {
+                                    tool_use_id: tool_use.id.clone(),
+                                    content: "The user denied this call.".into(),
+                                    is_error: true,
+                                }
+                            };
+                            this.update(&mut cx, |this, cx| {
+                                this.set_tool_use_status(
+                                    &tool_use.id,
+                                    ToolUseStatus::Finished(result),
+                                    cx,
+                                )
+                            })?;
+                        }
+
+                        request = this.update(&mut cx, |this, cx| {
+                            this.to_tool_use_request(assistant_message_id, cx)
+                        })?;
+                    }
+
+                    Err(anyhow!(
+                        "stopped after {MAX_TOOL_ROUNDS} rounds of tool calls"
+                    ))
+                };
+
+                let result = use_tools.await;
+
+                this.update(&mut cx, |this, cx| {
+                    let error_message = result
+                        .err()
+                        .map(|error| error.to_string().trim().to_string());
+
+                    this.update_metadata(assistant_message_id, cx, |metadata| {
+                        if let Some(error_message) = error_message.as_ref() {
+                            metadata.status =
+                                MessageStatus::Error(SharedString::from(error_message.clone()));
+                        } else {
+                            metadata.status = MessageStatus::Done;
+                        }
+                    });
+                    this.pending_completions
+                        .retain(|completion| completion.id != this.completion_count);
+                    this.summarize(false, cx);
+
+                    if let Some(telemetry) = this.telemetry.as_ref() {
+                        telemetry.report_assistant_event(
+                            Some(this.id.0.clone()),
+                            AssistantKind::Panel,
+                            model.telemetry_id(),
+                            response_latency,
+                            error_message,
+                        );
+                    }
+                })
+                .ok();
+            }
+        });
+
+        self.pending_completions.push(PendingCompletion {
+            id: post_inc(&mut self.completion_count),
+            _task: task,
+        });
+
+        Some(user_message)
+    }
+
     pub fn to_completion_request(&self, cx: &AppContext) -> LanguageModelRequest {
-        let messages = self
-            .messages(cx)
-            .filter(|message| matches!(message.status, MessageStatus::Done))
-            .map(|message| message.to_request_message(self.buffer.read(cx)));
+        self.completion_request_for_messages(
+            self.messages(cx)
+                .filter(|message| matches!(message.status, MessageStatus::Done)),
+            cx,
+        )
+    }
+
+    /// Builds the request continuing an assistant message that's calling tools, which
+    /// includes the message itself but not the messages queued after it.
+    fn to_tool_use_request(
+        &self,
+        assistant_message_id: MessageId,
+        cx: &AppContext,
+    ) -> LanguageModelRequest {
+        let mut messages = Vec::new();
+        for message in self.messages(cx) {
+            if message.id == assistant_message_id {
+                messages.push(message);
+                break;
+            } else if matches!(message.status, MessageStatus::Done) {
+                messages.push(message);
+            }
+        }
+        self.completion_request_for_messages(messages.into_iter(), cx)
+    }
+
+    fn completion_request_for_messages(
+        &self,
+        messages: impl Iterator<Item = Message>,
+        cx: &AppContext,
+    ) -> LanguageModelRequest {
+        let buffer = self.buffer.read(cx);
+        // Slash command output, such as whole files, tends to be resent unchanged with every
+        // follow-up message, so the messages ending a section are marked as cacheable.
+        let section_ends = self
+            .slash_command_output_sections
+            .iter()
+            .map(|section| section.range.end.to_offset(buffer))
+            .collect::<Vec<_>>();
+        let messages = messages.map(|message| {
+            let mut request_message =
+                message.to_request_message(buffer, &self.image_anchors, &self.tool_uses);
+            request_message.cache = section_ends
+                .iter()
+                .any(|end| message.offset_range.start < *end && *end <= message.offset_range.end);
+            request_message
+        });
 
         LanguageModelRequest {
             messages: messages.collect(),
@@ -1698,8 +2268,26 @@ impl Context {
         }
     }
 
-    pub fn cancel_last_assist(&mut self) -> bool {
-        self.pending_completions.pop().is_some()
+    pub fn cancel_last_assist(&mut self, cx: &mut ModelContext<Self>) -> bool {
+        if self.pending_completions.pop().is_none() {
+            return false;
+        }
+        // Calls that can no longer finish are answered, so that the model can be asked
+        // to continue.
+        if self.pending_completions.is_empty() {
+            self.pending_tool_approvals.clear();
+            for tool_use in &mut self.tool_uses {
+                if !matches!(tool_use.status, ToolUseStatus::Finished(_)) {
+                    tool_use.status = ToolUseStatus::Finished(LanguageModelToolResult {
+                        tool_use_id: tool_use.tool_use.id.clone(),
+                        content: "The call was cancelled.".into(),
+                        is_error: true,
+                    });
+                }
+            }
+            cx.emit(ContextEvent::ToolUsesChanged);
+        }
+        true
     }
 
     pub fn cycle_message_roles(&mut self, ids: HashSet<MessageId>, cx: &mut ModelContext<Self>) {
@@ -1947,10 +2535,19 @@ impl Context {
 
             let messages = self
                 .messages(cx)
-                .map(|message| message.to_request_message(self.buffer.read(cx)))
+                .map(|message| {
+                    message.to_request_message(
+                        self.buffer.read(cx),
+                        &self.image_anchors,
+                        &self.tool_uses,
+                    )
+                })
                 .chain(Some(LanguageModelRequestMessage {
                     role: Role::User,
-                    content: "Summarize the context into a short title without punctuation.".into(),
+                    content: vec![
+                        "Summarize the context into a short title without punctuation.".into(),
+                    ],
+                    cache: false,
                 }));
             let request = LanguageModelRequest {
                 messages: messages.collect(),
@@ -1961,11 +2558,22 @@ impl Context {
             self.pending_summary = cx.spawn(|this, mut cx| {
                 async move {
                     let stream = model.stream_completion(request, &cx);
-                    let mut messages = stream.await?;
+                    let mut events = stream.await?;
 
                     let mut replaced = !replace_old;
-                    while let Some(message) = messages.next().await {
-                        let text = message?;
+                    let mut reported_usage = TokenUsage::default();
+                    while let Some(event) = events.next().await {
+                        let text = match event? {
+                            LanguageModelCompletionEvent::Text(text) => text,
+                            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                                let new_usage = usage - reported_usage;
+                                reported_usage = usage;
+                                this.update(&mut cx, |this, cx| {
+                                    this.record_token_usage(&model.id(), new_usage, cx)
+                                })?;
+                                continue;
+                            }
+                        };
                         let mut lines = text.lines();
                         this.update(&mut cx, |this, cx| {
                             let version = this.version.clone();
@@ -2220,6 +2828,27 @@ pub struct SavedContext {
     pub summary: String,
     pub slash_command_output_sections:
         Vec<assistant_slash_command::SlashCommandOutputSection<usize>>,
+    #[serde(default)]
+    pub images: Vec<SavedImage>,
+    #[serde(default)]
+    pub tool_uses: Vec<SavedToolUse>,
+    /// The tokens spent by the context's completions, by model.
+    #[serde(default)]
+    pub token_usage: BTreeMap<String, TokenUsage>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedImage {
+    pub offset: usize,
+    pub image: LanguageModelImage,
+}
+
+/// A finished tool call. Calls that were still pending when the context was saved aren't kept.
+#[derive(Serialize, Deserialize)]
+pub struct SavedToolUse {
+    pub offset: usize,
+    pub tool_use: LanguageModelToolUse,
+    pub result: LanguageModelToolResult,
 }
 
 impl SavedContext {
@@ -2395,6 +3024,9 @@ impl SavedContextV0_3_0 {
                 .collect(),
             summary: self.summary,
             slash_command_output_sections: self.slash_command_output_sections,
+            images: Vec::new(),
+            tool_uses: Vec::new(),
+            token_usage: BTreeMap::default(),
         }
     }
 }
@@ -2478,6 +3110,7 @@ mod tests {
     use gpui::{AppContext, TestAppContext, WeakView};
     use indoc::indoc;
     use language::LspAdapterDelegate;
+    use language_model::settings::ModelPrice;
     use parking_lot::Mutex;
     use project::Project;
     use rand::prelude::*;
@@ -2799,6 +3432,194 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_images(cx: &mut AppContext) {
+        let settings_store = SettingsStore::test(cx);
+        language_model::LanguageModelRegistry::test(cx);
+        cx.set_global(settings_store);
+        assistant_panel::init(cx);
+        let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+
+        let context = cx.new_model(|cx| Context::local(registry.clone(), None, cx));
+        let buffer = context.read(cx).buffer.clone();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "before\nafter\n")], None, cx)
+        });
+
+        let mut png = Vec::new();
+        image::RgbaImage::new(4, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let image = ContextImage::from_bytes(&png).unwrap();
+        assert_eq!((image.image.width, image.image.height), (4, 2));
+        context.update(cx, |context, cx| {
+            let anchor = context.buffer.read(cx).anchor_before(7);
+            context.insert_image(image.clone(), anchor, cx);
+        });
+
+        // The image is sent at its position in the text, which can change afterwards.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "> ")], None, cx));
+        let expected_content = vec![
+            MessageContent::Text("> before\n".into()),
+            MessageContent::Image(image.image.clone()),
+            MessageContent::Text("after\n".into()),
+        ];
+        let request = context.read(cx).to_completion_request(cx);
+        assert_eq!(request.messages.len(), 1);
+        assert_eq!(request.messages[0].content, expected_content);
+
+        // Images are saved with the context.
+        let serialized_context = context.read(cx).serialize(cx);
+        assert_eq!(serialized_context.images.len(), 1);
+        assert_eq!(serialized_context.images[0].offset, 9);
+        let deserialized_context = cx.new_model(|cx| {
+            Context::deserialize(serialized_context, Default::default(), registry, None, cx)
+        });
+        let request = deserialized_context.read(cx).to_completion_request(cx);
+        assert_eq!(request.messages[0].content, expected_content);
+
+        context.update(cx, |context, cx| {
+            let anchor = context.image_anchors()[0].anchor;
+            context.remove_image(anchor, cx);
+        });
+        let request = context.read(cx).to_completion_request(cx);
+        assert_eq!(
+            request.messages[0].content,
+            vec![MessageContent::Text("> before\nafter\n".into())]
+        );
+    }
+
+    #[gpui::test]
+    fn test_tool_uses(cx: &mut AppContext) {
+        let settings_store = SettingsStore::test(cx);
+        language_model::LanguageModelRegistry::test(cx);
+        cx.set_global(settings_store);
+        assistant_panel::init(cx);
+        let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+
+        let context = cx.new_model(|cx| Context::local(registry.clone(), None, cx));
+        let buffer = context.read(cx).buffer.clone();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "Reading.\nDone.\n")], None, cx)
+        });
+
+        let tool_use = |id: &str| LanguageModelToolUse {
+            id: id.into(),
+            name: "read_file".into(),
+            input: json!({ "path": id }),
+        };
+        let result = |id: &str| LanguageModelToolResult {
+            tool_use_id: id.into(),
+            content: format!("content of {id}"),
+            is_error: false,
+        };
+        context.update(cx, |context, cx| {
+            let anchor = context.buffer.read(cx).anchor_before(8);
+            for id in ["a", "b"] {
+                context.insert_tool_use(
+                    anchor,
+                    tool_use(id),
+                    ToolUseStatus::Finished(result(id)),
+                    cx,
+                );
+            }
+            context.insert_tool_use(anchor, tool_use("c"), ToolUseStatus::Running, cx);
+        });
+
+        // The calls made together are followed by their results, and the unfinished calls
+        // aren't sent.
+        let expected_content = vec![
+            MessageContent::Text("Reading.".into()),
+            MessageContent::ToolUse(tool_use("a")),
+            MessageContent::ToolUse(tool_use("b")),
+            MessageContent::ToolResult(result("a")),
+            MessageContent::ToolResult(result("b")),
+            MessageContent::Text("\nDone.\n".into()),
+        ];
+        let request = context.read(cx).to_completion_request(cx);
+        assert_eq!(request.messages.len(), 1);
+        assert_eq!(request.messages[0].content, expected_content);
+
+        // Only the finished calls are saved with the context.
+        let serialized_context = context.read(cx).serialize(cx);
+        assert_eq!(serialized_context.tool_uses.len(), 2);
+        let deserialized_context = cx.new_model(|cx| {
+            Context::deserialize(serialized_context, Default::default(), registry, None, cx)
+        });
+        let request = deserialized_context.read(cx).to_completion_request(cx);
+        assert_eq!(request.messages[0].content, expected_content);
+
+        // Cancelling without a pending completion leaves the calls as they are.
+        context.update(cx, |context, cx| assert!(!context.cancel_last_assist(cx)));
+        assert!(matches!(
+            context.read(cx).tool_uses()[2].status,
+            ToolUseStatus::Running
+        ));
+    }
+
+    #[gpui::test]
+    fn test_token_usage(cx: &mut AppContext) {
+        let settings_store = SettingsStore::test(cx);
+        language_model::LanguageModelRegistry::test(cx);
+        cx.set_global(settings_store);
+        AllLanguageModelSettings::register(cx);
+        assistant_panel::init(cx);
+        let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+
+        let usage = |input_tokens, output_tokens| TokenUsage {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        };
+        let context = cx.new_model(|cx| Context::local(registry.clone(), None, cx));
+        context.update(cx, |context, cx| {
+            let model_a = LanguageModelId("model-a".into());
+            let model_b = LanguageModelId("model-b".into());
+            context.record_token_usage(&model_a, usage(1_000, 100), cx);
+            context.record_token_usage(&model_b, usage(2_000, 200), cx);
+            context.record_token_usage(&model_a, usage(500, 50), cx);
+        });
+        assert_eq!(context.read(cx).token_usage()["model-a"], usage(1_500, 150));
+        assert_eq!(context.read(cx).total_token_usage(), usage(3_500, 350));
+
+        // The cost is only estimated once every model that was used has a price.
+        let set_prices = |prices: &[(&str, f64, f64)], cx: &mut AppContext| {
+            let prices = prices
+                .iter()
+                .map(|(model_id, input, output)| {
+                    let price = ModelPrice {
+                        input_per_million_tokens: *input,
+                        output_per_million_tokens: *output,
+                        cache_write_per_million_tokens: None,
+                        cache_read_per_million_tokens: None,
+                    };
+                    (model_id.to_string(), price)
+                })
+                .collect();
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<AllLanguageModelSettings>(cx, |settings| {
+                    settings.model_prices = Some(prices);
+                });
+            });
+        };
+        assert_eq!(context.read(cx).estimated_cost(cx), None);
+        set_prices(&[("model-a", 1., 2.)], cx);
+        assert_eq!(context.read(cx).estimated_cost(cx), None);
+        set_prices(&[("model-a", 1., 2.), ("model-b", 3., 15.)], cx);
+        let cost = context.read(cx).estimated_cost(cx).unwrap();
+        assert!((cost - 0.0108).abs() < 1e-9, "unexpected cost {cost}");
+
+        // The usage is saved with the context.
+        let serialized_context = context.read(cx).serialize(cx);
+        let deserialized_context = cx.new_model(|cx| {
+            Context::deserialize(serialized_context, Default::default(), registry, None, cx)
+        });
+        assert_eq!(
+            deserialized_context.read(cx).token_usage(),
+            context.read(cx).token_usage()
+        );
+    }
+
     #[gpui::test]
     async fn test_slash_commands(cx: &mut TestAppContext) {
         let settings_store = cx.update(SettingsStore::test);
@@ -85,7 +85,10 @@ impl ContextStore {
     pub fn new(project: Model<Project>, cx: &mut AppContext) -> Task<Result<Model<Self>>> {
         let fs = project.read(cx).fs().clone();
         let languages = project.read(cx).languages().clone();
-        let telemetry = project.read(cx).client().telemetry().clone();
+        let Some(client) = project.read(cx).client() else {
+            return Task::ready(Err(anyhow!("project has no client")));
+        };
+        let telemetry = client.telemetry().clone();
         cx.spawn(|mut cx| async move {
             const CONTEXT_WATCH_DURATION: Duration = Duration::from_millis(100);
             let (mut events, _) = fs.watch(contexts_dir(), CONTEXT_WATCH_DURATION).await;
@@ -115,7 +118,7 @@ impl ContextStore {
                         cx.subscribe(&project, Self::handle_project_event),
                     ],
                     project_is_shared: false,
-                    client: project.read(cx).client(),
+                    client,
                     project: project.clone(),
                 };
                 this.handle_project_changed(project, cx);
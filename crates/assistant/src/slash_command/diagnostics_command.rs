@@ -227,9 +227,9 @@ impl SlashCommand for DiagnosticsSlashCommand {
 }
 
 #[derive(Default)]
-struct Options {
-    include_warnings: bool,
-    path_matcher: Option<PathMatcher>,
+pub(crate) struct Options {
+    pub(crate) include_warnings: bool,
+    pub(crate) path_matcher: Option<PathMatcher>,
 }
 
 const INCLUDE_WARNINGS_ARGUMENT: &str = "--include-warnings";
@@ -264,7 +264,7 @@ impl Options {
     }
 }
 
-fn collect_diagnostics(
+pub(crate) fn collect_diagnostics(
     project: Model<Project>,
     options: Options,
     cx: &mut AppContext,
@@ -0,0 +1,385 @@
+use super::{create_label_for_command, SlashCommand, SlashCommandOutput};
+use anyhow::{anyhow, Context as _, Result};
+use assistant_slash_command::{ArgumentCompletion, SlashCommandOutputSection};
+use fuzzy::StringMatchCandidate;
+use gpui::{AppContext, Task, WeakView};
+use language::{CodeLabel, LspAdapterDelegate};
+use std::{
+    fmt::Write,
+    sync::{atomic::AtomicBool, Arc},
+};
+use ui::prelude::*;
+use workspace::Workspace;
+
+pub(crate) struct DiffSlashCommand;
+
+const STAGED_ARGUMENT: &str = "--staged";
+
+/// The number of bytes of diff after which the diffs of the remaining files are left out,
+/// so that large changes don't fill the context.
+const MAX_DIFF_LEN: usize = 64 * 1024;
+
+impl SlashCommand for DiffSlashCommand {
+    fn name(&self) -> String {
+        "diff".into()
+    }
+
+    fn label(&self, cx: &AppContext) -> CodeLabel {
+        create_label_for_command("diff", &[STAGED_ARGUMENT, "[branch]"], cx)
+    }
+
+    fn description(&self) -> String {
+        "insert the git diff of the project".into()
+    }
+
+    fn menu_text(&self) -> String {
+        "Insert Git Diff".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        false
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        query: String,
+        cancellation_flag: Arc<AtomicBool>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Vec<ArgumentCompletion>>> {
+        let Some(workspace) = workspace.and_then(|workspace| workspace.upgrade()) else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let repo = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .active_repository(cx)
+            .map(|(repo, _)| repo);
+        // The completion replaces the whole argument, so it keeps the arguments before the
+        // one being completed.
+        let (previous_arguments, query) = match query.rsplit_once(char::is_whitespace) {
+            Some((previous_arguments, query)) => {
+                (format!("{previous_arguments} "), query.to_string())
+            }
+            None => (String::new(), query),
+        };
+        let options = Options::parse(Some(&previous_arguments));
+
+        let executor = cx.background_executor().clone();
+        cx.background_executor().spawn(async move {
+            let mut candidates = Vec::new();
+            if !options.staged {
+                candidates.push(STAGED_ARGUMENT.to_string());
+            }
+            if let (Some(repo), None) = (repo, &options.branch) {
+                candidates.extend(
+                    repo.branches()?
+                        .into_iter()
+                        .filter(|branch| !branch.is_head)
+                        .map(|branch| branch.name.to_string()),
+                );
+            }
+            let candidates = candidates
+                .into_iter()
+                .enumerate()
+                .map(|(id, candidate)| StringMatchCandidate::new(id, candidate))
+                .collect::<Vec<_>>();
+
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                100,
+                &cancellation_flag,
+                executor,
+            )
+            .await;
+            Ok(matches
+                .into_iter()
+                .map(|candidate| ArgumentCompletion {
+                    new_text: format!("{previous_arguments}{}", candidate.string),
+                    // A branch can still follow the staged flag.
+                    run_command: candidate.string != STAGED_ARGUMENT,
+                    label: candidate.string,
+                })
+                .collect())
+        })
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let Some((repo, _)) = workspace.read(cx).project().read(cx).active_repository(cx) else {
+            return Task::ready(Err(anyhow!("no git repository found in the project")));
+        };
+        let options = Options::parse(argument);
+
+        cx.background_executor().spawn(async move {
+            // Compare with where the branch diverged, so that the changes made on the other
+            // branch since then don't show up as reverted.
+            let base = match &options.branch {
+                Some(branch) => Some(
+                    repo.merge_base(branch)
+                        .with_context(|| format!("failed to find the merge base with {branch}"))?,
+                ),
+                None => None,
+            };
+            let diff = repo
+                .diff(options.staged, base.as_deref())
+                .context("failed to compute the diff")?;
+            if diff.trim().is_empty() {
+                return Err(anyhow!("no changes found"));
+            }
+            Ok(build_diff_output(&diff, &options, MAX_DIFF_LEN))
+        })
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    staged: bool,
+    branch: Option<String>,
+}
+
+impl Options {
+    fn parse(arguments_line: Option<&str>) -> Self {
+        let mut options = Self::default();
+        for arg in arguments_line.unwrap_or_default().split_whitespace() {
+            if arg == STAGED_ARGUMENT {
+                options.staged = true;
+            } else {
+                options.branch = Some(arg.to_string());
+            }
+        }
+        options
+    }
+
+    fn title(&self) -> String {
+        let changes = if self.staged {
+            "Staged changes"
+        } else {
+            "Changes"
+        };
+        match &self.branch {
+            Some(branch) => format!("{changes} since {branch}"),
+            None => changes.to_string(),
+        }
+    }
+}
+
+/// Inserts the diff of each file in its own fenced block, which is folded under the path
+/// of the file, and all of them under a section for the whole diff. Once the diffs exceed
+/// `max_len` bytes, the one that crosses it is cut at a line and the remaining files are
+/// only listed.
+fn build_diff_output(diff: &str, options: &Options, max_len: usize) -> SlashCommandOutput {
+    let title = options.title();
+    let mut text = format!("{title}:\n");
+    let mut sections = Vec::new();
+    let mut omitted_paths = Vec::new();
+    let mut remaining_len = max_len;
+    for file_diff in split_diff_by_file(diff) {
+        if remaining_len == 0 {
+            omitted_paths.push(file_diff.path);
+            continue;
+        }
+        let mut file_text = file_diff.text;
+        if file_text.len() > remaining_len {
+            let mut cut = remaining_len;
+            while !file_text.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let end = file_text[..cut].rfind('\n').map_or(0, |ix| ix + 1);
+            file_text = &file_text[..end];
+            remaining_len = 0;
+            if file_text.is_empty() {
+                omitted_paths.push(file_diff.path);
+                continue;
+            }
+        } else {
+            remaining_len -= file_text.len();
+        }
+
+        let start = text.len();
+        text.push_str("```diff\n");
+        text.push_str(file_text);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        if file_text.len() < file_diff.text.len() {
+            text.push_str("[…]\n");
+        }
+        text.push_str("```\n");
+        sections.push(SlashCommandOutputSection {
+            range: start..text.len() - 1,
+            icon: IconName::FileGit,
+            label: file_diff.path.to_string().into(),
+        });
+    }
+    if !omitted_paths.is_empty() {
+        writeln!(
+            text,
+            "The diff was cut short. These files also changed: {}",
+            omitted_paths.join(", ")
+        )
+        .unwrap();
+    }
+    sections.push(SlashCommandOutputSection {
+        range: 0..text.len() - 1,
+        icon: IconName::FileGit,
+        label: title.into(),
+    });
+
+    SlashCommandOutput {
+        text,
+        sections,
+        run_commands_in_text: false,
+    }
+}
+
+struct FileDiff<'a> {
+    path: &'a str,
+    text: &'a str,
+}
+
+/// Splits a unified diff into the parts for each file, which start with a `diff --git`
+/// header naming the file.
+fn split_diff_by_file(diff: &str) -> Vec<FileDiff> {
+    const HEADER: &str = "diff --git ";
+
+    let mut starts = diff
+        .match_indices(HEADER)
+        .map(|(ix, _)| ix)
+        .filter(|ix| *ix == 0 || diff.as_bytes()[ix - 1] == b'\n')
+        .collect::<Vec<_>>();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(diff.len());
+            let text = &diff[*start..end];
+            let header = text.lines().next().unwrap_or_default();
+            let path = header
+                .strip_prefix(HEADER)
+                .and_then(|paths| paths.rsplit_once(" b/"))
+                .map_or(header, |(_, path)| path);
+            FileDiff { path, text }
+        })
+        .filter(|file_diff| !file_diff.text.trim().is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_build_diff_output() {
+        let diff = indoc! {"
+            diff --git a/src/main.rs b/src/main.rs
+            index 1111111..2222222 100644
+            --- a/src/main.rs
+            +++ b/src/main.rs
+            @@ -1 +1 @@
+            -fn main() {}
+            +fn main() { run(); }
+            diff --git a/README.md b/README.md
+            new file mode 100644
+            --- /dev/null
+            +++ b/README.md
+            @@ -0,0 +1 @@
+            +diff --git is not a header here
+        "};
+        let options = Options::parse(Some("--staged main"));
+        let output = build_diff_output(diff, &options, MAX_DIFF_LEN);
+
+        let labels = output
+            .sections
+            .iter()
+            .map(|section| section.label.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            ["src/main.rs", "README.md", "Staged changes since main"]
+        );
+        assert_eq!(
+            &output.text[output.sections[1].range.clone()],
+            indoc! {"
+                ```diff
+                diff --git a/README.md b/README.md
+                new file mode 100644
+                --- /dev/null
+                +++ b/README.md
+                @@ -0,0 +1 @@
+                +diff --git is not a header here
+                ```"}
+        );
+        assert!(output
+            .text
+            .starts_with("Staged changes since main:\n```diff\n"));
+
+        // Past the maximum length, the file that crosses it is cut at a line and the
+        // others are only listed.
+        let output = build_diff_output(diff, &Options::default(), 100);
+        assert_eq!(
+            output.text,
+            indoc! {"
+                Changes:
+                ```diff
+                diff --git a/src/main.rs b/src/main.rs
+                index 1111111..2222222 100644
+                --- a/src/main.rs
+                […]
+                ```
+                The diff was cut short. These files also changed: README.md
+            "}
+        );
+        assert_eq!(output.sections.len(), 2);
+    }
+
+    #[test]
+    fn test_build_diff_output_cut_inside_a_character() {
+        let diff = indoc! {"
+            diff --git a/README.md b/README.md
+            --- a/README.md
+            +++ b/README.md
+            @@ -1 +1 @@
+            -Привет
+            +Здравствуйте
+        "};
+
+        let max_len = diff.find("Привет").unwrap() + 1;
+        assert!(!diff.is_char_boundary(max_len));
+        let output = build_diff_output(diff, &Options::default(), max_len);
+        assert_eq!(
+            output.text,
+            indoc! {"
+                Changes:
+                ```diff
+                diff --git a/README.md b/README.md
+                --- a/README.md
+                +++ b/README.md
+                @@ -1 +1 @@
+                […]
+                ```
+            "}
+        );
+
+        // Whichever byte the maximum length falls on, the diff is cut without panicking.
+        for max_len in 0..=diff.len() {
+            let output = build_diff_output(diff, &Options::default(), max_len);
+            assert!(output.text.starts_with("Changes:\n"));
+        }
+    }
+}
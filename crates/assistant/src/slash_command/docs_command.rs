@@ -82,7 +82,11 @@ impl DocsSlashCommand {
                     .upgrade()
                     .ok_or_else(|| anyhow!("workspace was dropped"))?;
                 let project = workspace.read(cx).project().clone();
-                anyhow::Ok(project.read(cx).client().http_client().clone())
+                let client = project
+                    .read(cx)
+                    .client()
+                    .ok_or_else(|| anyhow!("project has no client"))?;
+                anyhow::Ok(client.http_client())
             });
 
             if let Some(http_client) = http_client.log_err() {
@@ -0,0 +1,113 @@
+use super::{create_label_for_command, SlashCommand, SlashCommandOutput};
+use anyhow::{anyhow, Context as _, Result};
+use assistant_slash_command::{ArgumentCompletion, SlashCommandOutputSection};
+use gpui::{AppContext, Task, WeakView};
+use language::{CodeLabel, LspAdapterDelegate};
+use std::{
+    fmt::Write,
+    sync::{atomic::AtomicBool, Arc},
+};
+use ui::prelude::*;
+use workspace::Workspace;
+
+pub(crate) struct LogSlashCommand;
+
+/// The number of commits inserted when no count is given.
+const DEFAULT_COMMIT_COUNT: usize = 10;
+
+impl SlashCommand for LogSlashCommand {
+    fn name(&self) -> String {
+        "log".into()
+    }
+
+    fn label(&self, cx: &AppContext) -> CodeLabel {
+        create_label_for_command("log", &["[count]"], cx)
+    }
+
+    fn description(&self) -> String {
+        "insert recent commit messages".into()
+    }
+
+    fn menu_text(&self) -> String {
+        "Insert Recent Commits".into()
+    }
+
+    fn requires_argument(&self) -> bool {
+        false
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        _query: String,
+        _cancel: Arc<AtomicBool>,
+        _workspace: Option<WeakView<Workspace>>,
+        _cx: &mut AppContext,
+    ) -> Task<Result<Vec<ArgumentCompletion>>> {
+        Task::ready(Ok(Vec::new()))
+    }
+
+    fn run(
+        self: Arc<Self>,
+        argument: Option<&str>,
+        workspace: WeakView<Workspace>,
+        _delegate: Arc<dyn LspAdapterDelegate>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let Some((repo, _)) = workspace.read(cx).project().read(cx).active_repository(cx) else {
+            return Task::ready(Err(anyhow!("no git repository found in the project")));
+        };
+        let count = match argument
+            .map(str::trim)
+            .filter(|argument| !argument.is_empty())
+            .map(|argument| argument.parse::<usize>())
+        {
+            Some(Ok(count)) => count,
+            Some(Err(_)) => return Task::ready(Err(anyhow!("expected a number of commits"))),
+            None => DEFAULT_COMMIT_COUNT,
+        };
+
+        cx.background_executor().spawn(async move {
+            let commits = repo
+                .log(None, 0, count)
+                .context("failed to load the commits")?;
+            if commits.is_empty() {
+                return Err(anyhow!("no commits found"));
+            }
+
+            let mut text = "Recent commits:\n".to_string();
+            let mut sections = Vec::new();
+            for commit in &commits {
+                let start = text.len();
+                writeln!(
+                    text,
+                    "commit {}\nAuthor: {} <{}>\n",
+                    commit.sha, commit.author_name, commit.author_email
+                )
+                .unwrap();
+                for line in commit.message.trim_end().lines() {
+                    writeln!(text, "    {line}").unwrap();
+                }
+                sections.push(SlashCommandOutputSection {
+                    range: start..text.len() - 1,
+                    icon: IconName::FileGit,
+                    label: format!("{} {}", commit.sha.display_short(), commit.summary()).into(),
+                });
+                text.push('\n');
+            }
+            sections.push(SlashCommandOutputSection {
+                range: 0..text.len() - 1,
+                icon: IconName::HistoryRerun,
+                label: format!("Recent Commits ({})", commits.len()).into(),
+            });
+
+            Ok(SlashCommandOutput {
+                text,
+                sections,
+                run_commands_in_text: false,
+            })
+        })
+    }
+}
@@ -0,0 +1,109 @@
+mod diagnostics_tool;
+mod propose_edits_tool;
+mod read_file_tool;
+mod run_task_tool;
+mod search_tool;
+
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AnyWindowHandle, AsyncAppContext, Task, WeakView, WindowContext};
+use language_model::{LanguageModelRequestTool, LanguageModelToolUse};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use workspace::Workspace;
+
+use diagnostics_tool::DiagnosticsTool;
+use propose_edits_tool::ProposeEditsTool;
+use read_file_tool::ReadFileTool;
+use run_task_tool::RunTaskTool;
+use search_tool::SearchTool;
+
+/// A tool the model can call while responding in a context.
+pub(crate) trait AssistantTool: 'static + Send + Sync {
+    fn name(&self) -> String;
+
+    /// Tells the model what the tool does and when to use it.
+    fn description(&self) -> String;
+
+    fn input_schema(&self) -> serde_json::Value;
+
+    /// Whether each call must be allowed by the user before it runs. Tools that only read
+    /// from the project don't need to be.
+    fn needs_approval(&self) -> bool;
+
+    /// Runs a call of the tool, returning the output that's sent back to the model.
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>>;
+}
+
+/// Returns the JSON schema of a tool's input.
+fn input_schema<T: JsonSchema>() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(T)).unwrap()
+}
+
+fn parse_input<T: DeserializeOwned>(input: serde_json::Value) -> Result<T> {
+    serde_json::from_value(input).context("invalid input")
+}
+
+/// The tools available to the model in a context, run in the workspace of the window the
+/// context is open in.
+#[derive(Clone)]
+pub(crate) struct ContextTools {
+    tools: Vec<Arc<dyn AssistantTool>>,
+    workspace: WeakView<Workspace>,
+    window: AnyWindowHandle,
+}
+
+impl ContextTools {
+    pub(crate) fn builtin(workspace: WeakView<Workspace>, window: AnyWindowHandle) -> Self {
+        Self {
+            tools: vec![
+                Arc::new(ReadFileTool),
+                Arc::new(SearchTool),
+                Arc::new(DiagnosticsTool),
+                Arc::new(RunTaskTool),
+                Arc::new(ProposeEditsTool),
+            ],
+            workspace,
+            window,
+        }
+    }
+
+    pub(crate) fn definitions(&self) -> Vec<LanguageModelRequestTool> {
+        self.tools
+            .iter()
+            .map(|tool| LanguageModelRequestTool {
+                name: tool.name(),
+                description: tool.description(),
+                input_schema: tool.input_schema(),
+            })
+            .collect()
+    }
+
+    fn tool(&self, name: &str) -> Option<&Arc<dyn AssistantTool>> {
+        self.tools.iter().find(|tool| tool.name() == name)
+    }
+
+    /// Calls of unknown tools don't need to be approved, as they fail without running.
+    pub(crate) fn needs_approval(&self, name: &str) -> bool {
+        self.tool(name).map_or(false, |tool| tool.needs_approval())
+    }
+
+    pub(crate) fn run(
+        &self,
+        tool_use: &LanguageModelToolUse,
+        cx: &mut AsyncAppContext,
+    ) -> Task<Result<String>> {
+        let Some(tool) = self.tool(&tool_use.name).cloned() else {
+            return Task::ready(Err(anyhow!("no tool named {:?}", tool_use.name)));
+        };
+        let input = tool_use.input.clone();
+        let workspace = self.workspace.clone();
+        cx.update_window(self.window, |_, cx| tool.run(input, workspace, cx))
+            .unwrap_or_else(|error| Task::ready(Err(error)))
+    }
+}
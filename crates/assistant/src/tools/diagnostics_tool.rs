@@ -0,0 +1,73 @@
+use super::{input_schema, parse_input, AssistantTool};
+use crate::slash_command::diagnostics_command::{collect_diagnostics, Options};
+use anyhow::{anyhow, Result};
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use util::paths::PathMatcher;
+use workspace::Workspace;
+
+pub(crate) struct DiagnosticsTool;
+
+#[derive(Deserialize, JsonSchema)]
+struct DiagnosticsInput {
+    /// A glob that the paths of the files must match, such as `src/**/*.rs`. Defaults to
+    /// every file of the project.
+    path: Option<String>,
+    /// Whether to list warnings as well as errors.
+    #[serde(default)]
+    include_warnings: bool,
+}
+
+impl AssistantTool for DiagnosticsTool {
+    fn name(&self) -> String {
+        "list_diagnostics".into()
+    }
+
+    fn description(&self) -> String {
+        "Lists the errors, and optionally the warnings, reported by the language servers for \
+         the files of the project."
+            .into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        input_schema::<DiagnosticsInput>()
+    }
+
+    fn needs_approval(&self) -> bool {
+        false
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input = match parse_input::<DiagnosticsInput>(input) {
+            Ok(input) => input,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let path_matcher = match input.path.map(|path| PathMatcher::new(&[path])) {
+            Some(Ok(path_matcher)) => Some(path_matcher),
+            Some(Err(error)) => return Task::ready(Err(error.into())),
+            None => None,
+        };
+        let options = Options {
+            include_warnings: input.include_warnings,
+            path_matcher,
+        };
+
+        let task = collect_diagnostics(workspace.read(cx).project().clone(), options, cx);
+        cx.background_executor().spawn(async move {
+            match task.await? {
+                Some((text, _)) => Ok(text),
+                None => Ok("No diagnostics found.".into()),
+            }
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use super::{input_schema, parse_input, AssistantTool};
+use crate::{
+    assistant_panel::open_editor_for_edit_suggestions, AssistantPanel, EditOperation, EditStep,
+};
+use anyhow::{anyhow, Result};
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use workspace::Workspace;
+
+pub(crate) struct ProposeEditsTool;
+
+#[derive(Deserialize, JsonSchema)]
+struct ProposeEditsInput {
+    /// The edits to propose, each applying to one location of the codebase.
+    operations: Vec<EditOperation>,
+}
+
+impl AssistantTool for ProposeEditsTool {
+    fn name(&self) -> String {
+        "propose_edits".into()
+    }
+
+    fn description(&self) -> String {
+        "Proposes edits to the files of the project, which are opened as suggestions that the \
+         user reviews before they're applied."
+            .into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        input_schema::<ProposeEditsInput>()
+    }
+
+    fn needs_approval(&self) -> bool {
+        true
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input = match parse_input::<ProposeEditsInput>(input) {
+            Ok(input) => input,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(workspace_view) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let workspace_view = workspace_view.read(cx);
+        let project = workspace_view.project().clone();
+        let Some(assistant_panel) = workspace_view.panel::<AssistantPanel>(cx) else {
+            return Task::ready(Err(anyhow!("assistant panel not found")));
+        };
+        let assistant_panel = assistant_panel.downgrade();
+        let edit_suggestions =
+            EditStep::edit_suggestions_for_operations(&input.operations, &project, cx);
+
+        cx.spawn(|mut cx| async move {
+            let edit_suggestions = edit_suggestions.await;
+            let suggestion_count = edit_suggestions
+                .values()
+                .flatten()
+                .map(|group| group.suggestions.len())
+                .sum::<usize>();
+            let file_count = edit_suggestions.len();
+            if open_editor_for_edit_suggestions(
+                edit_suggestions,
+                workspace,
+                project,
+                assistant_panel,
+                &mut cx,
+            )?
+            .is_none()
+            {
+                return Err(anyhow!("none of the operations could be resolved"));
+            }
+            Ok(format!(
+                "Proposed {suggestion_count} edits in {file_count} files, out of {} operations. \
+                 The user will review them.",
+                input.operations.len()
+            ))
+        })
+    }
+}
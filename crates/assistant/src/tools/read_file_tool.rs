@@ -0,0 +1,99 @@
+use super::{input_schema, parse_input, AssistantTool};
+use crate::slash_command::file_command::codeblock_fence_for_path;
+use anyhow::{anyhow, Result};
+use gpui::{Task, WeakView, WindowContext};
+use language::{LineEnding, Point};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::{cmp, path::Path, sync::Arc};
+use workspace::Workspace;
+
+pub(crate) struct ReadFileTool;
+
+#[derive(Deserialize, JsonSchema)]
+struct ReadFileInput {
+    /// The path of the file, starting with the name of the project folder it's in.
+    path: String,
+    /// The first line to read, starting at 1. Defaults to the start of the file.
+    start_line: Option<u32>,
+    /// The last line to read, inclusive. Defaults to the end of the file.
+    end_line: Option<u32>,
+}
+
+impl AssistantTool for ReadFileTool {
+    fn name(&self) -> String {
+        "read_file".into()
+    }
+
+    fn description(&self) -> String {
+        "Reads the content of a file in the project, or of a range of its lines.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        input_schema::<ReadFileInput>()
+    }
+
+    fn needs_approval(&self) -> bool {
+        false
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input = match parse_input::<ReadFileInput>(input) {
+            Ok(input) => input,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let project = workspace.read(cx).project().clone();
+        let Some(project_path) = project
+            .read(cx)
+            .find_project_path(Path::new(&input.path), cx)
+        else {
+            return Task::ready(Err(anyhow!("no file found at {:?}", input.path)));
+        };
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+        cx.spawn(|cx| async move {
+            let buffer = open_buffer.await?;
+            let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+            // Private files, such as `.env` files, aren't sent to the model.
+            if snapshot.file().map_or(false, |file| file.is_private()) {
+                return Err(anyhow!(
+                    "{:?} is a private file, which can't be read",
+                    input.path
+                ));
+            }
+
+            let max_row = snapshot.max_point().row;
+            let start_row = input.start_line.unwrap_or(1).saturating_sub(1);
+            let end_row = cmp::min(
+                input
+                    .end_line
+                    .map_or(max_row, |line| line.saturating_sub(1)),
+                max_row,
+            );
+            if start_row > end_row {
+                return Err(anyhow!("the file has {} lines", max_row + 1));
+            }
+            let range = Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row));
+            let mut content = snapshot.text_for_range(range).collect::<String>();
+            LineEnding::normalize(&mut content);
+
+            let row_range = (input.start_line.is_some() || input.end_line.is_some())
+                .then_some(start_row..end_row);
+            let mut output = codeblock_fence_for_path(Some(Path::new(&input.path)), row_range);
+            output.push_str(&content);
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str("```");
+            Ok(output)
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use super::{input_schema, parse_input, AssistantTool};
+use anyhow::{anyhow, Context as _, Result};
+use editor::tasks::task_context;
+use futures::FutureExt as _;
+use gpui::{Task, WeakView, WindowContext};
+use project::terminals::TerminalKind;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::{fmt::Write, path::Path, sync::Arc, time::Duration};
+use task::TaskTemplate;
+use terminal::TaskStatus;
+use workspace::Workspace;
+
+/// The number of the last non-empty lines of output that are sent back to the model, as the
+/// end of the output usually matters most.
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// How long a task may run before it's stopped, so that a command that never exits, like
+/// a server or a watcher, doesn't stall the conversation.
+const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub(crate) struct RunTaskTool;
+
+#[derive(Deserialize, JsonSchema)]
+struct RunTaskInput {
+    /// The shell command to run, such as `cargo test`. Task variables like `$ZED_FILE` are
+    /// substituted.
+    command: String,
+    /// The directory to run the command in, starting with the name of the project folder
+    /// it's in. Defaults to the directory of the active file's project folder.
+    cwd: Option<String>,
+}
+
+impl AssistantTool for RunTaskTool {
+    fn name(&self) -> String {
+        "run_task".into()
+    }
+
+    fn description(&self) -> String {
+        "Runs a shell command as a task in the project, such as a build or the tests, and \
+         returns its exit status and output once it finishes."
+            .into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        input_schema::<RunTaskInput>()
+    }
+
+    fn needs_approval(&self) -> bool {
+        true
+    }
+
+    /// Runs the command in a terminal of the project, which spawns it on the remote host for
+    /// SSH projects. The terminal is dropped, and with it the process killed, once the tool
+    /// call is cancelled or the task times out.
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input = match parse_input::<RunTaskInput>(input) {
+            Ok(input) => input,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let project = workspace.read(cx).project().clone();
+        if project.read(cx).is_remote() {
+            return Task::ready(Err(anyhow!(
+                "running tasks is not supported in remote projects"
+            )));
+        }
+        let cwd = {
+            let project = project.read(cx);
+            match input.cwd {
+                Some(cwd) => {
+                    let Some(cwd) = project
+                        .find_project_path(Path::new(&cwd), cx)
+                        .and_then(|project_path| project.absolute_path(&project_path, cx))
+                    else {
+                        return Task::ready(Err(anyhow!("no directory found at {cwd:?}")));
+                    };
+                    Some(cwd)
+                }
+                None => project
+                    .visible_worktrees(cx)
+                    .next()
+                    .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+            }
+        };
+        let template = TaskTemplate {
+            label: input.command.clone(),
+            command: input.command,
+            cwd: cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+            ..TaskTemplate::default()
+        };
+        let task_context = workspace.update(cx, |workspace, cx| task_context(workspace, cx));
+        // Local commands run in the shell of this OS, remote ones in the shell of the host.
+        let use_cmd = cfg!(windows) && !project.read(cx).is_ssh();
+        let window = cx.window_handle();
+
+        cx.spawn(|mut cx| async move {
+            let task_context = task_context.await;
+            let mut spawn = template
+                .resolve_task("assistant", &task_context)
+                .and_then(|task| task.resolved)
+                .context("failed to resolve the task")?;
+            // The template has no arguments, so the substituted command is the whole script.
+            let script = std::mem::take(&mut spawn.command);
+            if use_cmd {
+                spawn.command = "cmd".into();
+                spawn.args = vec!["/C".into(), script];
+            } else {
+                spawn.command = "sh".into();
+                spawn.args = vec!["-c".into(), script];
+            }
+
+            let terminal = project.update(&mut cx, |project, cx| {
+                project.create_terminal(TerminalKind::Task(spawn), window, cx)
+            })??;
+            let completed =
+                terminal.update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))?;
+            let timed_out = futures::select_biased! {
+                _ = completed.fuse() => false,
+                _ = cx.background_executor().timer(TIMEOUT).fuse() => true,
+            };
+
+            terminal.read_with(&cx, |terminal, _| {
+                let mut text = String::new();
+                if timed_out {
+                    writeln!(
+                        text,
+                        "The task was stopped after running for {} seconds.",
+                        TIMEOUT.as_secs()
+                    )
+                    .unwrap();
+                } else {
+                    let status = match terminal.task().map(|task| task.status) {
+                        Some(TaskStatus::Completed { success: true }) => "succeeded",
+                        Some(TaskStatus::Completed { success: false }) => "failed",
+                        _ => "unknown",
+                    };
+                    writeln!(text, "Exit status: {status}").unwrap();
+                }
+                let output = terminal.last_n_non_empty_lines(MAX_OUTPUT_LINES);
+                if !output.is_empty() {
+                    write!(text, "\nOutput:\n```\n{}\n```\n", output.join("\n")).unwrap();
+                }
+                text
+            })
+        })
+    }
+}
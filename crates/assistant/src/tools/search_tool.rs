@@ -0,0 +1,138 @@
+use super::{input_schema, parse_input, AssistantTool};
+use anyhow::{anyhow, Result};
+use gpui::{Task, WeakView, WindowContext};
+use language::{OffsetRangeExt as _, Point};
+use project::{search::SearchQuery, SearchResult};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::{fmt::Write, sync::Arc};
+use util::paths::PathMatcher;
+use workspace::Workspace;
+
+/// The number of matching lines after which the search stops.
+const MAX_MATCHES: usize = 200;
+
+pub(crate) struct SearchTool;
+
+#[derive(Deserialize, JsonSchema)]
+struct SearchInput {
+    /// The text to search for.
+    query: String,
+    /// Whether the query is a regular expression.
+    #[serde(default)]
+    regex: bool,
+    /// A glob that the paths of the searched files must match, such as `src/**/*.rs`.
+    include: Option<String>,
+}
+
+impl SearchInput {
+    fn to_query(self) -> Result<SearchQuery> {
+        let files_to_include = match self.include {
+            Some(include) => PathMatcher::new(&[include])?,
+            None => PathMatcher::default(),
+        };
+        if self.regex {
+            SearchQuery::regex(
+                self.query,
+                false,
+                false,
+                false,
+                files_to_include,
+                PathMatcher::default(),
+            )
+        } else {
+            SearchQuery::text(
+                self.query,
+                false,
+                false,
+                false,
+                files_to_include,
+                PathMatcher::default(),
+            )
+        }
+    }
+}
+
+impl AssistantTool for SearchTool {
+    fn name(&self) -> String {
+        "search_project".into()
+    }
+
+    fn description(&self) -> String {
+        "Searches the files of the project for text or a regular expression, returning the \
+         matching lines with their paths and line numbers."
+            .into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        input_schema::<SearchInput>()
+    }
+
+    fn needs_approval(&self) -> bool {
+        false
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input = match parse_input::<SearchInput>(input) {
+            Ok(input) => input,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("workspace was dropped")));
+        };
+        let query = match input.to_query() {
+            Ok(query) => query,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let project = workspace.read(cx).project().clone();
+        let results = project.update(cx, |project, cx| project.search(query, cx));
+
+        cx.spawn(|cx| async move {
+            let mut output = String::new();
+            let mut match_count = 0;
+            'results: while let Ok(result) = results.recv().await {
+                let SearchResult::Buffer { buffer, ranges } = result else {
+                    break;
+                };
+                let (snapshot, path) = buffer.read_with(&cx, |buffer, cx| {
+                    // Like the read_file tool, the search leaves out private files.
+                    let path = buffer
+                        .file()
+                        .filter(|file| !file.is_private())
+                        .map(|file| file.full_path(cx));
+                    (buffer.snapshot(), path)
+                })?;
+                let Some(path) = path else {
+                    continue;
+                };
+                let mut last_row = None;
+                for range in ranges {
+                    let row = range.to_point(&snapshot).start.row;
+                    if last_row == Some(row) {
+                        continue;
+                    }
+                    last_row = Some(row);
+                    if match_count == MAX_MATCHES {
+                        writeln!(output, "(stopped after {MAX_MATCHES} matching lines)").unwrap();
+                        break 'results;
+                    }
+                    match_count += 1;
+                    let line = snapshot
+                        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                        .collect::<String>();
+                    writeln!(output, "{}:{}: {}", path.display(), row + 1, line.trim()).unwrap();
+                }
+            }
+
+            if output.is_empty() {
+                output.push_str("No matches found.");
+            }
+            Ok(output)
+        })
+    }
+}
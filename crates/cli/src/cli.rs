@@ -12,6 +12,8 @@ pub enum CliRequest {
     Open {
         paths: Vec<String>,
         urls: Vec<String>,
+        /// Pairs of an old and a new path to open side-by-side diffs of.
+        diff_paths: Vec<[String; 2]>,
         wait: bool,
         open_new_workspace: Option<bool>,
         dev_server_token: Option<String>,
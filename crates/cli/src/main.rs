@@ -38,6 +38,9 @@ struct Args {
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
     paths_with_position: Vec<String>,
+    /// Open a side-by-side diff of two files.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
+    diff: Vec<String>,
     /// Print Zed's version and the app path.
     #[arg(short, long)]
     version: bool,
@@ -133,6 +136,19 @@ fn main() -> Result<()> {
         }
     }
 
+    let mut diff_paths = vec![];
+    for pair in args.diff.chunks(2) {
+        let [old_path, new_path] = pair else {
+            continue;
+        };
+        let canonicalize = |path: &String| {
+            fs::canonicalize(path)
+                .map(|path| path.display().to_string())
+                .with_context(|| format!("Failed to find {path}"))
+        };
+        diff_paths.push([canonicalize(old_path)?, canonicalize(new_path)?]);
+    }
+
     let sender: JoinHandle<anyhow::Result<()>> = thread::spawn({
         let exit_status = exit_status.clone();
         move || {
@@ -141,6 +157,7 @@ fn main() -> Result<()> {
             tx.send(CliRequest::Open {
                 paths,
                 urls,
+                diff_paths,
                 wait: args.wait,
                 open_new_workspace,
                 dev_server_token: args.dev_server_token,
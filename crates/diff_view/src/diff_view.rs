@@ -0,0 +1,91 @@
+mod side_by_side_diff;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use gpui::{actions, AppContext, AsyncWindowContext, Context as _, Model, Task, View, ViewContext};
+use language::{Buffer, Capability, LanguageRegistry};
+use workspace::Workspace;
+
+pub use side_by_side_diff::SideBySideDiff;
+
+actions!(diff_view, [OpenSideBySideDiff]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenSideBySideDiff, cx| {
+            let Some(buffer) = workspace
+                .active_item_as::<Editor>(cx)
+                .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            else {
+                return;
+            };
+            if let Err(error) = SideBySideDiff::open_for_buffer(buffer, workspace, cx) {
+                workspace.show_error(&error, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Opens a side-by-side diff of two files on disk in the workspace.
+pub fn open_file_diff(
+    old_path: PathBuf,
+    new_path: PathBuf,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<View<SideBySideDiff>>> {
+    let fs = workspace.app_state().fs.clone();
+    let languages = workspace.project().read(cx).languages().clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let old_text = fs
+            .load(&old_path)
+            .await
+            .with_context(|| format!("failed to load {}", old_path.display()))?;
+        let new_text = fs
+            .load(&new_path)
+            .await
+            .with_context(|| format!("failed to load {}", new_path.display()))?;
+        let old_buffer = build_file_buffer(old_text, &old_path, &languages, &mut cx).await?;
+        let new_buffer = build_file_buffer(new_text, &new_path, &languages, &mut cx).await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            SideBySideDiff::open(
+                old_buffer,
+                new_buffer,
+                file_label(&old_path),
+                file_label(&new_path),
+                workspace,
+                cx,
+            )
+        })
+    })
+}
+
+/// Builds a read-only buffer for the contents of a file that isn't part of the project,
+/// highlighted by the language of its path.
+async fn build_file_buffer(
+    text: String,
+    path: &Path,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Model<Buffer>> {
+    let language = languages.language_for_file_path(path).await.ok();
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language_registry(languages.clone());
+        buffer.set_language(language, cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
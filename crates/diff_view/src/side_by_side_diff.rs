@@ -0,0 +1,599 @@
+use std::{ops::Range, time::Duration};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockProperties, BlockStyle, CustomBlockId},
+    Editor, EditorEvent,
+};
+use git::diff::{word_diff, BufferDiff};
+use gpui::{
+    point, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, HighlightStyle, Hsla,
+    Model, Render, Subscription, Task, View, ViewContext,
+};
+use language::{language_settings, Bias, Buffer, Capability, Point};
+use project::Project;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(50);
+
+enum DiffRowHighlight {}
+enum DiffWordHighlight {}
+
+/// Two read-only editors showing an old and a new version of a text next to each other,
+/// with the changed lines aligned and their scroll positions kept in sync.
+pub struct SideBySideDiff {
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    old_editor: View<Editor>,
+    new_editor: View<Editor>,
+    old_label: SharedString,
+    new_label: SharedString,
+    /// Whether the old buffer holds the new buffer's diff base, and is updated along with it.
+    follows_diff_base: bool,
+    old_blocks: HashSet<CustomBlockId>,
+    new_blocks: HashSet<CustomBlockId>,
+    _refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The changed lines and words of one side of a diff, and the filler lines that align
+/// them with the other side.
+#[derive(Default, Debug, PartialEq)]
+struct SideLayout {
+    changed_rows: Vec<Range<u32>>,
+    changed_words: Vec<Range<usize>>,
+    /// The rows above which filler lines are shown, along with how many are shown.
+    fillers: Vec<(u32, u32)>,
+}
+
+impl SideBySideDiff {
+    /// Opens a diff of two buffers in the workspace's active pane.
+    pub fn open(
+        old_buffer: Model<Buffer>,
+        new_buffer: Model<Buffer>,
+        old_label: impl Into<SharedString>,
+        new_label: impl Into<SharedString>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let project = workspace.project().clone();
+        let diff = cx.new_view(|cx| {
+            Self::new(
+                old_buffer,
+                new_buffer,
+                old_label.into(),
+                new_label.into(),
+                false,
+                project,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(diff.clone()), None, true, cx);
+        diff
+    }
+
+    /// Opens a diff of a project buffer against its diff base, which is the file's staged
+    /// contents or the revision that the project is diffed against.
+    pub fn open_for_buffer(
+        buffer: Model<Buffer>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Result<()> {
+        let project = workspace.project().clone();
+        let (diff_base, language, name, buffer_id) = {
+            let buffer = buffer.read(cx);
+            let diff_base = buffer
+                .diff_base()
+                .context("This file has no changes tracked by git")?
+                .to_string();
+            let name = buffer
+                .file()
+                .map(|file| file.file_name(cx).to_string_lossy().into_owned())
+                .unwrap_or_else(|| "untitled".to_string());
+            (
+                diff_base,
+                buffer.language().cloned(),
+                name,
+                buffer.remote_id(),
+            )
+        };
+        let old_label = match project.read(cx).diff_base_revision(buffer_id, cx) {
+            Some(revision) => format!("{name} @ {revision}"),
+            None => format!("{name} @ index"),
+        };
+        let languages = project.read(cx).languages().clone();
+        let old_buffer = cx.new_model(|cx| {
+            let mut old_buffer = Buffer::local(diff_base, cx);
+            old_buffer.set_language_registry(languages);
+            old_buffer.set_language(language, cx);
+            old_buffer.set_capability(Capability::ReadOnly, cx);
+            old_buffer
+        });
+
+        let diff = cx.new_view(|cx| {
+            Self::new(
+                old_buffer,
+                buffer,
+                old_label.into(),
+                name.into(),
+                true,
+                project,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(diff), None, true, cx);
+        Ok(())
+    }
+
+    fn new(
+        old_buffer: Model<Buffer>,
+        new_buffer: Model<Buffer>,
+        old_label: SharedString,
+        new_label: SharedString,
+        follows_diff_base: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old_editor = Self::build_editor(old_buffer.clone(), None, cx);
+        let new_editor = Self::build_editor(new_buffer.clone(), Some(project), cx);
+
+        let subscriptions = vec![
+            cx.subscribe(&old_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(true, cx);
+                }
+            }),
+            cx.subscribe(&new_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(false, cx);
+                }
+            }),
+            cx.subscribe(&old_buffer, |this, _, event: &language::Event, cx| {
+                if let language::Event::Edited = event {
+                    this.refresh(true, cx);
+                }
+            }),
+            cx.subscribe(
+                &new_buffer,
+                |this, _, event: &language::Event, cx| match event {
+                    language::Event::Edited => this.refresh(true, cx),
+                    language::Event::DiffBaseChanged if this.follows_diff_base => {
+                        this.update_old_text(cx)
+                    }
+                    _ => {}
+                },
+            ),
+        ];
+
+        let mut this = Self {
+            old_buffer,
+            new_buffer,
+            old_editor,
+            new_editor,
+            old_label,
+            new_label,
+            follows_diff_base,
+            old_blocks: HashSet::default(),
+            new_blocks: HashSet::default(),
+            _refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(false, cx);
+        this
+    }
+
+    fn build_editor(
+        buffer: Model<Buffer>,
+        project: Option<Model<Project>>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Editor> {
+        cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer, project, cx);
+            editor.set_read_only(true);
+            // Wrapping lines would break the alignment of the two sides.
+            editor.set_soft_wrap_mode(language_settings::SoftWrap::None, cx);
+            editor
+        })
+    }
+
+    /// Replaces the old buffer's text with the new buffer's diff base after it changed.
+    fn update_old_text(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(diff_base) = self
+            .new_buffer
+            .read(cx)
+            .diff_base()
+            .map(|base| base.to_string())
+        else {
+            return;
+        };
+        self.old_buffer.update(cx, |old_buffer, cx| {
+            if old_buffer.text() != diff_base {
+                old_buffer.set_text(diff_base, cx);
+            }
+        });
+    }
+
+    /// Scrolls one side to the same row as the other one, which shows the same lines
+    /// since both sides are aligned.
+    fn sync_scroll_position(&mut self, from_old: bool, cx: &mut ViewContext<Self>) {
+        let (source, target) = if from_old {
+            (&self.old_editor, &self.new_editor)
+        } else {
+            (&self.new_editor, &self.old_editor)
+        };
+        let source_position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+        target.update(cx, |editor, cx| {
+            let position = editor.scroll_position(cx);
+            if position.y != source_position.y {
+                editor.set_scroll_position(point(position.x, source_position.y), cx);
+            }
+        });
+    }
+
+    /// Diffs the two buffers in the background, then highlights the changes on both sides
+    /// and aligns them, after a short delay when `debounce` is set.
+    fn refresh(&mut self, debounce: bool, cx: &mut ViewContext<Self>) {
+        let old_snapshot = self.old_buffer.read(cx).text_snapshot();
+        let new_snapshot = self.new_buffer.read(cx).text_snapshot();
+        self._refresh_task = cx.spawn(|this, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            }
+            let (old_layout, new_layout) = cx
+                .background_executor()
+                .spawn(async move { layout_diff(&old_snapshot, &new_snapshot).await })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                let status = cx.theme().status();
+                let (mut deleted_word, mut created_word) = (status.deleted, status.created);
+                deleted_word.fade_out(0.7);
+                created_word.fade_out(0.7);
+                let (deleted_row, created_row) =
+                    (status.deleted_background, status.created_background);
+
+                let old_blocks = std::mem::take(&mut this.old_blocks);
+                this.old_blocks = apply_layout(
+                    &this.old_editor,
+                    old_layout,
+                    deleted_row,
+                    deleted_word,
+                    old_blocks,
+                    cx,
+                );
+                let new_blocks = std::mem::take(&mut this.new_blocks);
+                this.new_blocks = apply_layout(
+                    &this.new_editor,
+                    new_layout,
+                    created_row,
+                    created_word,
+                    new_blocks,
+                    cx,
+                );
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+}
+
+/// Diffs a new version of a text against an old one, and lays out both sides so that each
+/// change starts on the same row of both.
+async fn layout_diff(
+    old: &text::BufferSnapshot,
+    new: &text::BufferSnapshot,
+) -> (SideLayout, SideLayout) {
+    let mut diff = BufferDiff::new();
+    diff.update(old.as_rope(), new).await;
+
+    let mut old_layout = SideLayout::default();
+    let mut new_layout = SideLayout::default();
+    // How many rows further down the old text a row of the new text is.
+    let mut row_delta = 0_i64;
+    for hunk in diff.hunks_in_row_range(0..u32::MAX, new) {
+        let new_rows = hunk.associated_range;
+        let old_rows = if hunk.diff_base_byte_range.is_empty() {
+            let start = (new_rows.start as i64 + row_delta) as u32;
+            start..start
+        } else {
+            let start = old.offset_to_point(hunk.diff_base_byte_range.start);
+            let end = old.offset_to_point(hunk.diff_base_byte_range.end);
+            start.row..end.row + (end.column > 0) as u32
+        };
+        row_delta = old_rows.end as i64 - new_rows.end as i64;
+
+        let old_len = old_rows.end - old_rows.start;
+        let new_len = new_rows.end - new_rows.start;
+        if old_len < new_len {
+            old_layout.fillers.push((old_rows.end, new_len - old_len));
+        } else if new_len < old_len {
+            new_layout.fillers.push((new_rows.end, old_len - new_len));
+        }
+
+        if old_len > 0 && new_len > 0 {
+            let old_range = row_range_to_offsets(old, &old_rows);
+            let new_range = row_range_to_offsets(new, &new_rows);
+            let old_text = old.text_for_range(old_range.clone()).collect::<String>();
+            let new_text = new.text_for_range(new_range.clone()).collect::<String>();
+            let (old_words, new_words) = word_diff(&old_text, &new_text);
+            old_layout.changed_words.extend(
+                old_words
+                    .into_iter()
+                    .map(|range| old_range.start + range.start..old_range.start + range.end),
+            );
+            new_layout.changed_words.extend(
+                new_words
+                    .into_iter()
+                    .map(|range| new_range.start + range.start..new_range.start + range.end),
+            );
+        }
+
+        old_layout.changed_rows.push(old_rows);
+        new_layout.changed_rows.push(new_rows);
+    }
+    (old_layout, new_layout)
+}
+
+fn row_range_to_offsets(snapshot: &text::BufferSnapshot, rows: &Range<u32>) -> Range<usize> {
+    let row_start = |row: u32| {
+        if row > snapshot.max_point().row {
+            snapshot.len()
+        } else {
+            snapshot.point_to_offset(Point::new(row, 0))
+        }
+    };
+    row_start(rows.start)..row_start(rows.end)
+}
+
+/// Highlights the changes on one side of the diff, and replaces its filler blocks.
+fn apply_layout(
+    editor: &View<Editor>,
+    layout: SideLayout,
+    row_color: Hsla,
+    word_color: Hsla,
+    old_blocks: HashSet<CustomBlockId>,
+    cx: &mut ViewContext<SideBySideDiff>,
+) -> HashSet<CustomBlockId> {
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let max_row = snapshot.max_point().row;
+
+        editor.clear_row_highlights::<DiffRowHighlight>();
+        for rows in layout.changed_rows.iter().filter(|rows| !rows.is_empty()) {
+            let start = snapshot.clip_point(Point::new(rows.start, 0), Bias::Left);
+            let end = snapshot.clip_point(Point::new(rows.end - 1, 0), Bias::Left);
+            editor.highlight_rows::<DiffRowHighlight>(
+                snapshot.anchor_before(start)..=snapshot.anchor_before(end),
+                Some(row_color),
+                false,
+                cx,
+            );
+        }
+        editor.highlight_text::<DiffWordHighlight>(
+            layout
+                .changed_words
+                .into_iter()
+                .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+                .collect(),
+            HighlightStyle {
+                background_color: Some(word_color),
+                ..Default::default()
+            },
+            cx,
+        );
+
+        if !old_blocks.is_empty() {
+            editor.remove_blocks(old_blocks, None, cx);
+        }
+        let blocks = layout
+            .fillers
+            .into_iter()
+            .map(|(row, height)| {
+                let (position, disposition) = if row <= max_row {
+                    (Point::new(row, 0), BlockDisposition::Above)
+                } else {
+                    (Point::new(max_row, 0), BlockDisposition::Below)
+                };
+                BlockProperties {
+                    position: snapshot.anchor_before(position),
+                    height,
+                    style: BlockStyle::Flex,
+                    disposition,
+                    render: Box::new(move |cx| render_filler(height, cx)),
+                }
+            })
+            .collect::<Vec<_>>();
+        editor.insert_blocks(blocks, None, cx).into_iter().collect()
+    })
+}
+
+/// Renders the blank lines that take the place of the lines that only the other side has.
+fn render_filler(height: u32, cx: &mut BlockContext) -> AnyElement {
+    div()
+        .w_full()
+        .h(cx.line_height * height as f32)
+        .bg(cx.theme().colors().editor_subheader_background)
+        .into_any_element()
+}
+
+impl EventEmitter<ItemEvent> for SideBySideDiff {}
+
+impl FocusableView for SideBySideDiff {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.new_editor.focus_handle(cx)
+    }
+}
+
+impl Item for SideBySideDiff {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Split))
+    }
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.old_label, self.new_label).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("side by side diff")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for SideBySideDiff {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        let pane_header = |label: &SharedString| {
+            div().w_1_2().px_2().py_1().child(
+                Label::new(label.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        };
+
+        v_flex()
+            .key_context("SideBySideDiff")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(pane_header(&self.old_label))
+                    .child(pane_header(&self.new_label)),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .child(div().w_1_2().h_full().child(self.old_editor.clone()))
+                    .child(div().w_px().h_full().bg(border_color))
+                    .child(div().w_1_2().h_full().child(self.new_editor.clone())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use text::BufferId;
+
+    async fn layout(old: &str, new: &str) -> (SideLayout, SideLayout) {
+        let old = text::Buffer::new(0, BufferId::new(1).unwrap(), old.to_string());
+        let new = text::Buffer::new(0, BufferId::new(2).unwrap(), new.to_string());
+        layout_diff(&old.snapshot(), &new.snapshot()).await
+    }
+
+    #[gpui::test]
+    async fn test_layout_insertion(_cx: &mut TestAppContext) {
+        let (old, new) = layout("one\ntwo\n", "one\ninserted\ntwo\n").await;
+        assert_eq!(
+            old,
+            SideLayout {
+                changed_rows: vec![1..1],
+                changed_words: Vec::new(),
+                fillers: vec![(1, 1)],
+            }
+        );
+        assert_eq!(
+            new,
+            SideLayout {
+                changed_rows: vec![1..2],
+                changed_words: Vec::new(),
+                fillers: Vec::new(),
+            }
+        );
+    }
+
+    #[gpui::test]
+    async fn test_layout_deletion(_cx: &mut TestAppContext) {
+        let (old, new) = layout("one\ntwo\nthree\n", "one\nthree\n").await;
+        assert_eq!(
+            old,
+            SideLayout {
+                changed_rows: vec![1..2],
+                changed_words: Vec::new(),
+                fillers: Vec::new(),
+            }
+        );
+        assert_eq!(
+            new,
+            SideLayout {
+                changed_rows: vec![1..1],
+                changed_words: Vec::new(),
+                fillers: vec![(1, 1)],
+            }
+        );
+    }
+
+    #[gpui::test]
+    async fn test_layout_end_of_file(_cx: &mut TestAppContext) {
+        // The deletion at the start shifts the rows of the old text, so the insertion at
+        // the end lines up with the end of the old text.
+        let (old, new) = layout("zero\none\ntwo\n", "one\ntwo\nthree\n").await;
+        assert_eq!(
+            old,
+            SideLayout {
+                changed_rows: vec![0..1, 3..3],
+                changed_words: Vec::new(),
+                fillers: vec![(3, 1)],
+            }
+        );
+        assert_eq!(
+            new,
+            SideLayout {
+                changed_rows: vec![0..0, 2..3],
+                changed_words: Vec::new(),
+                fillers: vec![(0, 1)],
+            }
+        );
+
+        let (old, new) = layout("one\ntwo\nthree\n", "one\n").await;
+        assert_eq!(old.changed_rows, [1..3]);
+        assert!(old.fillers.is_empty());
+        assert_eq!(new.changed_rows, [1..1]);
+        assert_eq!(new.fillers, [(1, 2)]);
+    }
+
+    #[gpui::test]
+    async fn test_layout_without_trailing_newline(_cx: &mut TestAppContext) {
+        let (old, new) = layout("one\ntwo", "one\ntoo").await;
+        assert_eq!(
+            old,
+            SideLayout {
+                changed_rows: vec![1..2],
+                changed_words: vec![4..7],
+                fillers: Vec::new(),
+            }
+        );
+        assert_eq!(
+            new,
+            SideLayout {
+                changed_rows: vec![1..2],
+                changed_words: vec![4..7],
+                fillers: Vec::new(),
+            }
+        );
+
+        // Adding the newline changes the last line without adding a row.
+        let (old, new) = layout("one\ntwo", "one\ntwo\n").await;
+        assert_eq!(old.changed_rows, [1..2]);
+        assert_eq!(new.changed_rows, [1..2]);
+        assert!(old.fillers.is_empty());
+        assert!(new.fillers.is_empty());
+    }
+}
@@ -165,7 +165,10 @@ gpui::actions!(
     [
         AcceptPartialCopilotSuggestion,
         AcceptInlineCompletion,
+        AcceptBoth,
+        AcceptOurs,
         AcceptPartialInlineCompletion,
+        AcceptTheirs,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
@@ -291,6 +294,7 @@ gpui::actions!(
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageSelectedHunks,
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
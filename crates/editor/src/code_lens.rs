@@ -0,0 +1,299 @@
+use collections::HashMap;
+use futures::future;
+use gpui::{AnyElement, Model, Task, ViewContext, WeakView};
+use language::{point_from_lsp, Bias, Buffer, Point, ToOffset as _, ToPoint as _};
+use multi_buffer::{Anchor, ExcerptId, ToPoint as _};
+use project::CodeLens;
+use text::BufferId;
+use ui::{h_flex, prelude::*, ButtonSize, ButtonStyle};
+use util::ResultExt;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    Editor, FindAllReferences,
+};
+
+/// The code lenses of the buffers in an editor, the blocks that display them, and the task
+/// that resolves the visible lenses that arrived without a command.
+#[derive(Default)]
+pub(super) struct CodeLensState {
+    pub(super) lenses: HashMap<BufferId, Vec<CodeLens>>,
+    /// The position of each block and the lenses it displays.
+    blocks: HashMap<CustomBlockId, (Anchor, Vec<CodeLens>)>,
+    resolve_task: Option<Task<()>>,
+}
+
+/// Updates the editor's code lens blocks to show one block per line that has lenses, placed
+/// above the line's first non-whitespace character. The blocks of lines that still have
+/// lenses are kept, and only rendered again when their lenses changed.
+pub(super) fn update_code_lens_blocks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let editor_handle = cx.view().downgrade();
+
+    let mut old_blocks = editor
+        .code_lens
+        .blocks
+        .drain()
+        .filter(|(_, (position, _))| snapshot.can_resolve(position))
+        .map(|(block_id, (position, lenses))| (position.to_point(&snapshot), (block_id, lenses)))
+        .collect::<HashMap<_, _>>();
+    let mut blocks_to_insert = Vec::new();
+    let mut renderers_to_replace = HashMap::default();
+    for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+        let Some(lenses) = editor.code_lens.lenses.get(&buffer_snapshot.remote_id()) else {
+            continue;
+        };
+        let Some(buffer) = editor.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+            continue;
+        };
+        let context = excerpt_range.context;
+
+        let mut lenses_by_row = Vec::<(u32, Vec<CodeLens>)>::new();
+        for lens in lenses {
+            let start = &lens.range.start;
+            if start.cmp(&context.start, buffer_snapshot).is_lt()
+                || start.cmp(&context.end, buffer_snapshot).is_gt()
+            {
+                continue;
+            }
+            let row = start.to_point(buffer_snapshot).row;
+            match lenses_by_row.last_mut() {
+                Some((last_row, row_lenses)) if *last_row == row => row_lenses.push(lens.clone()),
+                _ => lenses_by_row.push((row, vec![lens.clone()])),
+            }
+        }
+
+        for (row, lenses) in lenses_by_row {
+            // Lenses without a command have nothing to show until they're resolved, but
+            // their line is reserved for them.
+            let lenses = lenses
+                .into_iter()
+                .filter(|lens| lens.lsp_lens.command.is_some())
+                .collect::<Vec<_>>();
+            let indent = buffer_snapshot.indent_size_for_line(row).len;
+            let position = buffer_snapshot.anchor_before(Point::new(row, indent));
+            let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, position) else {
+                continue;
+            };
+            let render = {
+                let lenses = lenses.clone();
+                let editor = editor_handle.clone();
+                let buffer = buffer.clone();
+                Box::new(move |cx: &mut BlockContext| {
+                    render_code_lenses(&lenses, &buffer, excerpt_id, &editor, cx)
+                })
+            };
+            match old_blocks.remove(&position.to_point(&snapshot)) {
+                Some((block_id, old_lenses)) => {
+                    if old_lenses != lenses {
+                        renderers_to_replace.insert(block_id, render as RenderBlock);
+                    }
+                    editor.code_lens.blocks.insert(block_id, (position, lenses));
+                }
+                None => blocks_to_insert.push((
+                    lenses,
+                    BlockProperties {
+                        position,
+                        height: 1,
+                        style: BlockStyle::Flex,
+                        disposition: BlockDisposition::Above,
+                        render,
+                    },
+                )),
+            }
+        }
+    }
+
+    if !old_blocks.is_empty() {
+        let block_ids = old_blocks.into_values().map(|(block_id, _)| block_id);
+        editor.remove_blocks(block_ids.collect(), None, cx);
+    }
+    if !renderers_to_replace.is_empty() {
+        editor.replace_blocks(renderers_to_replace, None, cx);
+    }
+    if !blocks_to_insert.is_empty() {
+        let (lenses, blocks): (Vec<_>, Vec<_>) = blocks_to_insert.into_iter().unzip();
+        let positions = blocks
+            .iter()
+            .map(|block| block.position)
+            .collect::<Vec<_>>();
+        let block_ids = editor.insert_blocks(blocks, None, cx);
+        for ((block_id, position), lenses) in block_ids.into_iter().zip(positions).zip(lenses) {
+            editor.code_lens.blocks.insert(block_id, (position, lenses));
+        }
+    }
+    cx.notify();
+}
+
+/// Resolves the visible code lenses that arrived without a command, which servers commonly
+/// send to defer computing the commands. The lenses of the other lines are resolved once
+/// they're scrolled into view, and lenses that still have no command are dropped.
+pub(super) fn resolve_visible_code_lenses(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let mut requests = Vec::new();
+    for (buffer, visible_range, _) in editor.visible_buffer_ranges(cx) {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let buffer_id = buffer_snapshot.remote_id();
+        let Some(lenses) = editor.code_lens.lenses.get(&buffer_id) else {
+            continue;
+        };
+        for lens in lenses {
+            let start = lens.range.start.to_offset(&buffer_snapshot);
+            if lens.lsp_lens.command.is_some()
+                || start < visible_range.start
+                || start > visible_range.end
+            {
+                continue;
+            }
+            let request = project.update(cx, |project, cx| {
+                project.resolve_code_lens(&buffer, lens.clone(), cx)
+            });
+            let lens = lens.clone();
+            requests.push(async move { (buffer_id, lens, request.await) });
+        }
+    }
+    if requests.is_empty() {
+        return;
+    }
+
+    editor.code_lens.resolve_task = Some(cx.spawn(|editor, mut cx| async move {
+        let responses = future::join_all(requests).await;
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, lens, resolved) in responses {
+                    let Some(resolved) = resolved.log_err() else {
+                        continue;
+                    };
+                    let Some(lenses) = editor.code_lens.lenses.get_mut(&buffer_id) else {
+                        continue;
+                    };
+                    let Some(ix) = lenses.iter().position(|stored| *stored == lens) else {
+                        continue;
+                    };
+                    if resolved.lsp_lens.command.is_some() {
+                        lenses[ix] = resolved;
+                    } else {
+                        lenses.remove(ix);
+                    }
+                }
+                update_code_lens_blocks(editor, cx);
+            })
+            .ok();
+    }));
+}
+
+fn render_code_lenses(
+    lenses: &[CodeLens],
+    buffer: &Model<Buffer>,
+    excerpt_id: ExcerptId,
+    editor: &WeakView<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .pl(cx.anchor_x)
+        .gap_1()
+        .children(lenses.iter().enumerate().map(|(ix, lens)| {
+            let title = lens
+                .lsp_lens
+                .command
+                .as_ref()
+                .map(|command| command.title.clone())
+                .unwrap_or_default();
+            h_flex()
+                .gap_1()
+                .when(ix > 0, |row| {
+                    row.child(Label::new("|").size(LabelSize::Small).color(Color::Muted))
+                })
+                .child(
+                    Button::new(ix, title)
+                        .label_size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .size(ButtonSize::None)
+                        .style(ButtonStyle::Transparent)
+                        .on_click({
+                            let editor = editor.clone();
+                            let buffer = buffer.clone();
+                            let lens = lens.clone();
+                            move |_, cx| {
+                                editor
+                                    .update(cx, |editor, cx| {
+                                        run_code_lens(
+                                            editor,
+                                            buffer.clone(),
+                                            excerpt_id,
+                                            lens.clone(),
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            }
+                        }),
+                )
+        }))
+        .into_any_element()
+}
+
+/// Runs the command of a clicked code lens. Lenses that show references, which language
+/// servers expect the client to implement, list the references of their symbol instead.
+fn run_code_lens(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    excerpt_id: ExcerptId,
+    lens: CodeLens,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(command) = lens.lsp_lens.command.clone() else {
+        return;
+    };
+
+    if command.command.ends_with("showReferences") {
+        // The arguments of these commands are the document URI, the symbol's position and
+        // the references, in the order of VS Code's `editor.action.showReferences`.
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let position = command
+            .arguments
+            .as_ref()
+            .and_then(|arguments| arguments.get(1))
+            .and_then(|argument| serde_json::from_value::<lsp::Position>(argument.clone()).ok())
+            .map(|position| {
+                let point = buffer_snapshot.clip_point_utf16(point_from_lsp(position), Bias::Left);
+                buffer_snapshot.anchor_before(point)
+            })
+            .unwrap_or(lens.range.start);
+        let Some(position) = editor
+            .buffer
+            .read(cx)
+            .snapshot(cx)
+            .anchor_in_excerpt(excerpt_id, position)
+        else {
+            return;
+        };
+        editor.change_selections(None, cx, |selections| {
+            selections.select_anchor_ranges([position..position]);
+        });
+        if let Some(task) = editor.find_all_references(&FindAllReferences, cx) {
+            task.detach_and_log_err(cx);
+        }
+        return;
+    }
+
+    let (Some(project), Some(workspace)) = (editor.project.clone(), editor.workspace()) else {
+        return;
+    };
+    let title = command.title.clone();
+    let apply_code_lens =
+        project.update(cx, |project, cx| project.apply_code_lens(buffer, lens, cx));
+    let workspace = workspace.downgrade();
+    cx.spawn(|editor, cx| async move {
+        let project_transaction = apply_code_lens.await?;
+        Editor::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+    })
+    .detach_and_log_err(cx);
+}
@@ -62,7 +62,7 @@ use std::{
 };
 use sum_tree::{Bias, TreeMap};
 use tab_map::{TabMap, TabSnapshot};
-use text::LineIndent;
+use text::{BufferId, LineIndent};
 use ui::WindowContext;
 use wrap_map::{WrapMap, WrapSnapshot};
 
@@ -83,6 +83,16 @@ pub trait ToDisplayPoint {
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
 
+/// A region of text highlighted according to a language server's semantic tokens.
+#[derive(Clone, Debug)]
+pub struct SemanticHighlight {
+    pub range: Range<Anchor>,
+    pub style: HighlightStyle,
+    /// Whether this highlight replaces the syntax highlights of its range,
+    /// instead of being layered over them.
+    pub replaces_syntax: bool,
+}
+
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
 ///
@@ -105,8 +115,13 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Non-overlapping regions of text highlighted by semantic tokens, sorted by their position.
+    semantic_highlights: Arc<[SemanticHighlight]>,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
+    /// The folding ranges of language servers, sorted by their start, which supersede
+    /// indentation based fold range suggestions in their buffers.
+    folding_ranges: Arc<HashMap<BufferId, Arc<[Range<text::Anchor>]>>>,
     fold_placeholder: FoldPlaceholder,
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
@@ -156,6 +171,8 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Arc::from([]),
+            folding_ranges: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -183,6 +200,8 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
+            folding_ranges: self.folding_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -370,6 +389,17 @@ impl DisplayMap {
         }
     }
 
+    pub(crate) fn set_semantic_highlights(&mut self, highlights: Vec<SemanticHighlight>) {
+        self.semantic_highlights = highlights.into();
+    }
+
+    pub(crate) fn set_folding_ranges(
+        &mut self,
+        folding_ranges: HashMap<BufferId, Arc<[Range<text::Anchor>]>>,
+    ) {
+        self.folding_ranges = Arc::new(folding_ranges);
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
         let highlights = self.text_highlights.get(&Some(type_id))?;
         Some((highlights.0, &highlights.1))
@@ -445,6 +475,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a [SemanticHighlight]>,
     pub styles: HighlightStyles,
 }
 
@@ -472,6 +503,8 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: Arc<[SemanticHighlight]>,
+    folding_ranges: Arc<HashMap<BufferId, Arc<[Range<text::Anchor>]>>>,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -656,6 +689,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
                 styles: highlight_styles,
             },
         )
@@ -951,6 +985,52 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether a fold can start on the given row, according to the language server folding
+    /// ranges of its buffer or, when it has none, to indentation.
+    pub fn starts_fold(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.language_server_fold_range(buffer_row) {
+            Some(range) => range.is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// Returns the outermost language server folding range starting on the given row, or
+    /// `None` when the row's buffer has no folding ranges and folds by indentation instead.
+    fn language_server_fold_range(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Option<Range<Point>>> {
+        if self.folding_ranges.is_empty() {
+            return None;
+        }
+        let line_start = Point::new(buffer_row.0, 0);
+        let excerpt = self
+            .buffer_snapshot
+            .excerpt_containing(line_start..line_start)?;
+        let buffer = excerpt.buffer();
+        let folding_ranges = self.folding_ranges.get(&buffer.remote_id())?;
+
+        let buffer_offset =
+            excerpt.map_offset_to_buffer(line_start.to_offset(&self.buffer_snapshot));
+        let row = buffer.offset_to_point(buffer_offset).row;
+        let start_ix = folding_ranges
+            .partition_point(|range| buffer.summary_for_anchor::<Point>(&range.start).row < row);
+        let range = folding_ranges[start_ix..]
+            .iter()
+            .map(|range| {
+                buffer.summary_for_anchor::<Point>(&range.start)
+                    ..buffer.summary_for_anchor::<Point>(&range.end)
+            })
+            .take_while(|range| range.start.row == row)
+            .find(|range| range.end.row > row)
+            .map(|range| {
+                let start = excerpt.map_offset_from_buffer(buffer.point_to_offset(range.start));
+                let end = excerpt.map_offset_from_buffer(buffer.point_to_offset(range.end));
+                start.to_point(&self.buffer_snapshot)..end.to_point(&self.buffer_snapshot)
+            });
+        Some(range)
+    }
+
     pub fn foldable_range(
         &self,
         buffer_row: MultiBufferRow,
@@ -964,6 +1044,10 @@ impl DisplaySnapshot {
                 crease.range.to_point(&self.buffer_snapshot),
                 crease.placeholder.clone(),
             ))
+        } else if let Some(range) = self.language_server_fold_range(buffer_row) {
+            range
+                .filter(|_| !self.is_line_folded(buffer_row))
+                .map(|range| (range, self.fold_placeholder.clone()))
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -16,7 +16,7 @@ use std::{
 use sum_tree::{Bias, Cursor, SumTree, TreeMap};
 use text::{Patch, Rope};
 
-use super::Highlights;
+use super::{Highlights, SemanticHighlight};
 
 /// Decides where the [`Inlay`]s should be displayed.
 ///
@@ -220,6 +220,7 @@ pub struct InlayChunks<'a> {
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    semantic_highlights: Peekable<vec::IntoIter<(Range<InlayOffset>, &'a SemanticHighlight)>>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -263,6 +264,20 @@ impl<'a> Iterator for InlayChunks<'a> {
             }
         }
 
+        while self
+            .semantic_highlights
+            .next_if(|(range, _)| range.end <= self.output_offset)
+            .is_some()
+        {}
+        let (active_semantic_highlight, next_semantic_highlight_boundary) =
+            match self.semantic_highlights.peek() {
+                Some((range, highlight)) if range.start <= self.output_offset => {
+                    (Some(*highlight), range.end)
+                }
+                Some((range, _)) => (None, range.start),
+                None => (None, InlayOffset(usize::MAX)),
+            };
+
         let chunk = match self.transforms.item()? {
             Transform::Isomorphic(_) => {
                 let chunk = self
@@ -277,7 +292,8 @@ impl<'a> Iterator for InlayChunks<'a> {
                         .text
                         .len()
                         .min(self.transforms.end(&()).0 .0 - self.output_offset.0)
-                        .min(next_highlight_endpoint.0 - self.output_offset.0),
+                        .min(next_highlight_endpoint.0 - self.output_offset.0)
+                        .min(next_semantic_highlight_boundary.0 - self.output_offset.0),
                 );
 
                 chunk.text = suffix;
@@ -286,12 +302,20 @@ impl<'a> Iterator for InlayChunks<'a> {
                     text: prefix,
                     ..chunk.clone()
                 };
-                if !self.active_highlights.is_empty() {
-                    let mut highlight_style = HighlightStyle::default();
-                    for active_highlight in self.active_highlights.values() {
-                        highlight_style.highlight(*active_highlight);
+                let mut highlight_style = None;
+                if let Some(semantic_highlight) = active_semantic_highlight {
+                    if semantic_highlight.replaces_syntax {
+                        prefix.syntax_highlight_id = None;
                     }
-                    prefix.highlight_style = Some(highlight_style);
+                    highlight_style = Some(semantic_highlight.style);
+                }
+                for active_highlight in self.active_highlights.values() {
+                    highlight_style
+                        .get_or_insert_with(HighlightStyle::default)
+                        .highlight(*active_highlight);
+                }
+                if highlight_style.is_some() {
+                    prefix.highlight_style = highlight_style;
                 }
                 prefix
             }
@@ -1039,6 +1063,12 @@ impl InlaySnapshot {
             }
         }
         highlight_endpoints.sort();
+        let semantic_highlights = highlights
+            .semantic_highlights
+            .map(|semantic_highlights| {
+                self.semantic_highlights_in_range(semantic_highlights, &range)
+            })
+            .unwrap_or_default();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
 
@@ -1053,6 +1083,7 @@ impl InlaySnapshot {
             highlight_styles: highlights.styles,
             highlight_endpoints: highlight_endpoints.into_iter().peekable(),
             active_highlights: Default::default(),
+            semantic_highlights: semantic_highlights.into_iter().peekable(),
             highlights,
             snapshot: self,
         }
@@ -1116,6 +1147,26 @@ impl InlaySnapshot {
         }
     }
 
+    fn semantic_highlights_in_range<'a>(
+        &self,
+        semantic_highlights: &'a [SemanticHighlight],
+        range: &Range<InlayOffset>,
+    ) -> Vec<(Range<InlayOffset>, &'a SemanticHighlight)> {
+        let start = self.buffer.anchor_after(self.to_buffer_offset(range.start));
+        let end = self.buffer.anchor_before(self.to_buffer_offset(range.end));
+        let start_ix = semantic_highlights
+            .partition_point(|highlight| highlight.range.end.cmp(&start, &self.buffer).is_le());
+        semantic_highlights[start_ix..]
+            .iter()
+            .take_while(|highlight| highlight.range.start.cmp(&end, &self.buffer).is_lt())
+            .map(|highlight| {
+                let start = self.to_inlay_offset(highlight.range.start.to_offset(&self.buffer));
+                let end = self.to_inlay_offset(highlight.range.end.to_offset(&self.buffer));
+                (start..end, highlight)
+            })
+            .collect()
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
@@ -0,0 +1,94 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use gpui::{Model, Task, ViewContext};
+use language::Buffer;
+use project::DocumentLink;
+use text::BufferId;
+use util::ResultExt;
+
+use crate::Editor;
+
+/// The language server document links of the buffers in an editor.
+#[derive(Default)]
+pub(super) struct DocumentLinksState {
+    pub(super) links: HashMap<BufferId, Vec<DocumentLink>>,
+}
+
+impl DocumentLinksState {
+    /// Returns the innermost document link of a buffer that contains the given position.
+    pub(super) fn link_at(
+        &self,
+        buffer: &text::BufferSnapshot,
+        position: text::Anchor,
+    ) -> Option<&DocumentLink> {
+        self.links
+            .get(&buffer.remote_id())?
+            .iter()
+            .filter(|link| {
+                link.range.start.cmp(&position, buffer).is_le()
+                    && link.range.end.cmp(&position, buffer).is_ge()
+            })
+            .last()
+    }
+}
+
+/// A document link whose target is known.
+pub(super) struct ResolvedDocumentLink {
+    pub range: Range<text::Anchor>,
+    pub target: lsp::Url,
+    pub tooltip: Option<String>,
+}
+
+/// Resolves the innermost document link of a buffer that contains the given position, once
+/// it's hovered or clicked. Links that arrived without a target are resolved by their
+/// language server, and stored so that they're only resolved once.
+pub(super) fn resolve_document_link_at(
+    editor: &Editor,
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Option<ResolvedDocumentLink>> {
+    let Some(link) = editor
+        .document_links
+        .link_at(buffer.read(cx), position)
+        .cloned()
+    else {
+        return Task::ready(None);
+    };
+    if link.lsp_link.target.is_some() {
+        return Task::ready(resolved_document_link(link));
+    }
+    let Some(project) = editor.project.clone() else {
+        return Task::ready(None);
+    };
+
+    let buffer_id = buffer.read(cx).remote_id();
+    let resolve = project.update(cx, |project, cx| {
+        project.resolve_document_link(buffer, link.clone(), cx)
+    });
+    cx.spawn(|editor, mut cx| async move {
+        let resolved = resolve.await.log_err()?;
+        editor
+            .update(&mut cx, |editor, _| {
+                let stored = editor
+                    .document_links
+                    .links
+                    .get_mut(&buffer_id)?
+                    .iter_mut()
+                    .find(|stored| **stored == link)?;
+                *stored = resolved.clone();
+                Some(())
+            })
+            .ok();
+        resolved_document_link(resolved)
+    })
+}
+
+fn resolved_document_link(link: DocumentLink) -> Option<ResolvedDocumentLink> {
+    Some(ResolvedDocumentLink {
+        range: link.range,
+        target: link.lsp_link.target?,
+        tooltip: link.lsp_link.tooltip,
+    })
+}
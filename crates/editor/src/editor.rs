@@ -15,11 +15,14 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod code_lens;
 mod debounced_delay;
 pub mod display_map;
+mod document_links;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -30,12 +33,15 @@ mod inlay_hint_cache;
 mod inline_completion_provider;
 pub mod items;
 mod linked_editing_ranges;
+mod lsp_data;
+mod merge_conflicts;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -67,6 +73,7 @@ use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
 use git::diff_hunk_to_display;
+use git::repository::{GitRepository, RepoPath};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, size, uniform_list, Action, AnyElement,
     AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardItem,
@@ -95,6 +102,7 @@ use language::{
 };
 use language::{point_to_lsp, BufferRow, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
+use lsp_data::{LspDataKind, LspDataRefreshReason};
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
@@ -255,6 +263,7 @@ impl InlayId {
 }
 
 enum DiffRowHighlight {}
+enum DiffWordHighlight {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
 enum InputComposition {}
@@ -507,6 +516,12 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    semantic_tokens: semantic_tokens::SemanticTokensState,
+    code_lens: code_lens::CodeLensState,
+    folding_ranges: folding_ranges::FoldingRangesState,
+    document_links: document_links::DocumentLinksState,
+    merge_conflicts: merge_conflicts::MergeConflictsState,
+    lsp_data: lsp_data::LspDataState,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -1728,6 +1743,21 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::RefreshSemanticTokens = event {
+                        editor.refresh_lsp_data(
+                            LspDataRefreshReason::Invalidated(&[LspDataKind::SemanticTokens]),
+                            cx,
+                        );
+                    } else if let project::Event::LanguageServerAdded(_) = event {
+                        editor.refresh_lsp_data(
+                            LspDataRefreshReason::Invalidated(LspDataKind::ALL),
+                            cx,
+                        );
+                    } else if let project::Event::RefreshCodeLens = event {
+                        editor.refresh_lsp_data(
+                            LspDataRefreshReason::Invalidated(&[LspDataKind::CodeLens]),
+                            cx,
+                        );
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1824,6 +1854,12 @@ impl Editor {
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            semantic_tokens: Default::default(),
+            code_lens: Default::default(),
+            folding_ranges: Default::default(),
+            document_links: Default::default(),
+            merge_conflicts: Default::default(),
+            lsp_data: Default::default(),
             pending_rename: Default::default(),
             searchable: true,
             cursor_shape: Default::default(),
@@ -1897,6 +1933,7 @@ impl Editor {
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
         this._subscriptions.extend(project_subscriptions);
+        merge_conflicts::refresh_merge_conflicts(&mut this, false, cx);
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
@@ -5918,6 +5955,118 @@ impl Editor {
         }
     }
 
+    pub fn stage_selected_hunks(&mut self, _: &StageSelectedHunks, cx: &mut ViewContext<Self>) {
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let hunks =
+            hunks_for_selections(&multi_buffer_snapshot, &self.selections.disjoint_anchors());
+        self.stage_hunks(hunks, cx);
+    }
+
+    /// Writes the given hunks to the git index, leaving the rest of each buffer's index
+    /// text as it was.
+    pub fn stage_hunks(
+        &mut self,
+        hunks: impl IntoIterator<Item = DiffHunk<MultiBufferRow>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let index_updates = match self.index_texts_with_hunks(hunks, cx) {
+            Ok(index_updates) => index_updates,
+            Err(err) => {
+                let message = format!("Failed to stage hunks: {err}");
+
+                Err::<(), anyhow::Error>(err).log_err();
+
+                if let Some(workspace) = self.workspace() {
+                    workspace.update(cx, |workspace, cx| {
+                        struct StageHunks;
+
+                        workspace.show_toast(
+                            Toast::new(NotificationId::unique::<StageHunks>(), message),
+                            cx,
+                        )
+                    })
+                }
+                return;
+            }
+        };
+        if index_updates.is_empty() {
+            return;
+        }
+
+        cx.background_executor()
+            .spawn(async move {
+                for (repo, repo_path, index_text) in index_updates {
+                    repo.set_index_text(&repo_path, index_text)?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Returns the index text of each buffer with the given hunks applied to it, along with
+    /// the repository and the path to write it to.
+    fn index_texts_with_hunks(
+        &self,
+        hunks: impl IntoIterator<Item = DiffHunk<MultiBufferRow>>,
+        cx: &AppContext,
+    ) -> Result<Vec<(Arc<dyn GitRepository>, RepoPath, String)>> {
+        let project = self
+            .project
+            .as_ref()
+            .context("the editor has no project")?
+            .read(cx);
+        if project.is_remote() || project.is_ssh() {
+            return Err(anyhow!("staging hunks isn't supported in remote projects"));
+        }
+        let mut hunks_by_buffer = HashMap::<BufferId, Vec<DiffHunk<MultiBufferRow>>>::default();
+        for hunk in hunks {
+            hunks_by_buffer
+                .entry(hunk.buffer_id)
+                .or_default()
+                .push(hunk);
+        }
+
+        let mut index_updates = Vec::new();
+        for (buffer_id, mut hunks) in hunks_by_buffer {
+            // Hunks of buffers diffed against another revision don't apply to the index.
+            if let Some(revision) = project.diff_base_revision(buffer_id, cx) {
+                return Err(anyhow!(
+                    "the changes are shown against {revision} instead of the index"
+                ));
+            }
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+                continue;
+            };
+            let buffer = buffer.read(cx);
+            let (Some(diff_base), Some(project_path)) =
+                (buffer.diff_base(), buffer.project_path(cx))
+            else {
+                continue;
+            };
+            let Some((repo, repo_path)) = project.repository_for_project_path(&project_path, cx)
+            else {
+                continue;
+            };
+
+            hunks.sort_by_key(|hunk| hunk.diff_base_byte_range.start);
+            let mut index_text = String::with_capacity(diff_base.len());
+            let mut diff_base_offset = 0;
+            for hunk in hunks {
+                if hunk.diff_base_byte_range.start < diff_base_offset {
+                    continue;
+                }
+                index_text.extend(
+                    diff_base.chunks_in_range(diff_base_offset..hunk.diff_base_byte_range.start),
+                );
+                index_text.extend(buffer.text_for_range(hunk.buffer_range.clone()));
+                diff_base_offset = hunk.diff_base_byte_range.end;
+            }
+            index_text.extend(diff_base.chunks_in_range(diff_base_offset..diff_base.len()));
+            index_updates.push((repo, repo_path, index_text));
+        }
+        Ok(index_updates)
+    }
+
     pub fn open_active_item_in_terminal(&mut self, _: &OpenInTerminal, cx: &mut ViewContext<Self>) {
         if let Some(working_directory) = self.active_excerpt(cx).and_then(|(_, buffer, _)| {
             let project_path = buffer.read(cx).project_path(cx)?;
@@ -9073,6 +9222,22 @@ impl Editor {
                     cx.open_url(&url);
                     Task::ready(Ok(None))
                 }
+                HoverLink::File(path) => {
+                    let Some(workspace) = self.workspace() else {
+                        return Task::ready(Ok(false));
+                    };
+                    let open_path = workspace.update(cx, |workspace, cx| {
+                        if split {
+                            workspace.split_abs_path(path, false, cx)
+                        } else {
+                            workspace.open_abs_path(path, false, cx)
+                        }
+                    });
+                    return cx.spawn(|_, _| async move {
+                        open_path.await?;
+                        Ok(true)
+                    });
+                }
             };
             cx.spawn(|editor, mut cx| async move {
                 let target = target_task.await.context("target resolution task")?;
@@ -9171,7 +9336,7 @@ impl Editor {
                                     )
                                 }),
                                 HoverLink::InlayHint(_, _) => None,
-                                HoverLink::Url(_) => None,
+                                HoverLink::Url(_) | HoverLink::File(_) => None,
                             })
                             .unwrap_or(tab_kind.to_string());
                         let location_tasks = definitions
@@ -9181,7 +9346,7 @@ impl Editor {
                                 HoverLink::InlayHint(lsp_location, server_id) => {
                                     editor.compute_target_location(lsp_location, server_id, cx)
                                 }
-                                HoverLink::Url(_) => Task::ready(Ok(None)),
+                                HoverLink::Url(_) | HoverLink::File(_) => Task::ready(Ok(None)),
                             })
                             .collect::<Vec<_>>();
                         (title, location_tasks, editor.workspace().clone())
@@ -11239,10 +11404,14 @@ impl Editor {
                     }
                 }
 
+                self.refresh_lsp_data(LspDataRefreshReason::BufferEdited, cx);
+                merge_conflicts::refresh_merge_conflicts(self, true, cx);
                 let Some(project) = &self.project else { return };
-                let telemetry = project.read(cx).client().telemetry().clone();
+                let client = project.read(cx).client();
                 refresh_linked_ranges(self, cx);
-                telemetry.log_edit_event("editor");
+                if let Some(client) = client {
+                    client.telemetry().log_edit_event("editor");
+                }
             }
             multi_buffer::Event::ExcerptsAdded {
                 buffer,
@@ -11256,9 +11425,13 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_lsp_data(LspDataRefreshReason::ExcerptsChanged, cx);
+                merge_conflicts::refresh_merge_conflicts(self, true, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_lsp_data(LspDataRefreshReason::ExcerptsChanged, cx);
+                merge_conflicts::refresh_merge_conflicts(self, true, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -11274,6 +11447,7 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                self.refresh_lsp_data(LspDataRefreshReason::Invalidated(LspDataKind::ALL), cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -11308,6 +11482,7 @@ impl Editor {
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.tasks_update_task = Some(self.refresh_runnables(cx));
         self.refresh_inline_completion(true, cx);
+        self.refresh_lsp_data(LspDataRefreshReason::SettingsChanged, cx);
         self.refresh_inlay_hints(
             InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
                 self.selections.newest_anchor().head(),
@@ -11503,6 +11678,9 @@ impl Editor {
         }
 
         let Some(project) = &self.project else { return };
+        let Some(client) = project.read(cx).client() else {
+            return;
+        };
 
         // If None, we are in a file without an extension
         let file = self
@@ -11530,8 +11708,7 @@ impl Editor {
             .settings_at(0, cx)
             .show_inline_completions;
 
-        let telemetry = project.read(cx).client().telemetry().clone();
-        telemetry.report_editor_event(
+        client.telemetry().report_editor_event(
             file_extension,
             vim_mode,
             operation,
@@ -12318,7 +12495,7 @@ impl EditorSnapshot {
                 cx,
             ))
         } else if folded
-            || (self.starts_indent(buffer_row) && (row_contains_cursor || self.gutter_hovered))
+            || (self.starts_fold(buffer_row) && (row_contains_cursor || self.gutter_hovered))
         {
             Some(
                 Disclosure::new(("indent-fold-indicator", buffer_row.0), !folded)
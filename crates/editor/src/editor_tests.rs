@@ -10800,6 +10800,58 @@ async fn test_toggle_hunk_diff(executor: BackgroundExecutor, cx: &mut gpui::Test
     });
 }
 
+#[gpui::test]
+async fn test_stage_one_of_several_hunks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\nTWO\nthree\nfour\nFIVE\n",
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(
+            Path::new("a.txt"),
+            "one\ntwo\nthree\nfour\nfive\n".to_string(),
+        )],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let editor = cx.add_window(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx));
+    cx.executor().run_until_parked();
+
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(1, 1)..Point::new(1, 1)])
+        });
+        editor.stage_selected_hunks(&StageSelectedHunks, cx);
+    });
+    cx.executor().run_until_parked();
+
+    let mut index_text = None;
+    fs.with_git_state(Path::new("/dir/.git"), false, |state| {
+        index_text = state.index_contents.get(Path::new("a.txt")).cloned();
+    });
+    assert_eq!(
+        index_text.as_deref(),
+        Some("one\nTWO\nthree\nfour\nfive\n"),
+        "only the hunk under the cursor should be staged"
+    );
+}
+
 #[gpui::test]
 async fn test_toggled_diff_base_change(
     executor: BackgroundExecutor,
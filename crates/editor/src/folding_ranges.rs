@@ -0,0 +1,96 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::{HashMap, HashSet};
+use gpui::ViewContext;
+use language::language_settings::{language_settings, FoldingRangeKind};
+use project::FoldingRange;
+use text::BufferId;
+
+use crate::Editor;
+
+/// The language server folding ranges of the buffers in an editor.
+#[derive(Default)]
+pub(super) struct FoldingRangesState {
+    pub(super) ranges: HashMap<BufferId, Arc<[Range<text::Anchor>]>>,
+    /// The buffers whose folded-by-default ranges have already been folded.
+    folded_by_default: HashSet<BufferId>,
+}
+
+/// Stores the folding ranges of a buffer, which then determine what can be folded in it,
+/// or drops them when its language server doesn't provide any. The first time a buffer has
+/// ranges, the ones of the kinds configured to be folded by default are folded.
+pub(super) fn set_folding_ranges(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    ranges: Option<Vec<FoldingRange>>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(ranges) = ranges else {
+        editor.folding_ranges.ranges.remove(&buffer_id);
+        return;
+    };
+    if editor.folding_ranges.folded_by_default.insert(buffer_id) {
+        fold_ranges_by_default(editor, buffer_id, &ranges, cx);
+    }
+    editor.folding_ranges.ranges.insert(
+        buffer_id,
+        ranges.into_iter().map(|range| range.range).collect(),
+    );
+}
+
+pub(super) fn update_folding_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let ranges = editor.folding_ranges.ranges.clone();
+    editor
+        .display_map
+        .update(cx, |display_map, _| display_map.set_folding_ranges(ranges));
+    cx.notify();
+}
+
+/// Folds the ranges of a buffer whose kinds are configured to be folded by default, in
+/// every excerpt of the buffer.
+fn fold_ranges_by_default(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    ranges: &[FoldingRange],
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(buffer) = editor.buffer.read(cx).buffer(buffer_id) else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    let kinds = language_settings(buffer.language(), buffer.file(), cx)
+        .folding_ranges
+        .folded_by_default
+        .clone();
+    if kinds.is_empty() {
+        return;
+    }
+
+    let display_snapshot = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+    let snapshot = &display_snapshot.buffer_snapshot;
+    let mut fold_ranges = Vec::new();
+    for (excerpt_id, buffer_snapshot, _) in snapshot.excerpts() {
+        if buffer_snapshot.remote_id() != buffer_id {
+            continue;
+        }
+        for range in ranges {
+            let folded_by_default = range.kind.as_ref().map_or(false, |kind| {
+                kinds.iter().any(|configured_kind| match configured_kind {
+                    FoldingRangeKind::Comment => *kind == lsp::FoldingRangeKind::Comment,
+                    FoldingRangeKind::Imports => *kind == lsp::FoldingRangeKind::Imports,
+                    FoldingRangeKind::Region => *kind == lsp::FoldingRangeKind::Region,
+                })
+            });
+            if !folded_by_default {
+                continue;
+            }
+            if let Some((start, end)) = snapshot
+                .anchor_in_excerpt(excerpt_id, range.range.start)
+                .zip(snapshot.anchor_in_excerpt(excerpt_id, range.range.end))
+            {
+                fold_ranges.push((start..end, display_snapshot.fold_placeholder.clone()));
+            }
+        }
+    }
+    editor.fold_ranges(fold_ranges, false, cx);
+}
@@ -1,4 +1,5 @@
 use crate::{
+    document_links,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
     Anchor, Editor, EditorSnapshot, FindAllReferences, GoToDefinition, GoToTypeDefinition, InlayId,
@@ -9,10 +10,13 @@ use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
-    ResolveState,
+    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink, Project,
+    ProjectPath, ResolveState,
+};
+use std::{
+    ops::Range,
+    path::{Component, Path, PathBuf},
 };
-use std::ops::Range;
 use theme::ActiveTheme as _;
 use util::{maybe, ResultExt, TryFutureExt};
 
@@ -63,10 +67,22 @@ impl RangeInEditor {
 #[derive(Debug, Clone)]
 pub enum HoverLink {
     Url(String),
+    File(PathBuf),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
 }
 
+impl HoverLink {
+    /// The link to a document link's target, which is opened in the workspace when it is a
+    /// file and in the browser otherwise.
+    pub(crate) fn for_target(target: &lsp::Url) -> Self {
+        match target.to_file_path() {
+            Ok(path) => Self::File(path),
+            Err(()) => Self::Url(target.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct InlayHighlight {
     pub inlay: InlayId,
@@ -486,7 +502,7 @@ pub fn show_link_definition(
         || hovered_link_state
             .links
             .first()
-            .is_some_and(|d| matches!(d, HoverLink::Url(_)));
+            .is_some_and(|d| matches!(d, HoverLink::Url(_) | HoverLink::File(_)));
 
     if same_kind {
         if is_cached && (&hovered_link_state.last_trigger_point == &trigger_point)
@@ -504,13 +520,31 @@ pub fn show_link_definition(
         editor.hide_hovered_link(cx)
     }
     let project = editor.project.clone();
+    let document_link = match &trigger_point {
+        TriggerPoint::Text(_) => {
+            document_links::resolve_document_link_at(editor, &buffer, buffer_position, cx)
+        }
+        TriggerPoint::InlayHint(..) => Task::ready(None),
+    };
 
     let snapshot = snapshot.buffer_snapshot.clone();
     hovered_link_state.task = Some(cx.spawn(|this, mut cx| {
         async move {
+            let link_range = |range: Range<text::Anchor>| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                Some(RangeInEditor::Text(start..end))
+            };
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some(document_link) = document_link.await {
+                        Some((
+                            link_range(document_link.range),
+                            vec![HoverLink::for_target(&document_link.target)],
+                        ))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(&mut cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -521,38 +555,46 @@ pub fn show_link_definition(
                             (range, vec![HoverLink::Url(url)])
                         })
                         .ok()
-                    } else if let Some(project) = project {
+                    } else {
                         // query the LSP for definition info
-                        project
-                            .update(&mut cx, |project, cx| match preferred_kind {
-                                LinkDefinitionKind::Symbol => {
-                                    project.definition(&buffer, buffer_position, cx)
-                                }
+                        let definition_result = match &project {
+                            Some(project) => project
+                                .update(&mut cx, |project, cx| match preferred_kind {
+                                    LinkDefinitionKind::Symbol => {
+                                        project.definition(&buffer, buffer_position, cx)
+                                    }
 
-                                LinkDefinitionKind::Type => {
-                                    project.type_definition(&buffer, buffer_position, cx)
-                                }
-                            })?
-                            .await
-                            .ok()
-                            .map(|definition_result| {
-                                (
-                                    definition_result.iter().find_map(|link| {
-                                        link.origin.as_ref().and_then(|origin| {
-                                            let start = snapshot.anchor_in_excerpt(
-                                                excerpt_id,
-                                                origin.range.start,
-                                            )?;
-                                            let end = snapshot
-                                                .anchor_in_excerpt(excerpt_id, origin.range.end)?;
-                                            Some(RangeInEditor::Text(start..end))
-                                        })
-                                    }),
-                                    definition_result.into_iter().map(HoverLink::Text).collect(),
-                                )
-                            })
-                    } else {
-                        None
+                                    LinkDefinitionKind::Type => {
+                                        project.type_definition(&buffer, buffer_position, cx)
+                                    }
+                                })?
+                                .await
+                                .ok()
+                                .filter(|definition_result| !definition_result.is_empty()),
+                            None => None,
+                        };
+                        if let Some(definition_result) = definition_result {
+                            Some((
+                                definition_result.iter().find_map(|link| {
+                                    link.origin.as_ref().and_then(|origin| {
+                                        let start = snapshot
+                                            .anchor_in_excerpt(excerpt_id, origin.range.start)?;
+                                        let end = snapshot
+                                            .anchor_in_excerpt(excerpt_id, origin.range.end)?;
+                                        Some(RangeInEditor::Text(start..end))
+                                    })
+                                }),
+                                definition_result.into_iter().map(HoverLink::Text).collect(),
+                            ))
+                        } else if let Some((path_range, path)) =
+                            find_file(&buffer, project.as_ref(), buffer_position, &mut cx)
+                        {
+                            // Paths that the language server doesn't know about still open
+                            // the files they name.
+                            Some((link_range(path_range), vec![HoverLink::File(path)]))
+                        } else {
+                            None
+                        }
                     }
                 }
                 TriggerPoint::InlayHint(highlight, lsp_location, server_id) => Some((
@@ -686,6 +728,105 @@ pub(crate) fn find_url(
     None
 }
 
+/// Finds the path of a project file around the given position, written either relative to
+/// the buffer's directory or to a worktree root, or as an absolute path.
+pub(crate) fn find_file(
+    buffer: &Model<language::Buffer>,
+    project: Option<&Model<Project>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, PathBuf)> {
+    const LIMIT: usize = 512;
+
+    let project = project?;
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let is_delimiter = |ch: char| {
+        ch.is_whitespace()
+            || matches!(
+                ch,
+                '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | ',' | ';'
+            )
+    };
+
+    let offset = position.to_offset(&snapshot);
+    let mut token_start = offset;
+    for ch in snapshot.reversed_chars_at(offset).take(LIMIT) {
+        if is_delimiter(ch) {
+            break;
+        }
+        token_start -= ch.len_utf8();
+    }
+    let mut token_end = offset;
+    for ch in snapshot.chars_at(offset).take(LIMIT) {
+        if is_delimiter(ch) {
+            break;
+        }
+        token_end += ch.len_utf8();
+    }
+
+    let token = snapshot
+        .text_for_range(token_start..token_end)
+        .collect::<String>();
+    // Paths are often followed by punctuation, and words without a separator or an
+    // extension are much more likely to be identifiers than file names.
+    let token = token.trim_end_matches(['.', ':']);
+    if !(token.contains('/') || token.contains('.')) || token.contains("://") {
+        return None;
+    }
+    let path = Path::new(token);
+
+    let abs_path = buffer
+        .update(cx, |buffer, cx| {
+            let project = project.read(cx);
+            let project_path = if path.is_absolute() {
+                let (worktree, relative_path) = project.find_worktree(path, cx)?;
+                ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: relative_path.into(),
+                }
+            } else {
+                project::File::from_dyn(buffer.file())
+                    .and_then(|file| {
+                        let path = normalize_relative_path(&file.path.parent()?.join(path))?;
+                        Some(ProjectPath {
+                            worktree_id: file.worktree_id(cx),
+                            path: path.into(),
+                        })
+                    })
+                    .filter(|project_path| project.entry_for_path(project_path, cx).is_some())
+                    .or_else(|| project.find_project_path(path, cx))?
+            };
+            project
+                .entry_for_path(&project_path, cx)
+                .filter(|entry| entry.is_file())?;
+            project.absolute_path(&project_path, cx)
+        })
+        .ok()??;
+
+    let range =
+        snapshot.anchor_before(token_start)..snapshot.anchor_after(token_start + token.len());
+    Some((range, abs_path))
+}
+
+/// Resolves the `.` and `..` components of a relative path, or returns `None` if it leaves
+/// the directory that it is relative to.
+fn normalize_relative_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(name) => normalized.push(name),
+            Component::Prefix(_) | Component::RootDir => return None,
+        }
+    }
+    Some(normalized)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -694,7 +835,7 @@ mod tests {
         editor_tests::init_test,
         inlay_hint_cache::tests::{cached_hint_labels, visible_hint_labels},
         test::editor_lsp_test_context::EditorLspTestContext,
-        DisplayPoint,
+        DisplayPoint, LspDataKind, LspDataRefreshReason,
     };
     use futures::StreamExt;
     use gpui::Modifiers;
@@ -1268,4 +1409,98 @@ mod tests {
         cx.simulate_click(screen_coord, Modifiers::secondary_key());
         assert_eq!(cx.opened_url(), Some("https://zed.dev/releases".into()));
     }
+
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            use serde::Deserialize;ˇ
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            use «serde»::Deserialize;
+        "});
+        let mut requests = cx.handle_request::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: Some("https://docs.rs/serde".parse().unwrap()),
+                    tooltip: None,
+                    data: None,
+                }]))
+            },
+        );
+        cx.update_editor(|editor, cx| {
+            editor.refresh_lsp_data(
+                LspDataRefreshReason::Invalidated(&[LspDataKind::DocumentLinks]),
+                cx,
+            )
+        });
+        requests.next().await;
+        cx.background_executor.run_until_parked();
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            use serˇde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+    }
+
+    #[gpui::test]
+    async fn test_file_paths(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let fs = cx.update_workspace(|workspace, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file("/root/dir/other.rs", String::new())
+            .await;
+        cx.background_executor.run_until_parked();
+
+        cx.set_state(indoc! {"
+            // See \"./other.rs\" and missing.rs.ˇ
+        "});
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            // See \"./otˇher.rs\" and missing.rs.
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See \"«./other.rsˇ»\" and missing.rs.
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        cx.background_executor.run_until_parked();
+        let opened_path = cx.update_workspace(|workspace, cx| {
+            workspace
+                .active_item(cx)
+                .and_then(|item| item.project_path(cx))
+                .map(|project_path| project_path.path)
+        });
+        assert_eq!(opened_path.as_deref(), Some(Path::new("dir/other.rs")));
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     display_map::{InlayOffset, ToDisplayPoint},
+    document_links::{self, ResolvedDocumentLink},
     hover_links::{InlayHighlight, RangeInEditor},
     scroll::ScrollAmount,
     Anchor, AnchorRangeExt, DisplayPoint, DisplayRow, Editor, EditorSettings, EditorSnapshot,
@@ -15,14 +16,14 @@ use language::{DiagnosticEntry, Language, LanguageRegistry};
 use lsp::DiagnosticSeverity;
 use markdown::{Markdown, MarkdownStyle};
 use multi_buffer::ToOffset;
-use project::{HoverBlock, InlayHintLabelPart};
+use project::{HoverBlock, HoverBlockKind, InlayHintLabelPart};
 use settings::Settings;
 use std::rc::Rc;
 use std::{borrow::Cow, cell::RefCell};
 use std::{ops::Range, sync::Arc, time::Duration};
 use theme::ThemeSettings;
 use ui::{prelude::*, window_is_transparent};
-use util::TryFutureExt;
+use util::{maybe, TryFutureExt};
 pub const HOVER_DELAY_MILLIS: u64 = 350;
 pub const HOVER_REQUEST_DELAY_MILLIS: u64 = 200;
 
@@ -268,6 +269,9 @@ fn show_hover(
                     project.hover(&buffer, buffer_position, cx)
                 })
             })?;
+            let document_link = this.update(&mut cx, |editor, cx| {
+                document_links::resolve_document_link_at(editor, &buffer, buffer_position, cx)
+            })?;
 
             if let Some(delay) = delay {
                 delay.await;
@@ -415,6 +419,30 @@ fn show_hover(
                     },
                 ));
             }
+            if let Some(document_link) = document_link.await {
+                let range = maybe!({
+                    let start = snapshot
+                        .buffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, document_link.range.start)?;
+                    let end = snapshot
+                        .buffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, document_link.range.end)?;
+                    Some(start..end)
+                })
+                .unwrap_or_else(|| anchor..anchor);
+                let blocks = [document_link_hover_block(&document_link)];
+                let parsed_content = parse_blocks(&blocks, &language_registry, None, &mut cx).await;
+                info_popover_tasks.push((
+                    range.clone(),
+                    InfoPopover {
+                        symbol_range: RangeInEditor::Text(range),
+                        parsed_content,
+                        scroll_handle: ScrollHandle::new(),
+                        keyboard_grace: Rc::new(RefCell::new(ignore_timeout)),
+                        anchor: Some(anchor),
+                    },
+                ));
+            }
             for (highlight_range, info_popover) in info_popover_tasks {
                 hover_highlights.push(highlight_range);
                 info_popovers.push(info_popover);
@@ -445,6 +473,31 @@ fn show_hover(
     editor.hover_state.info_task = Some(task);
 }
 
+/// Describes the target of a document link, which can be followed by clicking it with the
+/// secondary modifier held.
+fn document_link_hover_block(document_link: &ResolvedDocumentLink) -> HoverBlock {
+    let target = match document_link.target.to_file_path() {
+        Ok(path) => path.display().to_string(),
+        Err(()) => document_link.target.to_string(),
+    };
+    let modifier = if cfg!(target_os = "macos") {
+        "cmd"
+    } else {
+        "ctrl"
+    };
+    let mut text = format!(
+        "[{target}]({}) ({modifier}-click to follow)",
+        document_link.target
+    );
+    if let Some(tooltip) = &document_link.tooltip {
+        text = format!("{tooltip}\n\n{text}");
+    }
+    HoverBlock {
+        text,
+        kind: HoverBlockKind::Markdown,
+    }
+}
+
 fn same_info_hover(editor: &Editor, snapshot: &EditorSnapshot, anchor: Anchor) -> bool {
     editor
         .hover_state
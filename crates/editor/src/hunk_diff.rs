@@ -23,9 +23,9 @@ use crate::{
     git::{diff_hunk_to_display, DisplayDiffHunk},
     hunk_status, hunks_for_selections,
     mouse_context_menu::MouseContextMenu,
-    BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, DiffRowHighlight, Editor,
-    EditorElement, EditorSnapshot, ExpandAllHunkDiffs, RangeToAnchorExt, RevertSelectedHunks,
-    ToDisplayPoint, ToggleHunkDiff,
+    BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, DiffRowHighlight,
+    DiffWordHighlight, Editor, EditorElement, EditorSnapshot, ExpandAllHunkDiffs, RangeToAnchorExt,
+    RevertSelectedHunks, StageSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
 };
 
 #[derive(Debug, Clone)]
@@ -63,6 +63,8 @@ pub(super) struct ExpandedHunk {
     pub diff_base_byte_range: Range<usize>,
     pub status: DiffHunkStatus,
     pub folded: bool,
+    /// The words that changed within a modified hunk.
+    pub word_ranges: Arc<[Range<Anchor>]>,
 }
 
 impl Editor {
@@ -139,6 +141,18 @@ impl Editor {
                             }
                         }
                     })
+                    .entry("Stage Hunk", Some(StageSelectedHunks.boxed_clone()), {
+                        let editor = editor_handle.clone();
+                        let hunk = hovered_hunk.clone();
+                        move |cx| {
+                            editor.update(cx, |editor, cx| {
+                                let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+                                if let Some(hunk) = to_diff_hunk(&hunk, &multi_buffer_snapshot) {
+                                    editor.stage_hunks(Some(hunk), cx);
+                                }
+                            });
+                        }
+                    })
                     .entry("Revert File", None, {
                         let editor = editor_handle.clone();
                         move |cx| {
@@ -319,6 +333,7 @@ impl Editor {
                         );
                     }
                     editor.remove_blocks(blocks_to_remove, None, cx);
+                    editor.refresh_diff_word_highlights(cx);
                     for hunk in hunks_to_expand {
                         editor.expand_diff_hunk(None, &hunk, cx);
                     }
@@ -349,7 +364,7 @@ impl Editor {
 
         let buffer = self.buffer().clone();
         let snapshot = self.snapshot(cx);
-        let (diff_base_buffer, deleted_text_lines) = buffer.update(cx, |buffer, cx| {
+        let (diff_base_buffer, deleted_text_lines, diff) = buffer.update(cx, |buffer, cx| {
             let hunk = buffer_diff_hunk(&snapshot.buffer_snapshot, multi_buffer_row_range.clone())?;
             let mut buffer_ranges = buffer.range_to_buffer_ranges(multi_buffer_row_range, cx);
             if buffer_ranges.len() == 1 {
@@ -366,11 +381,22 @@ impl Editor {
                     let line_count = diff_end_row - diff_start_row;
                     line_count
                 })?;
-                Some((diff_base_buffer, deleted_text_lines))
+                Some((diff_base_buffer, deleted_text_lines, hunk))
             } else {
                 None
             }
         })?;
+        let word_ranges = diff
+            .buffer_word_ranges
+            .iter()
+            .filter_map(|range| {
+                Some(
+                    multi_buffer_snapshot.anchor_in_excerpt(hunk_start.excerpt_id, range.start)?
+                        ..multi_buffer_snapshot
+                            .anchor_in_excerpt(hunk_start.excerpt_id, range.end)?,
+                )
+            })
+            .collect::<Arc<[_]>>();
 
         let block_insert_index = match self.expanded_hunks.hunks.binary_search_by(|probe| {
             probe
@@ -384,7 +410,7 @@ impl Editor {
 
         let block = match hunk.status {
             DiffHunkStatus::Removed => {
-                self.insert_deleted_text_block(diff_base_buffer, deleted_text_lines, &hunk, cx)
+                self.insert_deleted_text_block(diff_base_buffer, deleted_text_lines, &[], &hunk, cx)
             }
             DiffHunkStatus::Added => {
                 self.highlight_rows::<DiffRowHighlight>(
@@ -402,7 +428,13 @@ impl Editor {
                     false,
                     cx,
                 );
-                self.insert_deleted_text_block(diff_base_buffer, deleted_text_lines, &hunk, cx)
+                self.insert_deleted_text_block(
+                    diff_base_buffer,
+                    deleted_text_lines,
+                    &diff.diff_base_word_ranges,
+                    &hunk,
+                    cx,
+                )
             }
         };
         self.expanded_hunks.hunks.insert(
@@ -413,22 +445,55 @@ impl Editor {
                 status: hunk.status,
                 folded: false,
                 diff_base_byte_range: hunk.diff_base_byte_range.clone(),
+                word_ranges,
             },
         );
+        self.refresh_diff_word_highlights(cx);
 
         Some(())
     }
 
+    /// Highlights the changed words of the expanded hunks that aren't folded, in the text and
+    /// in the gutter.
+    fn refresh_diff_word_highlights(&mut self, cx: &mut ViewContext<'_, Self>) {
+        let word_ranges = self
+            .expanded_hunks
+            .hunks(false)
+            .flat_map(|hunk| hunk.word_ranges.iter().cloned())
+            .collect::<Vec<_>>();
+        if word_ranges.is_empty() {
+            self.clear_background_highlights::<DiffWordHighlight>(cx);
+            self.clear_gutter_highlights::<DiffWordHighlight>(cx);
+        } else {
+            self.highlight_background::<DiffWordHighlight>(
+                &word_ranges,
+                |colors| colors.editor_diff_word_added_background,
+                cx,
+            );
+            self.highlight_gutter::<DiffWordHighlight>(
+                &word_ranges,
+                |cx| cx.theme().colors().editor_diff_word_added_background,
+                cx,
+            );
+        }
+    }
+
     fn insert_deleted_text_block(
         &mut self,
         diff_base_buffer: Model<Buffer>,
         deleted_text_height: u32,
+        diff_base_word_ranges: &[Range<usize>],
         hunk: &HoveredHunk,
         cx: &mut ViewContext<'_, Self>,
     ) -> Option<CustomBlockId> {
         let deleted_hunk_color = deleted_hunk_color(cx);
-        let (editor_height, editor_with_deleted_text) =
-            editor_with_deleted_text(diff_base_buffer, deleted_hunk_color, hunk, cx);
+        let (editor_height, editor_with_deleted_text) = editor_with_deleted_text(
+            diff_base_buffer,
+            deleted_hunk_color,
+            diff_base_word_ranges,
+            hunk,
+            cx,
+        );
         let editor = cx.view().clone();
         let hunk = hunk.clone();
         let height = editor_height.max(deleted_text_height);
@@ -542,6 +607,8 @@ impl Editor {
     pub(super) fn clear_clicked_diff_hunks(&mut self, cx: &mut ViewContext<'_, Editor>) -> bool {
         self.expanded_hunks.hunk_update_tasks.clear();
         self.clear_row_highlights::<DiffRowHighlight>();
+        self.clear_background_highlights::<DiffWordHighlight>(cx);
+        self.clear_gutter_highlights::<DiffWordHighlight>(cx);
         let to_remove = self
             .expanded_hunks
             .hunks
@@ -690,6 +757,7 @@ impl Editor {
                         );
                     }
                     editor.remove_blocks(blocks_to_remove, None, cx);
+                    editor.refresh_diff_word_highlights(cx);
 
                     if let Some(diff_base_buffer) = &diff_base_buffer {
                         for hunk in hunks_to_reexpand {
@@ -747,6 +815,8 @@ fn to_diff_hunk(
         buffer_id,
         buffer_range,
         diff_base_byte_range: hovered_hunk.diff_base_byte_range.clone(),
+        buffer_word_ranges: Arc::from([]),
+        diff_base_word_ranges: Arc::from([]),
     })
 }
 
@@ -783,6 +853,7 @@ fn deleted_hunk_color(cx: &AppContext) -> Hsla {
 fn editor_with_deleted_text(
     diff_base_buffer: Model<Buffer>,
     deleted_color: Hsla,
+    diff_base_word_ranges: &[Range<usize>],
     hunk: &HoveredHunk,
     cx: &mut ViewContext<'_, Editor>,
 ) -> (u32, View<Editor>) {
@@ -816,6 +887,25 @@ fn editor_with_deleted_text(
 
         editor.highlight_rows::<DiffRowHighlight>(start..=end, Some(deleted_color), false, cx);
 
+        // The excerpt starts at the beginning of the hunk's diff base text.
+        let diff_base_start = hunk.diff_base_byte_range.start;
+        let word_ranges = diff_base_word_ranges
+            .iter()
+            .map(|range| {
+                editor_snapshot
+                    .buffer_snapshot
+                    .anchor_after(range.start.saturating_sub(diff_base_start))
+                    ..editor_snapshot
+                        .buffer_snapshot
+                        .anchor_before(range.end.saturating_sub(diff_base_start))
+            })
+            .collect::<Vec<_>>();
+        editor.highlight_background::<DiffWordHighlight>(
+            &word_ranges,
+            |colors| colors.editor_diff_word_deleted_background,
+            cx,
+        );
+
         let subscription_editor = parent_editor.clone();
         editor._subscriptions.extend([
             cx.on_blur(&editor.focus_handle, |editor, cx| {
@@ -1452,6 +1452,7 @@ pub fn entry_git_aware_label_color(
         match git_status {
             Some(GitFileStatus::Added) => Color::Created,
             Some(GitFileStatus::Modified) => Color::Modified,
+            Some(GitFileStatus::Deleted) => Color::Deleted,
             Some(GitFileStatus::Conflict) => Color::Conflict,
             None => entry_label_color(selected),
         }
@@ -0,0 +1,452 @@
+use std::{mem, ops::Range, time::Duration};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use futures::{future, future::LocalBoxFuture, FutureExt as _};
+use gpui::{AppContext, Model, Task, ViewContext};
+use language::{
+    language_settings::{self, language_settings},
+    Bias, Buffer, Point,
+};
+use multi_buffer::{ExcerptId, ToPoint as _};
+use project::{CodeLens, DocumentLink, FoldingRange, SemanticToken};
+use text::BufferId;
+use util::ResultExt;
+
+use crate::{code_lens, folding_ranges, semantic_tokens, Editor, EditorMode};
+
+const LSP_DATA_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// A kind of data that the editor requests from the language servers of its buffers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum LspDataKind {
+    SemanticTokens,
+    CodeLens,
+    FoldingRanges,
+    DocumentLinks,
+}
+
+impl LspDataKind {
+    pub(super) const ALL: &'static [Self] = &[
+        Self::SemanticTokens,
+        Self::CodeLens,
+        Self::FoldingRanges,
+        Self::DocumentLinks,
+    ];
+
+    fn is_enabled(self, buffer: &Buffer, cx: &AppContext) -> bool {
+        let settings = language_settings(buffer.language(), buffer.file(), cx);
+        match self {
+            Self::SemanticTokens => {
+                settings.semantic_tokens != language_settings::SemanticTokens::Off
+            }
+            Self::CodeLens => settings.code_lens,
+            Self::FoldingRanges => settings.folding_ranges.enabled,
+            Self::DocumentLinks => settings.document_links,
+        }
+    }
+}
+
+enum LspData {
+    SemanticTokens(Vec<SemanticToken>),
+    CodeLens(Vec<CodeLens>),
+    FoldingRanges(Option<Vec<FoldingRange>>),
+    DocumentLinks(Vec<DocumentLink>),
+}
+
+/// The data of a kind for a buffer, along with the version of the buffer it was requested for.
+type LspDataResponse = (BufferId, LspDataKind, clock::Global, Result<LspData>);
+
+/// Why the data of the editor's buffers is refreshed.
+#[derive(Debug)]
+pub(super) enum LspDataRefreshReason {
+    /// Lines that weren't visible before were shown, by scrolling or resizing the editor.
+    NewLinesShown,
+    BufferEdited,
+    /// Excerpts were added or removed, so the data is displayed in different places.
+    ExcerptsChanged,
+    /// Kinds of data may have been enabled, disabled or configured to display differently.
+    SettingsChanged,
+    /// The data of these kinds is out of date in every buffer, because the language servers
+    /// asked for it to be refreshed, a server was added, or the language of a buffer changed.
+    Invalidated(&'static [LspDataKind]),
+}
+
+/// The versions of the buffers that the editor's data was requested for, and the task that
+/// requests the data that is missing or out of date.
+#[derive(Default)]
+pub(super) struct LspDataState {
+    versions: HashMap<(BufferId, LspDataKind), clock::Global>,
+    /// The kinds whose display is out of date with their data.
+    dirty_kinds: HashSet<LspDataKind>,
+    refresh_task: Option<Task<()>>,
+}
+
+impl Editor {
+    /// Requests the data of the buffers with visible excerpts that is missing or older than
+    /// the buffers, and updates the display of the data. Buffers that aren't visible keep
+    /// their data until they're scrolled into view.
+    pub(super) fn refresh_lsp_data(
+        &mut self,
+        reason: LspDataRefreshReason,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.mode != EditorMode::Full || self.project.is_none() {
+            return;
+        }
+        let debounce = match reason {
+            LspDataRefreshReason::NewLinesShown | LspDataRefreshReason::BufferEdited => true,
+            LspDataRefreshReason::ExcerptsChanged | LspDataRefreshReason::SettingsChanged => {
+                self.lsp_data.dirty_kinds.extend(LspDataKind::ALL);
+                true
+            }
+            LspDataRefreshReason::Invalidated(kinds) => {
+                self.lsp_data
+                    .versions
+                    .retain(|(_, kind), _| !kinds.contains(kind));
+                false
+            }
+        };
+
+        self.lsp_data.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(LSP_DATA_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+
+            let Ok(requests) = editor.update(&mut cx, |editor, cx| editor.lsp_data_requests(cx))
+            else {
+                return;
+            };
+            let responses = future::join_all(requests).await;
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    for (buffer_id, kind, version, data) in responses {
+                        let Some(data) = data.log_err() else {
+                            continue;
+                        };
+                        editor.lsp_data.versions.insert((buffer_id, kind), version);
+                        editor.set_lsp_data(buffer_id, data, cx);
+                        editor.lsp_data.dirty_kinds.insert(kind);
+                    }
+                    for kind in mem::take(&mut editor.lsp_data.dirty_kinds) {
+                        editor.update_lsp_data_display(kind, cx);
+                    }
+                    code_lens::resolve_visible_code_lenses(editor, cx);
+                })
+                .ok();
+        }));
+    }
+
+    /// Drops the data of the buffers that were removed and of the kinds that were disabled,
+    /// and starts the requests for the data of the visible buffers that is out of date.
+    fn lsp_data_requests(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<LocalBoxFuture<'static, LspDataResponse>> {
+        let Some(project) = self.project.clone() else {
+            return Vec::new();
+        };
+        let mut enabled = HashSet::default();
+        for buffer in self.buffer.read(cx).all_buffers() {
+            let buffer = buffer.read(cx);
+            for kind in LspDataKind::ALL {
+                if kind.is_enabled(buffer, cx) {
+                    enabled.insert((buffer.remote_id(), *kind));
+                }
+            }
+        }
+        self.lsp_data
+            .versions
+            .retain(|key, _| enabled.contains(key));
+        for kind in LspDataKind::ALL {
+            if self.retain_lsp_data(*kind, |buffer_id| enabled.contains(&(buffer_id, *kind))) {
+                self.lsp_data.dirty_kinds.insert(*kind);
+            }
+        }
+
+        let mut requests = Vec::new();
+        for buffer in self.visible_buffers(cx) {
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let buffer_id = buffer_snapshot.remote_id();
+            let version = buffer_snapshot.version().clone();
+            for kind in LspDataKind::ALL {
+                if !enabled.contains(&(buffer_id, *kind))
+                    || self.lsp_data.versions.get(&(buffer_id, *kind)) == Some(&version)
+                {
+                    continue;
+                }
+                let data = match kind {
+                    LspDataKind::SemanticTokens => project
+                        .update(cx, |project, cx| project.semantic_tokens(&buffer, cx))
+                        .map(|tokens| tokens.map(LspData::SemanticTokens))
+                        .boxed_local(),
+                    LspDataKind::CodeLens => project
+                        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+                        .map(|lenses| lenses.map(LspData::CodeLens))
+                        .boxed_local(),
+                    LspDataKind::FoldingRanges => project
+                        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+                        .map(|ranges| ranges.map(LspData::FoldingRanges))
+                        .boxed_local(),
+                    LspDataKind::DocumentLinks => project
+                        .update(cx, |project, cx| project.document_links(&buffer, cx))
+                        .map(|links| links.map(LspData::DocumentLinks))
+                        .boxed_local(),
+                };
+                let (kind, version) = (*kind, version.clone());
+                requests.push(
+                    data.map(move |data| (buffer_id, kind, version, data))
+                        .boxed_local(),
+                );
+            }
+        }
+        requests
+    }
+
+    /// Returns the buffers that have an excerpt in the visible lines of the editor.
+    fn visible_buffers(&self, cx: &AppContext) -> Vec<Model<Buffer>> {
+        let mut buffers = Vec::<Model<Buffer>>::new();
+        for (buffer, _, _) in self.visible_buffer_ranges(cx) {
+            if !buffers.contains(&buffer) {
+                buffers.push(buffer);
+            }
+        }
+        buffers
+    }
+
+    /// Returns the visible offset range of the buffer of each excerpt in the visible lines of
+    /// the editor.
+    pub(super) fn visible_buffer_ranges(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(Model<Buffer>, Range<usize>, ExcerptId)> {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let visible_start = self.scroll_manager.anchor().anchor.to_point(&snapshot);
+        let visible_end = snapshot.clip_point(
+            visible_start + Point::new(self.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+            Bias::Left,
+        );
+        multi_buffer.range_to_buffer_ranges(visible_start..visible_end, cx)
+    }
+
+    fn set_lsp_data(&mut self, buffer_id: BufferId, data: LspData, cx: &mut ViewContext<Self>) {
+        match data {
+            LspData::SemanticTokens(tokens) => {
+                self.semantic_tokens.tokens.insert(buffer_id, tokens);
+            }
+            LspData::CodeLens(lenses) => {
+                self.code_lens.lenses.insert(buffer_id, lenses);
+            }
+            LspData::FoldingRanges(ranges) => {
+                folding_ranges::set_folding_ranges(self, buffer_id, ranges, cx);
+            }
+            LspData::DocumentLinks(links) => {
+                self.document_links.links.insert(buffer_id, links);
+            }
+        }
+    }
+
+    /// Keeps the data of a kind only for the buffers that `retain` returns true for, and
+    /// returns whether any was dropped.
+    fn retain_lsp_data(&mut self, kind: LspDataKind, retain: impl Fn(BufferId) -> bool) -> bool {
+        fn retain_buffers<T>(
+            map: &mut HashMap<BufferId, T>,
+            retain: impl Fn(BufferId) -> bool,
+        ) -> bool {
+            let len = map.len();
+            map.retain(|buffer_id, _| retain(*buffer_id));
+            map.len() != len
+        }
+
+        match kind {
+            LspDataKind::SemanticTokens => retain_buffers(&mut self.semantic_tokens.tokens, retain),
+            LspDataKind::CodeLens => retain_buffers(&mut self.code_lens.lenses, retain),
+            LspDataKind::FoldingRanges => retain_buffers(&mut self.folding_ranges.ranges, retain),
+            LspDataKind::DocumentLinks => retain_buffers(&mut self.document_links.links, retain),
+        }
+    }
+
+    fn update_lsp_data_display(&mut self, kind: LspDataKind, cx: &mut ViewContext<Self>) {
+        match kind {
+            LspDataKind::SemanticTokens => semantic_tokens::update_semantic_highlights(self, cx),
+            LspDataKind::CodeLens => code_lens::update_code_lens_blocks(self, cx),
+            LspDataKind::FoldingRanges => folding_ranges::update_folding_ranges(self, cx),
+            // Document links are only displayed when hovered.
+            LspDataKind::DocumentLinks => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{self, AtomicUsize},
+        Arc,
+    };
+
+    use indoc::indoc;
+    use language::language_settings::FoldingRangeSettings;
+
+    use super::*;
+    use crate::{
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+
+    #[gpui::test]
+    async fn test_semantic_tokens_requested_once_edits_settle(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::FUNCTION],
+                                token_modifiers: Vec::new(),
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let _requests = cx.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>({
+            let request_count = request_count.clone();
+            move |_, _, _| {
+                request_count.fetch_add(1, atomic::Ordering::SeqCst);
+                async move {
+                    Ok(Some(lsp::SemanticTokensResult::Tokens(
+                        lsp::SemanticTokens {
+                            result_id: None,
+                            data: Vec::new(),
+                        },
+                    )))
+                }
+            }
+        });
+
+        cx.set_state("fn main() {ˇ}");
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        let requests_after_open = request_count.load(atomic::Ordering::SeqCst);
+        assert!(requests_after_open > 0);
+
+        cx.simulate_keystrokes("a b c");
+        assert_eq!(
+            request_count.load(atomic::Ordering::SeqCst),
+            requests_after_open,
+            "no tokens should be requested while typing"
+        );
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        assert_eq!(
+            request_count.load(atomic::Ordering::SeqCst),
+            requests_after_open + 1,
+            "the tokens should be requested once after the edits"
+        );
+
+        cx.update_editor(|editor, cx| {
+            editor.refresh_lsp_data(LspDataRefreshReason::NewLinesShown, cx)
+        });
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        assert_eq!(
+            request_count.load(atomic::Ordering::SeqCst),
+            requests_after_open + 1,
+            "the tokens of an unchanged buffer should not be requested again"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_code_lens_refresh_requested_by_server(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let _requests = cx.handle_request::<lsp::request::CodeLensRequest, _, _>({
+            let request_count = request_count.clone();
+            move |_, _, _| {
+                request_count.fetch_add(1, atomic::Ordering::SeqCst);
+                async move { Ok(Some(Vec::new())) }
+            }
+        });
+
+        cx.set_state("fn main() {ˇ}");
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        let requests_after_open = request_count.load(atomic::Ordering::SeqCst);
+        assert!(requests_after_open > 0);
+
+        cx.lsp
+            .request::<lsp::request::CodeLensRefresh>(())
+            .await
+            .expect("code lens refresh request failed");
+        cx.executor().run_until_parked();
+        assert_eq!(
+            request_count.load(atomic::Ordering::SeqCst),
+            requests_after_open + 1,
+            "the lenses of an unchanged buffer should be requested again when the server asks"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_folding_ranges_dropped_when_disabled(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        let _requests =
+            cx.handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _, _| async move {
+                Ok(Some(vec![lsp::FoldingRange {
+                    start_line: 0,
+                    start_character: None,
+                    end_line: 2,
+                    end_character: None,
+                    kind: None,
+                    collapsed_text: None,
+                }]))
+            });
+
+        cx.set_state(indoc! {"
+            fn main() {ˇ
+                println!();
+            }
+        "});
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| assert_eq!(editor.folding_ranges.ranges.len(), 1));
+
+        update_test_language_settings(&mut cx, |settings| {
+            settings.defaults.folding_ranges = Some(FoldingRangeSettings {
+                enabled: false,
+                folded_by_default: Vec::new(),
+            });
+        });
+        cx.executor().advance_clock(LSP_DATA_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| assert!(editor.folding_ranges.ranges.is_empty()));
+    }
+}
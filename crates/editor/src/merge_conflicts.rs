@@ -0,0 +1,405 @@
+use std::{ops::Range, time::Duration};
+
+use collections::{HashMap, HashSet};
+use gpui::{AnyElement, Hsla, Model, Task, ViewContext, WeakView};
+use language::{
+    Buffer, BufferSnapshot, MergeConflict, MergeConflictResolution, Point, ToPoint as _,
+};
+use multi_buffer::{ExcerptId, MultiBufferSnapshot};
+use text::BufferId;
+use ui::{h_flex, prelude::*, ButtonSize, ButtonStyle};
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockProperties, BlockStyle, CustomBlockId},
+    AcceptBoth, AcceptOurs, AcceptTheirs, Editor, EditorMode,
+};
+
+const MERGE_CONFLICTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
+
+enum ConflictOursHighlight {}
+enum ConflictBaseHighlight {}
+enum ConflictTheirsHighlight {}
+
+/// The merge conflicts of the buffers in an editor, and the blocks that display the
+/// controls to resolve them.
+#[derive(Default)]
+pub(super) struct MergeConflictsState {
+    conflicts: HashMap<BufferId, Vec<MergeConflict>>,
+    blocks: HashSet<CustomBlockId>,
+    refresh_task: Option<Task<()>>,
+}
+
+/// Finds the merge conflict markers in the editor's buffers, highlighting both sides of
+/// each conflict and showing controls to resolve it above its first line, after a short
+/// delay when `debounce` is set.
+pub(super) fn refresh_merge_conflicts(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .map(|buffer| buffer.read(cx).snapshot())
+        .collect::<Vec<_>>();
+    editor.merge_conflicts.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(MERGE_CONFLICTS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let conflicts = cx
+            .background_executor()
+            .spawn(async move {
+                buffers
+                    .into_iter()
+                    .filter_map(|buffer| {
+                        let conflicts = buffer.merge_conflicts();
+                        (!conflicts.is_empty()).then(|| (buffer.remote_id(), conflicts))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                if conflicts.is_empty() && editor.merge_conflicts.conflicts.is_empty() {
+                    return;
+                }
+                editor.merge_conflicts.conflicts = conflicts;
+                update_merge_conflict_highlights(editor, cx);
+            })
+            .ok();
+    }));
+}
+
+/// Replaces the editor's conflict highlights and blocks with ones for the current conflicts
+/// in every excerpt.
+fn update_merge_conflict_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_row_highlights::<ConflictOursHighlight>();
+    editor.clear_row_highlights::<ConflictBaseHighlight>();
+    editor.clear_row_highlights::<ConflictTheirsHighlight>();
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let editor_handle = cx.view().downgrade();
+    let status_colors = cx.theme().status();
+    let (ours_color, base_color, theirs_color) = (
+        status_colors.created_background,
+        status_colors.ignored_background,
+        status_colors.modified_background,
+    );
+
+    let mut blocks = Vec::new();
+    for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+        let Some(conflicts) = editor
+            .merge_conflicts
+            .conflicts
+            .get(&buffer_snapshot.remote_id())
+            .cloned()
+        else {
+            continue;
+        };
+        let Some(buffer) = editor.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+            continue;
+        };
+        let context = excerpt_range.context;
+
+        for conflict in &conflicts {
+            let start = &conflict.range.start;
+            if start.cmp(&context.start, buffer_snapshot).is_lt()
+                || start.cmp(&context.end, buffer_snapshot).is_gt()
+            {
+                continue;
+            }
+
+            highlight_conflict_rows::<ConflictOursHighlight>(
+                editor,
+                &snapshot,
+                excerpt_id,
+                buffer_snapshot,
+                Some(&conflict.ours),
+                ours_color,
+                cx,
+            );
+            highlight_conflict_rows::<ConflictBaseHighlight>(
+                editor,
+                &snapshot,
+                excerpt_id,
+                buffer_snapshot,
+                conflict.base.as_ref(),
+                base_color,
+                cx,
+            );
+            highlight_conflict_rows::<ConflictTheirsHighlight>(
+                editor,
+                &snapshot,
+                excerpt_id,
+                buffer_snapshot,
+                Some(&conflict.theirs),
+                theirs_color,
+                cx,
+            );
+
+            let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)
+            else {
+                continue;
+            };
+            let editor = editor_handle.clone();
+            let buffer = buffer.clone();
+            let conflict = conflict.clone();
+            blocks.push(BlockProperties {
+                position,
+                height: 1,
+                style: BlockStyle::Flex,
+                disposition: BlockDisposition::Above,
+                render: Box::new(move |cx| {
+                    render_conflict_controls(&conflict, &buffer, &editor, cx)
+                }),
+            });
+        }
+    }
+
+    let old_blocks = std::mem::take(&mut editor.merge_conflicts.blocks);
+    if !old_blocks.is_empty() {
+        editor.remove_blocks(old_blocks, None, cx);
+    }
+    if !blocks.is_empty() {
+        editor.merge_conflicts.blocks =
+            editor.insert_blocks(blocks, None, cx).into_iter().collect();
+    }
+    cx.notify();
+}
+
+/// Highlights the lines of one side of a conflict, which end before the line of the marker
+/// that follows them.
+fn highlight_conflict_rows<T: 'static>(
+    editor: &mut Editor,
+    snapshot: &MultiBufferSnapshot,
+    excerpt_id: ExcerptId,
+    buffer_snapshot: &BufferSnapshot,
+    range: Option<&Range<text::Anchor>>,
+    color: Hsla,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(range) = range else {
+        return;
+    };
+    let start = range.start.to_point(buffer_snapshot);
+    let end = range.end.to_point(buffer_snapshot);
+    if end.row <= start.row {
+        return;
+    }
+    let last_row = buffer_snapshot.anchor_before(Point::new(end.row - 1, 0));
+    if let Some((start, end)) = snapshot
+        .anchor_in_excerpt(excerpt_id, range.start)
+        .zip(snapshot.anchor_in_excerpt(excerpt_id, last_row))
+    {
+        editor.highlight_rows::<T>(start..=end, Some(color), false, cx);
+    }
+}
+
+fn render_conflict_controls(
+    conflict: &MergeConflict,
+    buffer: &Model<Buffer>,
+    editor: &WeakView<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let ours_label = if conflict.ours_label.is_empty() {
+        "Accept Ours".to_string()
+    } else {
+        format!("Accept Ours ({})", conflict.ours_label)
+    };
+    let theirs_label = if conflict.theirs_label.is_empty() {
+        "Accept Theirs".to_string()
+    } else {
+        format!("Accept Theirs ({})", conflict.theirs_label)
+    };
+
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_1()
+        .children(
+            [
+                (ours_label, MergeConflictResolution::Ours),
+                (theirs_label, MergeConflictResolution::Theirs),
+                ("Accept Both".to_string(), MergeConflictResolution::Both),
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (label, resolution))| {
+                h_flex()
+                    .gap_1()
+                    .when(ix > 0, |row| {
+                        row.child(Label::new("|").size(LabelSize::Small).color(Color::Muted))
+                    })
+                    .child(
+                        Button::new(ix, label)
+                            .label_size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .size(ButtonSize::None)
+                            .style(ButtonStyle::Transparent)
+                            .on_click({
+                                let editor = editor.clone();
+                                let buffer = buffer.clone();
+                                let conflict = conflict.clone();
+                                move |_, cx| {
+                                    editor
+                                        .update(cx, |editor, cx| {
+                                            editor.resolve_merge_conflicts(
+                                                vec![(buffer.clone(), conflict.clone())],
+                                                resolution,
+                                                cx,
+                                            )
+                                        })
+                                        .ok();
+                                }
+                            }),
+                    )
+            }),
+        )
+        .into_any_element()
+}
+
+impl Editor {
+    pub fn accept_ours(&mut self, _: &AcceptOurs, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts_at_selections(MergeConflictResolution::Ours, cx);
+    }
+
+    pub fn accept_theirs(&mut self, _: &AcceptTheirs, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts_at_selections(MergeConflictResolution::Theirs, cx);
+    }
+
+    pub fn accept_both(&mut self, _: &AcceptBoth, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts_at_selections(MergeConflictResolution::Both, cx);
+    }
+
+    /// Resolves the merge conflicts that contain the heads of the editor's selections.
+    fn resolve_merge_conflicts_at_selections(
+        &mut self,
+        resolution: MergeConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut conflicts = Vec::<(Model<Buffer>, MergeConflict)>::new();
+        for selection in self.selections.disjoint_anchors().iter() {
+            let Some((buffer, position)) = self
+                .buffer
+                .read(cx)
+                .text_anchor_for_position(selection.head(), cx)
+            else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let Some(conflict) = self
+                .merge_conflicts
+                .conflicts
+                .get(&buffer_snapshot.remote_id())
+                .and_then(|conflicts| {
+                    conflicts.iter().find(|conflict| {
+                        conflict
+                            .range
+                            .start
+                            .cmp(&position, &buffer_snapshot)
+                            .is_le()
+                            && conflict.range.end.cmp(&position, &buffer_snapshot).is_ge()
+                    })
+                })
+            else {
+                continue;
+            };
+            if !conflicts.iter().any(|(_, existing)| existing == conflict) {
+                conflicts.push((buffer, conflict.clone()));
+            }
+        }
+        self.resolve_merge_conflicts(conflicts, resolution, cx);
+    }
+
+    /// Replaces each conflict with the sides that the resolution keeps.
+    pub(crate) fn resolve_merge_conflicts(
+        &mut self,
+        conflicts: Vec<(Model<Buffer>, MergeConflict)>,
+        resolution: MergeConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if conflicts.is_empty() {
+            return;
+        }
+        self.transact(cx, |_, cx| {
+            for (buffer, conflict) in conflicts {
+                buffer.update(cx, |buffer, cx| {
+                    let text = conflict.resolved_text(resolution, &buffer.snapshot());
+                    buffer.edit([(conflict.range, text)], None, cx);
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+
+    #[gpui::test]
+    async fn test_merge_conflicts_refreshed_after_edits_settle(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state(indoc! {"
+            fn main() {ˇ}
+        "});
+        cx.executor()
+            .advance_clock(MERGE_CONFLICTS_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| assert!(editor.merge_conflicts.conflicts.is_empty()));
+
+        cx.set_state(indoc! {"
+            fn main() {
+            <<<<<<< HEAD
+                println!(\"ours\");ˇ
+            =======
+                println!(\"theirs\");
+            >>>>>>> feature
+            }
+        "});
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| {
+            assert!(
+                editor.merge_conflicts.conflicts.is_empty(),
+                "conflicts should not be looked for until the edits settle"
+            );
+        });
+        cx.executor()
+            .advance_clock(MERGE_CONFLICTS_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| {
+            let conflicts = editor.merge_conflicts.conflicts.values().flatten().count();
+            assert_eq!(conflicts, 1);
+            assert_eq!(editor.merge_conflicts.blocks.len(), 1);
+        });
+
+        cx.set_state(indoc! {"
+            fn main() {
+                println!(\"ours\");ˇ
+            }
+        "});
+        cx.executor()
+            .advance_clock(MERGE_CONFLICTS_DEBOUNCE_TIMEOUT);
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| {
+            assert!(editor.merge_conflicts.conflicts.is_empty());
+            assert!(editor.merge_conflicts.blocks.is_empty());
+        });
+    }
+}
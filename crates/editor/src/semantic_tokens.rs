@@ -0,0 +1,59 @@
+use collections::HashMap;
+use gpui::ViewContext;
+use language::language_settings::{language_settings, SemanticTokens};
+use project::SemanticToken;
+use text::BufferId;
+use theme::ActiveTheme;
+
+use crate::{display_map::SemanticHighlight, Editor};
+
+/// The semantic tokens of the buffers in an editor, which are refreshed with the editor's
+/// other LSP data.
+#[derive(Default)]
+pub(super) struct SemanticTokensState {
+    pub(super) tokens: HashMap<BufferId, Vec<SemanticToken>>,
+}
+
+pub(super) fn update_semantic_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let syntax_theme = cx.theme().syntax().clone();
+
+    let mut highlights = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+        let Some(tokens) = editor.semantic_tokens.tokens.get(&buffer.remote_id()) else {
+            continue;
+        };
+        let replaces_syntax = language_settings(buffer.language(), buffer.file(), cx)
+            .semantic_tokens
+            == SemanticTokens::Replace;
+        let context = excerpt_range.context;
+        let start_ix =
+            tokens.partition_point(|token| token.range.end.cmp(&context.start, buffer).is_le());
+        for token in &tokens[start_ix..] {
+            if token.range.start.cmp(&context.end, buffer).is_ge() {
+                break;
+            }
+            let Some(style) =
+                syntax_theme.semantic_token_style(&token.token_type, &token.modifiers)
+            else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+            ) else {
+                continue;
+            };
+            highlights.push(SemanticHighlight {
+                range: start..end,
+                style,
+                replaces_syntax,
+            });
+        }
+    }
+
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_highlights(highlights)
+    });
+    cx.notify();
+}
@@ -1,5 +1,6 @@
+use crate::repository::RepoPath;
 use crate::Oid;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use std::path::Path;
 use std::process::Command;
@@ -47,3 +48,105 @@ pub fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oi
         )
         .collect::<HashMap<Oid, String>>())
 }
+
+/// A commit listed in a repository's history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitDetails {
+    pub sha: Oid,
+    pub author_name: String,
+    pub author_email: String,
+    /// Timestamp of the commit, normalized to Unix Epoch format.
+    pub commit_timestamp: i64,
+    pub message: String,
+}
+
+impl CommitDetails {
+    /// Returns the first line of the commit message.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// The changes introduced by a commit, relative to its first parent.
+#[derive(Clone, Debug)]
+pub struct CommitDiff {
+    pub details: CommitDetails,
+    pub files: Vec<CommitFileDiff>,
+}
+
+/// The contents of a text file before and after a commit. The old text is `None` for files
+/// that the commit added, and the new text is `None` for files that it deleted.
+#[derive(Clone, Debug)]
+pub struct CommitFileDiff {
+    pub path: RepoPath,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+/// The `git log` format that [`parse_log`] expects, used along with `-z` so that each commit
+/// ends with a NUL byte.
+pub const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%ae%x1f%ct%x1f%B";
+
+/// Parses the output of `git log -z` with the [`LOG_FORMAT`] format.
+pub fn parse_log(output: &str) -> Result<Vec<CommitDetails>> {
+    output
+        .split('\0')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let mut fields = entry.trim_start_matches('\n').splitn(5, '\x1f');
+            let mut next_field = |name: &str| {
+                fields
+                    .next()
+                    .with_context(|| format!("missing {name} in git log entry {entry:?}"))
+            };
+            Ok(CommitDetails {
+                sha: next_field("sha")?.parse()?,
+                author_name: next_field("author name")?.to_string(),
+                author_email: next_field("author email")?.to_string(),
+                commit_timestamp: next_field("timestamp")?
+                    .parse()
+                    .context("failed to parse commit timestamp")?,
+                message: next_field("message")?.trim_end().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = concat!(
+            "3a1bfb9fc2e4e5f1a0c5d6f0b0c0d7e2f6b1c8a9\x1fAda Lovelace\x1fada@example.com\x1f1700000000\x1f",
+            "Add engine\n\nWith a longer description.\n\n\0",
+            "\n8b2f1ca5a1e3c5c4f0d9e8b7a6c5d4e3f2a1b0c9\x1fCharles Babbage\x1fcharles@example.com\x1f1600000000\x1f",
+            "Initial commit\n\0",
+        );
+        let commits = parse_log(output).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        assert_eq!(
+            commits[0].sha.to_string(),
+            "3a1bfb9fc2e4e5f1a0c5d6f0b0c0d7e2f6b1c8a9"
+        );
+        assert_eq!(commits[0].author_name, "Ada Lovelace");
+        assert_eq!(commits[0].author_email, "ada@example.com");
+        assert_eq!(commits[0].commit_timestamp, 1700000000);
+        assert_eq!(
+            commits[0].message,
+            "Add engine\n\nWith a longer description."
+        );
+        assert_eq!(commits[0].summary(), "Add engine");
+
+        assert_eq!(
+            commits[1].sha.to_string(),
+            "8b2f1ca5a1e3c5c4f0d9e8b7a6c5d4e3f2a1b0c9"
+        );
+        assert_eq!(commits[1].summary(), "Initial commit");
+
+        assert!(parse_log("").unwrap().is_empty());
+        assert!(parse_log("not a commit\0").is_err());
+    }
+}
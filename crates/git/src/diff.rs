@@ -1,5 +1,5 @@
 use rope::Rope;
-use std::{iter, ops::Range};
+use std::{iter, ops::Range, sync::Arc};
 use sum_tree::SumTree;
 use text::{Anchor, BufferId, BufferSnapshot, OffsetRangeExt, Point};
 
@@ -28,6 +28,11 @@ pub struct DiffHunk<T> {
     pub buffer_range: Range<Anchor>,
     /// Original singleton buffer text before the change, that was instead of the `buffer_range`.
     pub diff_base_byte_range: Range<usize>,
+    /// For modified hunks, the ranges of the words that changed within `buffer_range`.
+    pub buffer_word_ranges: Arc<[Range<Anchor>]>,
+    /// For modified hunks, the byte ranges of the words that changed within
+    /// `diff_base_byte_range`.
+    pub diff_base_word_ranges: Arc<[Range<usize>]>,
 }
 
 impl sum_tree::Item for DiffHunk<Anchor> {
@@ -57,6 +62,10 @@ impl sum_tree::Summary for DiffHunkSummary {
     }
 }
 
+/// The size in bytes above which modified hunks aren't compared word by word, as the
+/// changed words of large hunks are too scattered to be useful.
+const MAX_WORD_DIFF_LEN: usize = 4096;
+
 #[derive(Debug, Clone)]
 pub struct BufferDiff {
     last_buffer_version: Option<clock::Global>,
@@ -105,17 +114,20 @@ impl BufferDiff {
             [
                 (
                     &hunk.associated_range.start,
-                    hunk.diff_base_byte_range.start,
+                    (hunk.diff_base_byte_range.start, hunk),
+                ),
+                (
+                    &hunk.associated_range.end,
+                    (hunk.diff_base_byte_range.end, hunk),
                 ),
-                (&hunk.associated_range.end, hunk.diff_base_byte_range.end),
             ]
             .into_iter()
         });
 
         let mut summaries = buffer.summaries_for_anchors_with_payload::<Point, _, _>(anchor_iter);
         iter::from_fn(move || {
-            let (start_point, start_base) = summaries.next()?;
-            let (mut end_point, end_base) = summaries.next()?;
+            let (start_point, (start_base, hunk)) = summaries.next()?;
+            let (mut end_point, (end_base, _)) = summaries.next()?;
 
             if end_point.column > 0 {
                 end_point.row += 1;
@@ -127,6 +139,8 @@ impl BufferDiff {
                 diff_base_byte_range: start_base..end_base,
                 buffer_range: buffer.anchor_before(start_point)..buffer.anchor_after(end_point),
                 buffer_id: buffer.remote_id(),
+                buffer_word_ranges: hunk.buffer_word_ranges.clone(),
+                diff_base_word_ranges: hunk.diff_base_word_ranges.clone(),
             })
         })
     }
@@ -158,6 +172,8 @@ impl BufferDiff {
                 diff_base_byte_range: hunk.diff_base_byte_range.clone(),
                 buffer_range: hunk.buffer_range.clone(),
                 buffer_id: hunk.buffer_id,
+                buffer_word_ranges: hunk.buffer_word_ranges.clone(),
+                diff_base_word_ranges: hunk.diff_base_word_ranges.clone(),
             })
         })
     }
@@ -178,7 +194,9 @@ impl BufferDiff {
         if let Some(patch) = patch {
             let mut divergence = 0;
             for hunk_index in 0..patch.num_hunks() {
-                let hunk = Self::process_patch_hunk(&patch, hunk_index, buffer, &mut divergence);
+                let mut hunk =
+                    Self::process_patch_hunk(&patch, hunk_index, buffer, &mut divergence);
+                Self::compute_word_ranges(&mut hunk, &diff_base_text, buffer);
                 tree.push(hunk, buffer);
             }
         }
@@ -281,7 +299,125 @@ impl BufferDiff {
             buffer_range,
             diff_base_byte_range,
             buffer_id: buffer.remote_id(),
+            buffer_word_ranges: Arc::from([]),
+            diff_base_word_ranges: Arc::from([]),
+        }
+    }
+
+    /// Fills in the words that changed within a modified hunk, unless the hunk is too large
+    /// to be compared word by word.
+    fn compute_word_ranges(
+        hunk: &mut DiffHunk<Anchor>,
+        diff_base_text: &str,
+        buffer: &text::BufferSnapshot,
+    ) {
+        let buffer_range = hunk.buffer_range.to_offset(buffer);
+        let diff_base_range = hunk.diff_base_byte_range.clone();
+        if buffer_range.is_empty()
+            || diff_base_range.is_empty()
+            || buffer_range.len() > MAX_WORD_DIFF_LEN
+            || diff_base_range.len() > MAX_WORD_DIFF_LEN
+        {
+            return;
         }
+
+        let buffer_text = buffer
+            .text_for_range(buffer_range.clone())
+            .collect::<String>();
+        let (diff_base_words, buffer_words) =
+            word_diff(&diff_base_text[diff_base_range.clone()], &buffer_text);
+        hunk.diff_base_word_ranges = diff_base_words
+            .into_iter()
+            .map(|range| diff_base_range.start + range.start..diff_base_range.start + range.end)
+            .collect();
+        hunk.buffer_word_ranges = buffer_words
+            .into_iter()
+            .map(|range| {
+                buffer.anchor_after(buffer_range.start + range.start)
+                    ..buffer.anchor_before(buffer_range.start + range.end)
+            })
+            .collect();
+    }
+}
+
+/// Returns the byte ranges of the words that differ between an old and a new version of a
+/// text, in the old text and in the new text respectively.
+///
+/// Texts are compared as sequences of words, runs of whitespace and single punctuation
+/// characters, and adjacent changed ranges are merged.
+pub fn word_diff(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let (old_words, old_offsets) = tokenize_words(old);
+    let (new_words, new_offsets) = tokenize_words(new);
+    let diff = similar::TextDiff::configure().diff_slices(&old_words, &new_words);
+
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == similar::DiffTag::Equal {
+            continue;
+        }
+        push_word_range(
+            &mut old_ranges,
+            old_offsets[old_range.start]..old_offsets[old_range.end],
+        );
+        push_word_range(
+            &mut new_ranges,
+            new_offsets[new_range.start]..new_offsets[new_range.end],
+        );
+    }
+    (old_ranges, new_ranges)
+}
+
+/// Splits a text into words, returning them along with the byte offset of each word's start
+/// and, as a final entry, the length of the text.
+fn tokenize_words(text: &str) -> (Vec<&str>, Vec<usize>) {
+    #[derive(PartialEq)]
+    enum CharKind {
+        Word,
+        Whitespace,
+        Punctuation,
+    }
+
+    fn char_kind(c: char) -> CharKind {
+        if c.is_alphanumeric() || c == '_' {
+            CharKind::Word
+        } else if c.is_whitespace() {
+            CharKind::Whitespace
+        } else {
+            CharKind::Punctuation
+        }
+    }
+
+    let mut words = Vec::new();
+    let mut offsets = Vec::new();
+    let mut start = 0;
+    let mut previous_kind = None;
+    for (ix, c) in text.char_indices() {
+        let kind = char_kind(c);
+        let continues_word = kind != CharKind::Punctuation && previous_kind.as_ref() == Some(&kind);
+        if ix > 0 && !continues_word {
+            words.push(&text[start..ix]);
+            offsets.push(start);
+            start = ix;
+        }
+        previous_kind = Some(kind);
+    }
+    if !text.is_empty() {
+        words.push(&text[start..]);
+        offsets.push(start);
+    }
+    offsets.push(text.len());
+    (words, offsets)
+}
+
+fn push_word_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
     }
 }
 
@@ -327,6 +463,26 @@ mod tests {
     use text::{Buffer, BufferId};
     use unindent::Unindent as _;
 
+    #[test]
+    fn test_word_diff() {
+        let old = "let foo = bar(1, 2);";
+        let new = "let foo = baz(1, 2);";
+        let (old_ranges, new_ranges) = word_diff(old, new);
+        assert_eq!(old_ranges, [10..13]);
+        assert_eq!(new_ranges, [10..13]);
+
+        let (old_ranges, new_ranges) = word_diff("fn main() {}", "pub fn main() {}");
+        assert!(old_ranges.is_empty());
+        assert_eq!(new_ranges, [0..4]);
+
+        let (old_ranges, new_ranges) = word_diff("same text", "same text");
+        assert!(old_ranges.is_empty() && new_ranges.is_empty());
+
+        let (old_ranges, new_ranges) = word_diff("", "héllo wörld");
+        assert!(old_ranges.is_empty());
+        assert_eq!(new_ranges, [0.."héllo wörld".len()]);
+    }
+
     #[test]
     fn test_buffer_diff_simple() {
         let diff_base = "
@@ -367,6 +523,50 @@ mod tests {
         assert_hunks(diff.hunks(&buffer), &buffer, &diff_base, &[]);
     }
 
+    #[test]
+    fn test_buffer_diff_word_ranges() {
+        let diff_base = "
+            one
+            let foo = bar(1, 2);
+            three
+        "
+        .unindent();
+        let diff_base_rope = Rope::from(diff_base.clone());
+
+        let buffer_text = "
+            one
+            let foo = baz(1, 2);
+            three
+            four
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text);
+        let mut diff = BufferDiff::new();
+        smol::block_on(diff.update(&diff_base_rope, &buffer));
+        let hunks = diff.hunks(&buffer).collect::<Vec<_>>();
+        assert_eq!(hunks.len(), 2);
+
+        let modified_hunk = &hunks[0];
+        let word_ranges = modified_hunk
+            .buffer_word_ranges
+            .iter()
+            .map(|range| buffer.text_for_range(range.clone()).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(word_ranges, ["baz"]);
+        let diff_base_words = modified_hunk
+            .diff_base_word_ranges
+            .iter()
+            .map(|range| &diff_base[range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(diff_base_words, ["bar"]);
+
+        // Added lines have no words to compare.
+        let added_hunk = &hunks[1];
+        assert!(added_hunk.buffer_word_ranges.is_empty());
+        assert!(added_hunk.diff_base_word_ranges.is_empty());
+    }
+
     #[test]
     fn test_buffer_diff_range() {
         let diff_base = "
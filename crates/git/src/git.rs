@@ -14,7 +14,10 @@ pub use crate::hosting_provider::*;
 pub mod blame;
 pub mod commit;
 pub mod diff;
+pub mod rebase;
+pub mod remote;
 pub mod repository;
+pub mod stash;
 pub mod status;
 
 lazy_static! {
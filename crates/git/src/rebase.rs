@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+/// The command of a line in the todo list of an interactive rebase, `git-rebase-todo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+    /// A command that doesn't take a commit, such as `exec`, `break` or `label`.
+    Other,
+}
+
+impl RebaseAction {
+    /// The commands that apply to a commit, which the todo list can switch between.
+    pub const COMMIT_ACTIONS: [RebaseAction; 6] = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Edit,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ];
+
+    /// Parses a command word, in its full or its abbreviated form.
+    pub fn parse(word: &str) -> Option<Self> {
+        Some(match word {
+            "pick" | "p" => RebaseAction::Pick,
+            "reword" | "r" => RebaseAction::Reword,
+            "edit" | "e" => RebaseAction::Edit,
+            "squash" | "s" => RebaseAction::Squash,
+            "fixup" | "f" => RebaseAction::Fixup,
+            "drop" | "d" => RebaseAction::Drop,
+            "exec" | "x" | "break" | "b" | "label" | "l" | "reset" | "t" | "merge" | "m"
+            | "update-ref" | "u" => RebaseAction::Other,
+            _ => return None,
+        })
+    }
+
+    /// Returns the full command word, or `None` for the commands that don't take a commit.
+    pub fn keyword(&self) -> Option<&'static str> {
+        Some(match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+            RebaseAction::Other => return None,
+        })
+    }
+}
+
+/// A command line of the todo list, with the byte ranges of its parts within the line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TodoLine {
+    pub action: RebaseAction,
+    pub action_range: Range<usize>,
+    /// The range of the commit's SHA, for the commands that apply to a commit.
+    pub sha_range: Option<Range<usize>>,
+}
+
+/// Parses a line of the todo list, returning `None` for blank lines, comments and lines
+/// that don't start with a known command.
+pub fn parse_todo_line(line: &str) -> Option<TodoLine> {
+    let (action_range, action) = words(line).next()?;
+    let action = RebaseAction::parse(action)?;
+    let sha_range = if action == RebaseAction::Other {
+        None
+    } else {
+        // `fixup` takes an optional `-C` or `-c` flag before the commit.
+        words(line)
+            .skip(1)
+            .find(|(_, word)| !word.starts_with('-'))
+            .map(|(range, _)| range)
+    };
+    Some(TodoLine {
+        action,
+        action_range,
+        sha_range,
+    })
+}
+
+/// Returns the whitespace-separated words of a line along with their ranges, stopping at a
+/// comment.
+fn words(line: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut offset = 0;
+    line.split_ascii_whitespace()
+        .map(move |word| {
+            let start = offset + line[offset..].find(word).unwrap_or(0);
+            offset = start + word.len();
+            (start..offset, word)
+        })
+        .take_while(|(_, word)| !word.starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_todo_line() {
+        assert_eq!(
+            parse_todo_line("pick 1234567 Add a feature"),
+            Some(TodoLine {
+                action: RebaseAction::Pick,
+                action_range: 0..4,
+                sha_range: Some(5..12),
+            })
+        );
+        assert_eq!(
+            parse_todo_line("  f -C abcdef0 fixup! Add a feature"),
+            Some(TodoLine {
+                action: RebaseAction::Fixup,
+                action_range: 2..3,
+                sha_range: Some(7..14),
+            })
+        );
+        assert_eq!(
+            parse_todo_line("exec cargo test"),
+            Some(TodoLine {
+                action: RebaseAction::Other,
+                action_range: 0..4,
+                sha_range: None,
+            })
+        );
+        assert_eq!(
+            parse_todo_line("# Rebase 1234567..89abcde onto 1234567"),
+            None
+        );
+        assert_eq!(parse_todo_line(""), None);
+        assert_eq!(parse_todo_line("unknown 1234567"), None);
+    }
+}
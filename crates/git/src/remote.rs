@@ -0,0 +1,406 @@
+use anyhow::{anyhow, Context, Result};
+use parking_lot::Mutex;
+use std::{
+    ffi::OsStr,
+    io::Read,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// How `pull` integrates the upstream's changes into the current branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PullStrategy {
+    Merge,
+    Rebase,
+}
+
+/// How far the current branch has diverged from its upstream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpstreamStatus {
+    /// The number of commits on the branch that aren't on its upstream.
+    pub ahead: usize,
+    /// The number of commits on the upstream that aren't on the branch.
+    pub behind: usize,
+}
+
+/// Receives the progress of an operation that talks to a remote, and answers the prompts
+/// for credentials and passphrases that it runs into.
+///
+/// The delegate is called from the thread running the operation, so answering a prompt
+/// may block until the user has responded.
+pub trait RemoteDelegate: Send + Sync {
+    /// Reports a progress update, such as "Receiving objects: 50% (10/20)".
+    fn progress(&self, message: &str);
+
+    /// Answers a prompt such as "Password for 'https://github.com':", or returns `None`
+    /// to cancel the operation.
+    fn ask(&self, prompt: &str) -> Option<String>;
+}
+
+/// Runs the git binary with a command that talks to a remote, reporting its progress and
+/// forwarding its credential prompts to the delegate.
+pub(crate) fn run_remote_command<I, S>(
+    git_binary_path: &Path,
+    working_directory: &Path,
+    args: I,
+    delegate: &dyn RemoteDelegate,
+) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new(git_binary_path);
+    command
+        .current_dir(working_directory)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    let askpass = askpass::AskPass::new()?;
+    #[cfg(unix)]
+    command.envs(askpass.environment());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        // There's no way to answer the prompts, so fail instead of waiting for input.
+        command.env("GIT_TERMINAL_PROMPT", "0");
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git process: {}", e))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("failed to read git process output")?;
+    let child = Mutex::new(child);
+    let (lines, status) = std::thread::scope(|scope| {
+        #[cfg(unix)]
+        scope.spawn(|| {
+            if let Err(error) = askpass.serve(delegate) {
+                log::error!("failed to answer git credential prompts: {error:?}");
+                // The prompts would wait for an answer forever, so the process is stopped.
+                askpass.cancel_pending();
+                child.lock().kill().ok();
+            }
+        });
+        let lines = read_progress(&mut stderr, delegate);
+        let status = child.lock().wait();
+        #[cfg(unix)]
+        askpass.stop();
+        (lines, status)
+    });
+
+    let status = status.context("failed to wait for git process")?;
+    if !status.success() {
+        #[cfg(unix)]
+        if askpass.was_cancelled() {
+            return Err(anyhow!("authentication was cancelled"));
+        }
+        return Err(anyhow!("git process failed: {}", error_message(&lines)));
+    }
+    Ok(())
+}
+
+/// Reads the output of a git process until it exits, reporting every progress update to
+/// the delegate, and returns the lines that it finished.
+///
+/// Git rewrites progress lines in place by ending each update with a carriage return, so
+/// only the lines ending with a newline are kept.
+fn read_progress(output: &mut impl Read, delegate: &dyn RemoteDelegate) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let len = match output.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(len) => len,
+        };
+        pending.extend_from_slice(&buffer[..len]);
+        while let Some(ix) = pending
+            .iter()
+            .position(|byte| *byte == b'\r' || *byte == b'\n')
+        {
+            let line = String::from_utf8_lossy(&pending[..ix]).trim().to_string();
+            let finished = pending[ix] == b'\n';
+            pending.drain(..=ix);
+            if !line.is_empty() {
+                delegate.progress(&line);
+                if finished {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+
+    let line = String::from_utf8_lossy(&pending).trim().to_string();
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Picks the lines that explain why a git process failed from its output.
+fn error_message(lines: &[String]) -> String {
+    let errors = lines
+        .iter()
+        .filter(|line| {
+            line.starts_with("fatal:") || line.starts_with("error:") || line.starts_with('!')
+        })
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        lines.last().cloned().unwrap_or_default()
+    } else {
+        errors.join("\n")
+    }
+}
+
+/// Answers the prompts of git and ssh through a helper script, which git runs as its
+/// `GIT_ASKPASS` and ssh as its `SSH_ASKPASS`.
+///
+/// The script and the thread serving it communicate through a private directory: the
+/// script creates a named pipe `response-<pid>`, writes its prompt to `request-<pid>` and
+/// reads the answer from the pipe, which is closed without an answer when the prompt was
+/// dismissed. This way, the answers are never written to the disk.
+#[cfg(unix)]
+mod askpass {
+    use super::RemoteDelegate;
+    use anyhow::{Context, Result};
+    use std::{
+        ffi::OsStr,
+        fs,
+        io::Write,
+        os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt},
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst},
+        time::Duration,
+    };
+
+    const SCRIPT: &str = r#"#!/bin/sh
+response="$ZED_ASKPASS_DIR/response-$$"
+request="$ZED_ASKPASS_DIR/request-$$"
+mkfifo -m 600 "$response" || exit 1
+if ! { printf '%s' "$1" > "$request.tmp" && mv "$request.tmp" "$request"; }; then
+    rm -f "$response"
+    exit 1
+fi
+IFS= read -r answer < "$response"
+status=$?
+rm -f "$response"
+[ "$status" -eq 0 ] || exit 1
+printf '%s\n' "$answer"
+"#;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    pub(super) struct AskPass {
+        dir: PathBuf,
+        script: PathBuf,
+        done: AtomicBool,
+        cancelled: AtomicBool,
+    }
+
+    impl AskPass {
+        pub fn new() -> Result<Self> {
+            let dir = std::env::temp_dir().join(format!(
+                "zed-askpass-{}-{}",
+                std::process::id(),
+                NEXT_ID.fetch_add(1, SeqCst)
+            ));
+            fs::DirBuilder::new()
+                .mode(0o700)
+                .create(&dir)
+                .with_context(|| format!("failed to create {dir:?}"))?;
+            let script = dir.join("askpass.sh");
+            fs::write(&script, SCRIPT)?;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o700))?;
+            Ok(Self {
+                dir,
+                script,
+                done: AtomicBool::new(false),
+                cancelled: AtomicBool::new(false),
+            })
+        }
+
+        pub fn environment(&self) -> [(&str, &OsStr); 4] {
+            [
+                ("GIT_ASKPASS", self.script.as_os_str()),
+                ("SSH_ASKPASS", self.script.as_os_str()),
+                ("SSH_ASKPASS_REQUIRE", OsStr::new("force")),
+                ("ZED_ASKPASS_DIR", self.dir.as_os_str()),
+            ]
+        }
+
+        /// Answers the script's prompts with the delegate until [`AskPass::stop`] is called.
+        ///
+        /// When this fails, the prompts still waiting for an answer have to be cancelled
+        /// with [`AskPass::cancel_pending`].
+        pub fn serve(&self, delegate: &dyn RemoteDelegate) -> Result<()> {
+            while !self.done.load(SeqCst) {
+                for entry in fs::read_dir(&self.dir)? {
+                    let path = entry?.path();
+                    let Some(pid) = path
+                        .file_name()
+                        .and_then(|name| name.to_str()?.strip_prefix("request-"))
+                        .filter(|pid| !pid.ends_with(".tmp"))
+                        .map(str::to_string)
+                    else {
+                        continue;
+                    };
+                    let prompt = fs::read_to_string(&path)?;
+                    fs::remove_file(&path)?;
+
+                    let answer = delegate.ask(prompt.trim());
+                    if answer.is_none() {
+                        self.cancelled.store(true, SeqCst);
+                    }
+                    let response = self.dir.join(format!("response-{pid}"));
+                    let mut pipe = self.open_response_pipe(&response)?;
+                    if let Some(answer) = answer {
+                        pipe.write_all(format!("{answer}\n").as_bytes())?;
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Ok(())
+        }
+
+        /// Opens a response pipe for writing, once the script has opened it for reading.
+        /// Closing the pipe without writing to it cancels the prompt.
+        fn open_response_pipe(&self, path: &Path) -> Result<fs::File> {
+            loop {
+                // Opening the pipe without blocking fails until there's a reader, which
+                // keeps this from waiting forever for a script that has exited.
+                match fs::OpenOptions::new()
+                    .write(true)
+                    .custom_flags(libc::O_NONBLOCK)
+                    .open(path)
+                {
+                    Ok(pipe) => return Ok(pipe),
+                    Err(error)
+                        if error.raw_os_error() == Some(libc::ENXIO) && !self.done.load(SeqCst) =>
+                    {
+                        std::thread::sleep(POLL_INTERVAL);
+                    }
+                    Err(error) => {
+                        return Err(error).with_context(|| format!("failed to open {path:?}"))
+                    }
+                }
+            }
+        }
+
+        /// Cancels the prompts that are waiting for an answer, so that the scripts asking
+        /// them exit.
+        pub fn cancel_pending(&self) {
+            let Ok(entries) = fs::read_dir(&self.dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let is_response = entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("response-"));
+                if is_response {
+                    fs::OpenOptions::new()
+                        .write(true)
+                        .custom_flags(libc::O_NONBLOCK)
+                        .open(entry.path())
+                        .ok();
+                }
+            }
+        }
+
+        pub fn stop(&self) {
+            self.done.store(true, SeqCst);
+        }
+
+        /// Returns whether a prompt was dismissed, which makes the git process fail.
+        pub fn was_cancelled(&self) -> bool {
+            self.cancelled.load(SeqCst)
+        }
+    }
+
+    impl Drop for AskPass {
+        fn drop(&mut self) {
+            self.cancel_pending();
+            fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct RecordingDelegate {
+        progress: Mutex<Vec<String>>,
+    }
+
+    impl RemoteDelegate for RecordingDelegate {
+        fn progress(&self, message: &str) {
+            self.progress.lock().push(message.to_string());
+        }
+
+        fn ask(&self, _prompt: &str) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_read_progress() {
+        let output = concat!(
+            "remote: Enumerating objects: 5, done.\n",
+            "Receiving objects:  50% (1/2)\r",
+            "Receiving objects: 100% (2/2), done.\n",
+            "From github.com:zed-industries/zed\n",
+            "   1234567..89abcde  main       -> origin/main",
+        );
+        let delegate = RecordingDelegate::default();
+        let lines = read_progress(&mut output.as_bytes(), &delegate);
+
+        assert_eq!(
+            *delegate.progress.lock(),
+            [
+                "remote: Enumerating objects: 5, done.",
+                "Receiving objects:  50% (1/2)",
+                "Receiving objects: 100% (2/2), done.",
+                "From github.com:zed-industries/zed",
+            ]
+        );
+        assert_eq!(
+            lines,
+            [
+                "remote: Enumerating objects: 5, done.",
+                "Receiving objects: 100% (2/2), done.",
+                "From github.com:zed-industries/zed",
+                "1234567..89abcde  main       -> origin/main",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        let lines = [
+            "To github.com:zed-industries/zed.git".to_string(),
+            "! [rejected]        main -> main (fetch first)".to_string(),
+            "error: failed to push some refs to 'github.com:zed-industries/zed.git'".to_string(),
+            "hint: Updates were rejected because the remote contains work that you do".to_string(),
+        ];
+        assert_eq!(
+            error_message(&lines),
+            "! [rejected]        main -> main (fetch first)\n\
+             error: failed to push some refs to 'github.com:zed-industries/zed.git'"
+        );
+        assert_eq!(error_message(&lines[..1]), lines[0]);
+        assert_eq!(error_message(&[]), "");
+    }
+}
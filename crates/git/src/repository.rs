@@ -1,14 +1,23 @@
+use crate::commit::{self, CommitDetails, CommitDiff, CommitFileDiff};
+use crate::remote::{self, PullStrategy, RemoteDelegate, UpstreamStatus};
+use crate::stash::{self, StashEntry};
 use crate::GitHostingProviderRegistry;
-use crate::{blame::Blame, status::GitStatus};
-use anyhow::{Context, Result};
-use collections::HashMap;
+use crate::{
+    blame::Blame,
+    status::{GitStatus, GitStatusPair},
+    Oid, DOT_GIT,
+};
+use anyhow::{anyhow, Context, Result};
+use collections::{BTreeSet, HashMap};
 use git2::BranchType;
 use parking_lot::Mutex;
 use rope::Rope;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    ffi::OsStr,
     path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
     sync::Arc,
 };
 use sum_tree::MapSeekTarget;
@@ -39,7 +48,7 @@ pub trait GitRepository: Send + Sync {
     fn statuses(&self, path_prefix: &Path) -> Result<GitStatus>;
 
     fn status(&self, path: &Path) -> Option<GitFileStatus> {
-        Some(self.statuses(path).ok()?.entries.first()?.1)
+        Some(self.statuses(path).ok()?.entries.first()?.1.combined())
     }
 
     fn branches(&self) -> Result<Vec<Branch>>;
@@ -47,6 +56,86 @@ pub trait GitRepository: Send + Sync {
     fn create_branch(&self, _: &str) -> Result<()>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
+
+    /// Returns the paths whose changes are staged in the index.
+    fn staged_paths(&self) -> Result<Vec<RepoPath>>;
+
+    /// Adds the current contents of the given paths, including their deletion, to the index.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index entries of the given paths to their state at HEAD.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Replaces the contents of a path in the index, which stages a part of its changes.
+    fn set_index_text(&self, path: &RepoPath, content: String) -> Result<()>;
+
+    /// Commits the staged changes, or replaces the HEAD commit with them when `amend` is set.
+    fn commit(&self, message: &str, amend: bool) -> Result<()>;
+
+    /// Returns up to `limit` commits reachable from HEAD, newest first, after skipping the
+    /// first `skip` of them. When a path is given, only the commits that changed the file at
+    /// that path are listed, so that each of them can be shown by the same path.
+    fn log(&self, path: Option<&RepoPath>, skip: usize, limit: usize)
+        -> Result<Vec<CommitDetails>>;
+
+    /// Returns the details of a commit and the contents of the text files that it changed.
+    fn show_commit(&self, sha: &str) -> Result<CommitDiff>;
+
+    /// Returns the details of the commit that a revision, such as an abbreviated SHA,
+    /// resolves to.
+    fn commit_details(&self, revision: &str) -> Result<CommitDetails>;
+
+    /// Loads the contents of a path at the given revision, or `None` if the path did not
+    /// exist in it.
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>>;
+
+    /// Returns the SHA of the best common ancestor of HEAD and the given revision, which is
+    /// where the current branch diverged from it.
+    fn merge_base(&self, revision: &str) -> Result<String>;
+
+    /// Returns the paths whose contents in the working directory differ from the given
+    /// revision, without detecting renames. Untracked files are not included.
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>>;
+
+    /// Returns the changes in the working directory, or the staged changes when `staged` is
+    /// set, as a unified diff. They're compared with the given revision, or else with the
+    /// index for the working directory and with HEAD for the staged changes.
+    fn diff(&self, staged: bool, revision: Option<&str>) -> Result<String>;
+
+    /// Saves the changes in the working directory and the index to a new stash and reverts
+    /// them, along with the untracked files when `include_untracked` is set.
+    fn stash_push(&self, message: Option<&str>, include_untracked: bool) -> Result<()>;
+
+    /// Returns the stashes, most recent first.
+    fn stash_list(&self) -> Result<Vec<StashEntry>>;
+
+    /// Applies the changes of the stash at the given index of the stash list, keeping it.
+    fn stash_apply(&self, index: usize) -> Result<()>;
+
+    /// Applies the changes of the stash at the given index and removes it from the list.
+    fn stash_pop(&self, index: usize) -> Result<()>;
+
+    /// Removes the stash at the given index from the list without applying it.
+    fn stash_drop(&self, index: usize) -> Result<()>;
+
+    /// Downloads the objects and refs of the current branch's remote, or of `origin` when
+    /// the branch has none.
+    fn fetch(&self, delegate: &dyn RemoteDelegate) -> Result<()>;
+
+    /// Fetches the current branch's upstream and integrates its changes into the branch.
+    fn pull(&self, strategy: PullStrategy, delegate: &dyn RemoteDelegate) -> Result<()>;
+
+    /// Pushes the current branch to its upstream. A branch without an upstream is pushed
+    /// to a branch of the same name on the default remote, which becomes its upstream.
+    fn push(&self, delegate: &dyn RemoteDelegate) -> Result<()>;
+
+    /// Returns how far the current branch has diverged from its upstream, when it has one.
+    fn upstream_status(&self) -> Option<UpstreamStatus>;
+
+    /// Returns the directories holding the repository's metadata when its `.git` is a file
+    /// pointing to them, as in linked worktrees and submodules. For a linked worktree, these
+    /// are its own git directory and the one it shares with the main worktree.
+    fn linked_git_dirs(&self) -> Vec<PathBuf>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -73,6 +162,94 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    fn working_directory(&self) -> Result<PathBuf> {
+        Ok(self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf())
+    }
+
+    /// Runs the git binary in the working directory, returning its output.
+    fn run_git<I, S>(&self, args: I) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new(&self.git_binary_path);
+        command
+            .current_dir(self.working_directory()?)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| anyhow!("Failed to start git process: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("git process failed: {}", stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Runs the git binary with a command that talks to a remote in the working directory.
+    fn run_remote_git<I, S>(&self, args: I, delegate: &dyn RemoteDelegate) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        remote::run_remote_command(
+            &self.git_binary_path,
+            &self.working_directory()?,
+            args,
+            delegate,
+        )
+    }
+
+    /// Returns the remote that branches are pushed to when they have no upstream: `origin`
+    /// when there is one, or else the first remote.
+    fn default_remote(&self) -> Result<String> {
+        let repo = self.repository.lock();
+        let remotes = repo.remotes()?;
+        let names = remotes.iter().flatten().collect::<Vec<_>>();
+        names
+            .iter()
+            .find(|name| **name == "origin")
+            .or(names.first())
+            .map(|name| name.to_string())
+            .context("the repository has no remotes")
+    }
+
+    fn has_upstream(&self) -> bool {
+        let repo = self.repository.lock();
+        let Ok(head) = repo.head() else {
+            return false;
+        };
+        head.is_branch() && git2::Branch::wrap(head).upstream().is_ok()
+    }
+}
+
+fn git2_commit_details(commit: &git2::Commit) -> CommitDetails {
+    let author = commit.author();
+    CommitDetails {
+        sha: Oid(commit.id()),
+        author_name: String::from_utf8_lossy(author.name_bytes()).into_owned(),
+        author_email: String::from_utf8_lossy(author.email_bytes()).into_owned(),
+        commit_timestamp: commit.time().seconds(),
+        message: String::from_utf8_lossy(commit.message_bytes())
+            .trim_end()
+            .to_string(),
+    }
 }
 
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
@@ -203,6 +380,285 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn staged_paths(&self) -> Result<Vec<RepoPath>> {
+        let output = self.run_git(["diff", "--cached", "--name-only", "--no-renames", "-z"])?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| RepoPath(PathBuf::from(path)))
+            .collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec![OsStr::new("add"), OsStr::new("--all"), OsStr::new("--")];
+        args.extend(paths.iter().map(|path| path.as_os_str()));
+        self.run_git(args)?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec![OsStr::new("reset"), OsStr::new("--quiet"), OsStr::new("--")];
+        args.extend(paths.iter().map(|path| path.as_os_str()));
+        self.run_git(args)?;
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: String) -> Result<()> {
+        const STAGE_NORMAL: i32 = 0;
+        const GIT_MODE_FILE: u32 = 0o100644;
+
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let mut index = repo.index()?;
+        // The index always separates path components with slashes.
+        let index_path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mode = index
+            .get_path(path, STAGE_NORMAL)
+            .map_or(GIT_MODE_FILE, |entry| entry.mode);
+        let entry = git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: git2::Oid::zero(),
+            flags: 0,
+            flags_extended: 0,
+            path: index_path.into_bytes(),
+        };
+        index.add_frombuffer(&entry, content.as_bytes())?;
+        index.write()?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let mut args = vec!["commit", "--quiet"];
+        if amend {
+            args.push("--amend");
+        }
+        if message.trim().is_empty() {
+            anyhow::ensure!(amend, "commit message is empty");
+            args.push("--no-edit");
+        } else {
+            args.extend(["--message", message]);
+        }
+        self.run_git(args)?;
+        Ok(())
+    }
+
+    fn log(
+        &self,
+        path: Option<&RepoPath>,
+        skip: usize,
+        limit: usize,
+    ) -> Result<Vec<CommitDetails>> {
+        let skip = format!("--skip={skip}");
+        let limit = format!("--max-count={limit}");
+        let mut args = vec![
+            OsStr::new("log"),
+            OsStr::new("-z"),
+            OsStr::new("--no-show-signature"),
+            OsStr::new("--no-color"),
+            OsStr::new(commit::LOG_FORMAT),
+            OsStr::new(&skip),
+            OsStr::new(&limit),
+        ];
+        if let Some(path) = path {
+            args.extend([OsStr::new("--"), path.as_os_str()]);
+        }
+        commit::parse_log(&self.run_git(args)?)
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<CommitDiff> {
+        let repo = self.repository.lock();
+        let commit = repo.revparse_single(sha)?.peel_to_commit()?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        let load_text = |file: git2::DiffFile| -> Result<Option<String>> {
+            if file.id().is_zero() {
+                return Ok(None);
+            }
+            let blob = repo.find_blob(file.id())?;
+            anyhow::ensure!(!blob.is_binary(), "blob {} is binary", file.id());
+            Ok(Some(String::from_utf8(blob.content().to_owned())?))
+        };
+        let mut files = Vec::new();
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+            // Binary files and submodules have no text to show.
+            let (Ok(old_text), Ok(new_text)) =
+                (load_text(delta.old_file()), load_text(delta.new_file()))
+            else {
+                continue;
+            };
+            files.push(CommitFileDiff {
+                path: RepoPath::new(path.to_path_buf()),
+                old_text,
+                new_text,
+            });
+        }
+
+        Ok(CommitDiff {
+            details: git2_commit_details(&commit),
+            files,
+        })
+    }
+
+    fn commit_details(&self, revision: &str) -> Result<CommitDetails> {
+        let repo = self.repository.lock();
+        let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+        Ok(git2_commit_details(&commit))
+    }
+
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let tree = repo.revparse_single(revision)?.peel_to_tree()?;
+        let entry = match tree.get_path(path) {
+            Ok(entry) => entry,
+            Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        let blob = entry.to_object(&repo)?.peel_to_blob()?;
+        Ok(Some(String::from_utf8(blob.content().to_owned())?))
+    }
+
+    fn merge_base(&self, revision: &str) -> Result<String> {
+        let repo = self.repository.lock();
+        let head = repo.head()?.peel_to_commit()?.id();
+        let other = repo.revparse_single(revision)?.peel_to_commit()?.id();
+        Ok(repo.merge_base(head, other)?.to_string())
+    }
+
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        // Keep the revision from being read as an option.
+        anyhow::ensure!(!revision.starts_with('-'), "invalid revision {revision:?}");
+        let output = self.run_git(["diff", "--name-only", "--no-renames", "-z", revision, "--"])?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| RepoPath(PathBuf::from(path)))
+            .collect())
+    }
+
+    fn diff(&self, staged: bool, revision: Option<&str>) -> Result<String> {
+        let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+        if staged {
+            args.push("--cached");
+        }
+        if let Some(revision) = revision {
+            // Keep the revision from being read as an option.
+            anyhow::ensure!(!revision.starts_with('-'), "invalid revision {revision:?}");
+            args.push(revision);
+        }
+        args.push("--");
+        self.run_git(args)
+    }
+
+    fn stash_push(&self, message: Option<&str>, include_untracked: bool) -> Result<()> {
+        let mut args = vec!["stash", "push", "--quiet"];
+        if include_untracked {
+            args.push("--include-untracked");
+        }
+        if let Some(message) = message.filter(|message| !message.trim().is_empty()) {
+            args.extend(["--message", message]);
+        }
+        self.run_git(args)?;
+        Ok(())
+    }
+
+    fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        stash::parse_stash_list(&self.run_git(["stash", "list", stash::STASH_LIST_FORMAT])?)
+    }
+
+    fn stash_apply(&self, index: usize) -> Result<()> {
+        self.run_git(["stash", "apply", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.run_git(["stash", "pop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        self.run_git(["stash", "drop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn fetch(&self, delegate: &dyn RemoteDelegate) -> Result<()> {
+        self.run_remote_git(["fetch", "--progress"], delegate)
+    }
+
+    fn pull(&self, strategy: PullStrategy, delegate: &dyn RemoteDelegate) -> Result<()> {
+        let args: &[&str] = match strategy {
+            PullStrategy::Merge => &["pull", "--progress", "--no-rebase", "--no-edit"],
+            PullStrategy::Rebase => &["pull", "--progress", "--rebase"],
+        };
+        self.run_remote_git(args, delegate)
+    }
+
+    fn push(&self, delegate: &dyn RemoteDelegate) -> Result<()> {
+        if self.has_upstream() {
+            self.run_remote_git(["push", "--progress"], delegate)
+        } else {
+            let remote = self.default_remote()?;
+            self.run_remote_git(
+                ["push", "--progress", "--set-upstream", &remote, "HEAD"],
+                delegate,
+            )
+        }
+    }
+
+    fn upstream_status(&self) -> Option<UpstreamStatus> {
+        let repo = self.repository.lock();
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let local = head.target()?;
+        let upstream = git2::Branch::wrap(head).upstream().ok()?.get().target()?;
+        let (ahead, behind) = repo.graph_ahead_behind(local, upstream).log_err()?;
+        Some(UpstreamStatus { ahead, behind })
+    }
+
+    fn linked_git_dirs(&self) -> Vec<PathBuf> {
+        let repo = self.repository.lock();
+        let Some(work_directory) = repo.workdir() else {
+            return Vec::new();
+        };
+        if !work_directory.join(*DOT_GIT).is_file() {
+            return Vec::new();
+        }
+        // File system events are reported for canonical paths.
+        let mut git_dirs = [repo.path(), repo.commondir()]
+            .into_iter()
+            .filter_map(|git_dir| git_dir.canonicalize().log_err())
+            .collect::<Vec<_>>();
+        git_dirs.dedup();
+        git_dirs
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -215,7 +671,21 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    pub staged_paths: BTreeSet<RepoPath>,
+    pub commit_messages: Vec<String>,
     pub branch_name: Option<String>,
+    /// The history returned by `log` and `show_commit`, newest first.
+    pub commits: Vec<CommitDiff>,
+    pub revision_texts: HashMap<(String, RepoPath), String>,
+    /// The output of `diff`, by whether it's for the staged changes and by the revision the
+    /// changes are compared with.
+    pub diffs: HashMap<(bool, Option<String>), String>,
+    /// The stash list, most recent first. Stashing saves and clears the worktree statuses,
+    /// and applying a stash restores them.
+    pub stashes: Vec<(StashEntry, HashMap<RepoPath, GitFileStatus>)>,
+    /// How far the branch has diverged from its upstream, or `None` when it has none.
+    /// Pulling catches up with the upstream, and pushing fails while the branch is behind.
+    pub upstream_status: Option<UpstreamStatus>,
 }
 
 impl FakeGitRepository {
@@ -247,15 +717,38 @@ impl GitRepository for FakeGitRepository {
 
     fn statuses(&self, path_prefix: &Path) -> Result<GitStatus> {
         let state = self.state.lock();
+        // The changes of the staged paths are all in the index, and the staged paths
+        // without a worktree status are modified.
         let mut entries = state
             .worktree_statuses
             .iter()
+            .map(|(repo_path, status)| (repo_path, Some(*status)))
+            .chain(
+                state
+                    .staged_paths
+                    .iter()
+                    .filter(|repo_path| !state.worktree_statuses.contains_key(*repo_path))
+                    .map(|repo_path| (repo_path, None)),
+            )
             .filter_map(|(repo_path, status)| {
-                if repo_path.0.starts_with(path_prefix) {
-                    Some((repo_path.to_owned(), *status))
-                } else {
-                    None
+                if !repo_path.0.starts_with(path_prefix) {
+                    return None;
                 }
+                let pair = match status {
+                    Some(GitFileStatus::Conflict) => GitStatusPair {
+                        index_status: Some(GitFileStatus::Conflict),
+                        worktree_status: Some(GitFileStatus::Conflict),
+                    },
+                    Some(status) if !state.staged_paths.contains(repo_path) => GitStatusPair {
+                        index_status: None,
+                        worktree_status: Some(status),
+                    },
+                    status => GitStatusPair {
+                        index_status: Some(status.unwrap_or(GitFileStatus::Modified)),
+                        worktree_status: None,
+                    },
+                };
+                Some((repo_path.to_owned(), pair))
             })
             .collect::<Vec<_>>();
         entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
@@ -288,6 +781,215 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn staged_paths(&self) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        Ok(state.staged_paths.iter().cloned().collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        state.staged_paths.extend(paths.iter().cloned());
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            state.staged_paths.remove(path);
+        }
+        Ok(())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: String) -> Result<()> {
+        let mut state = self.state.lock();
+        state.index_contents.insert(path.to_path_buf(), content);
+        state.staged_paths.insert(path.clone());
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        if amend {
+            let last_message = state
+                .commit_messages
+                .last_mut()
+                .context("no commit to amend")?;
+            if !message.trim().is_empty() {
+                *last_message = message.to_owned();
+            }
+        } else {
+            anyhow::ensure!(!state.staged_paths.is_empty(), "nothing to commit");
+            anyhow::ensure!(!message.trim().is_empty(), "commit message is empty");
+            state.commit_messages.push(message.to_owned());
+        }
+        for path in std::mem::take(&mut state.staged_paths) {
+            state.worktree_statuses.remove(&path);
+        }
+        Ok(())
+    }
+
+    fn log(
+        &self,
+        path: Option<&RepoPath>,
+        skip: usize,
+        limit: usize,
+    ) -> Result<Vec<CommitDetails>> {
+        let state = self.state.lock();
+        Ok(state
+            .commits
+            .iter()
+            .filter(|commit| {
+                path.map_or(true, |path| {
+                    commit.files.iter().any(|file| &file.path == path)
+                })
+            })
+            .skip(skip)
+            .take(limit)
+            .map(|commit| commit.details.clone())
+            .collect())
+    }
+
+    fn show_commit(&self, sha: &str) -> Result<CommitDiff> {
+        let state = self.state.lock();
+        state
+            .commits
+            .iter()
+            .find(|commit| commit.details.sha.to_string().starts_with(sha))
+            .cloned()
+            .with_context(|| format!("no commit {sha}"))
+    }
+
+    fn commit_details(&self, revision: &str) -> Result<CommitDetails> {
+        Ok(self.show_commit(revision)?.details)
+    }
+
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>> {
+        let state = self.state.lock();
+        Ok(state
+            .revision_texts
+            .get(&(revision.to_string(), path.clone()))
+            .cloned())
+    }
+
+    fn merge_base(&self, revision: &str) -> Result<String> {
+        Ok(revision.to_string())
+    }
+
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        let mut paths = state
+            .revision_texts
+            .keys()
+            .filter(|(text_revision, _)| text_revision == revision)
+            .map(|(_, path)| path.clone())
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn diff(&self, staged: bool, revision: Option<&str>) -> Result<String> {
+        let state = self.state.lock();
+        Ok(state
+            .diffs
+            .get(&(staged, revision.map(ToString::to_string)))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn stash_push(&self, message: Option<&str>, _include_untracked: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            !state.worktree_statuses.is_empty(),
+            "no local changes to save"
+        );
+        let branch = state.branch_name.clone().unwrap_or_default();
+        let message = match message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}"),
+        };
+        let statuses = std::mem::take(&mut state.worktree_statuses);
+        state.staged_paths.clear();
+        let entry = StashEntry {
+            index: 0,
+            sha: Oid::from_bytes(&[state.stashes.len() as u8 + 1; 20])?,
+            message,
+            timestamp: 0,
+        };
+        state.stashes.insert(0, (entry, statuses));
+        for (index, (entry, _)) in state.stashes.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        Ok(())
+    }
+
+    fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        let state = self.state.lock();
+        Ok(state
+            .stashes
+            .iter()
+            .map(|(entry, _)| entry.clone())
+            .collect())
+    }
+
+    fn stash_apply(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        let (_, statuses) = state
+            .stashes
+            .get(index)
+            .cloned()
+            .with_context(|| format!("no stash at index {index}"))?;
+        state.worktree_statuses.extend(statuses);
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.stash_apply(index)?;
+        self.stash_drop(index)
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(index < state.stashes.len(), "no stash at index {index}");
+        state.stashes.remove(index);
+        for (index, (entry, _)) in state.stashes.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        Ok(())
+    }
+
+    fn fetch(&self, _delegate: &dyn RemoteDelegate) -> Result<()> {
+        Ok(())
+    }
+
+    fn pull(&self, _strategy: PullStrategy, _delegate: &dyn RemoteDelegate) -> Result<()> {
+        let mut state = self.state.lock();
+        let status = state
+            .upstream_status
+            .as_mut()
+            .context("the current branch has no upstream")?;
+        status.behind = 0;
+        Ok(())
+    }
+
+    fn push(&self, _delegate: &dyn RemoteDelegate) -> Result<()> {
+        let mut state = self.state.lock();
+        let behind = state.upstream_status.map_or(0, |status| status.behind);
+        anyhow::ensure!(
+            behind == 0,
+            "updates were rejected: the branch is behind its upstream"
+        );
+        state.upstream_status = Some(UpstreamStatus::default());
+        Ok(())
+    }
+
+    fn upstream_status(&self) -> Option<UpstreamStatus> {
+        self.state.lock().upstream_status
+    }
+
+    fn linked_git_dirs(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -323,6 +1025,7 @@ fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
 pub enum GitFileStatus {
     Added,
     Modified,
+    Deleted,
     Conflict,
 }
 
@@ -343,6 +1046,9 @@ impl GitFileStatus {
             (Some(GitFileStatus::Modified), _) | (_, Some(GitFileStatus::Modified)) => {
                 Some(GitFileStatus::Modified)
             }
+            (Some(GitFileStatus::Deleted), _) | (_, Some(GitFileStatus::Deleted)) => {
+                Some(GitFileStatus::Deleted)
+            }
             (Some(GitFileStatus::Added), _) | (_, Some(GitFileStatus::Added)) => {
                 Some(GitFileStatus::Added)
             }
@@ -0,0 +1,81 @@
+use crate::Oid;
+use anyhow::{Context, Result};
+
+/// A stash in a repository's stash list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the stash in the stash list, where 0 is the most recent stash, as in
+    /// `stash@{0}`.
+    pub index: usize,
+    pub sha: Oid,
+    /// The description of the stash, such as "WIP on main: 1234567 Add engine".
+    pub message: String,
+    /// Timestamp of the stash, normalized to Unix Epoch format.
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    /// Returns the `stash@{n}` reference to the stash.
+    pub fn reference(&self) -> String {
+        format!("stash@{{{}}}", self.index)
+    }
+}
+
+/// The `git stash list` format that [`parse_stash_list`] expects.
+pub const STASH_LIST_FORMAT: &str = "--format=%H%x1f%ct%x1f%gs";
+
+/// Parses the output of `git stash list` with the [`STASH_LIST_FORMAT`] format.
+pub fn parse_stash_list(output: &str) -> Result<Vec<StashEntry>> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields = line.splitn(3, '\x1f');
+            let mut next_field = |name: &str| {
+                fields
+                    .next()
+                    .with_context(|| format!("missing {name} in git stash entry {line:?}"))
+            };
+            Ok(StashEntry {
+                index,
+                sha: next_field("sha")?.parse()?,
+                timestamp: next_field("timestamp")?
+                    .parse()
+                    .context("failed to parse stash timestamp")?,
+                message: next_field("message")?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stash_list() {
+        let output = concat!(
+            "3a1bfb9fc2e4e5f1a0c5d6f0b0c0d7e2f6b1c8a9\x1f1700000000\x1fOn main: try the new engine\n",
+            "8b2f1ca5a1e3c5c4f0d9e8b7a6c5d4e3f2a1b0c9\x1f1600000000\x1fWIP on main: 1234567 Initial commit\n",
+        );
+        let stashes = parse_stash_list(output).unwrap();
+        assert_eq!(stashes.len(), 2);
+
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(
+            stashes[0].sha.to_string(),
+            "3a1bfb9fc2e4e5f1a0c5d6f0b0c0d7e2f6b1c8a9"
+        );
+        assert_eq!(stashes[0].timestamp, 1700000000);
+        assert_eq!(stashes[0].message, "On main: try the new engine");
+        assert_eq!(stashes[0].reference(), "stash@{0}");
+
+        assert_eq!(stashes[1].index, 1);
+        assert_eq!(stashes[1].message, "WIP on main: 1234567 Initial commit");
+        assert_eq!(stashes[1].reference(), "stash@{1}");
+
+        assert!(parse_stash_list("").unwrap().is_empty());
+        assert!(parse_stash_list("not a stash\n").is_err());
+    }
+}
@@ -8,7 +8,35 @@ use std::{
 
 #[derive(Clone)]
 pub struct GitStatus {
-    pub entries: Arc<[(RepoPath, GitFileStatus)]>,
+    pub entries: Arc<[(RepoPath, GitStatusPair)]>,
+}
+
+/// The status of a path in the index, compared to HEAD, and in the working tree, compared
+/// to the index, as reported by the two columns of `git status --porcelain`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GitStatusPair {
+    pub index_status: Option<GitFileStatus>,
+    pub worktree_status: Option<GitFileStatus>,
+}
+
+impl GitStatusPair {
+    /// Returns the status of the file in the working tree compared to HEAD.
+    pub fn combined(&self) -> GitFileStatus {
+        match (self.index_status, self.worktree_status) {
+            (Some(GitFileStatus::Conflict), _) | (_, Some(GitFileStatus::Conflict)) => {
+                GitFileStatus::Conflict
+            }
+            // A file that was removed from the index but kept in the working tree.
+            (Some(GitFileStatus::Deleted), Some(GitFileStatus::Added)) => GitFileStatus::Added,
+            (Some(GitFileStatus::Deleted), _) | (_, Some(GitFileStatus::Deleted)) => {
+                GitFileStatus::Deleted
+            }
+            (Some(GitFileStatus::Added), _) | (_, Some(GitFileStatus::Added)) => {
+                GitFileStatus::Added
+            }
+            _ => GitFileStatus::Modified,
+        }
+    }
 }
 
 impl GitStatus {
@@ -57,36 +85,92 @@ impl GitStatus {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut entries = stdout
-            .split('\0')
-            .filter_map(|entry| {
-                if entry.is_char_boundary(3) {
-                    let (status, path) = entry.split_at(3);
-                    let status = status.trim();
-                    Some((
-                        RepoPath(PathBuf::from(path)),
-                        match status {
-                            "A" | "??" => GitFileStatus::Added,
-                            "M" => GitFileStatus::Modified,
-                            _ => return None,
+        Ok(Self::parse(&stdout))
+    }
+
+    /// Parses the output of `git status --porcelain=v1 -z`. A renamed or copied path is
+    /// followed by its original path, which is listed as deleted in the index when renamed.
+    /// A path can be listed twice, as deleted from the index and as untracked, in which case
+    /// both of its statuses are kept.
+    fn parse(output: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut fields = output.split('\0');
+        while let Some(entry) = fields.next() {
+            if !entry.is_char_boundary(3) {
+                continue;
+            }
+            let (status, path) = entry.split_at(3);
+            let mut status = status.chars();
+            let (Some(x), Some(y)) = (status.next(), status.next()) else {
+                continue;
+            };
+            if matches!(x, 'R' | 'C') {
+                let original_path = fields.next().unwrap_or_default();
+                if x == 'R' && !original_path.is_empty() {
+                    entries.push((
+                        RepoPath(PathBuf::from(original_path)),
+                        GitStatusPair {
+                            index_status: Some(GitFileStatus::Deleted),
+                            worktree_status: None,
                         },
-                    ))
-                } else {
-                    None
+                    ));
                 }
-            })
-            .collect::<Vec<_>>();
-        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        Ok(Self {
+            }
+
+            let pair = match (x, y) {
+                ('!', '!') => continue,
+                ('?', '?') => GitStatusPair {
+                    index_status: None,
+                    worktree_status: Some(GitFileStatus::Added),
+                },
+                ('D', 'D')
+                | ('A', 'U')
+                | ('U', 'D')
+                | ('U', 'A')
+                | ('D', 'U')
+                | ('A', 'A')
+                | ('U', 'U') => GitStatusPair {
+                    index_status: Some(GitFileStatus::Conflict),
+                    worktree_status: Some(GitFileStatus::Conflict),
+                },
+                (x, y) => GitStatusPair {
+                    index_status: column_status(x),
+                    worktree_status: column_status(y),
+                },
+            };
+            entries.push((RepoPath(PathBuf::from(path)), pair));
+        }
+
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|(path, pair), (kept_path, kept_pair)| {
+            if path != kept_path {
+                return false;
+            }
+            kept_pair.index_status = kept_pair.index_status.or(pair.index_status);
+            kept_pair.worktree_status = kept_pair.worktree_status.or(pair.worktree_status);
+            true
+        });
+        Self {
             entries: entries.into(),
-        })
+        }
     }
 
     pub fn get(&self, path: &Path) -> Option<GitFileStatus> {
         self.entries
             .binary_search_by(|(repo_path, _)| repo_path.0.as_path().cmp(path))
             .ok()
-            .map(|index| self.entries[index].1)
+            .map(|index| self.entries[index].1.combined())
+    }
+}
+
+/// Returns the status that a column of `git status --porcelain` stands for.
+fn column_status(status: char) -> Option<GitFileStatus> {
+    match status {
+        'A' | 'R' | 'C' => Some(GitFileStatus::Added),
+        'M' | 'T' => Some(GitFileStatus::Modified),
+        'D' => Some(GitFileStatus::Deleted),
+        'U' => Some(GitFileStatus::Conflict),
+        _ => None,
     }
 }
 
@@ -97,3 +181,86 @@ impl Default for GitStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = concat!(
+            " M modified.txt\0",
+            "M  staged.txt\0",
+            "MM partly-staged.txt\0",
+            "AM added-then-modified.txt\0",
+            " D deleted.txt\0",
+            "D  staged-deleted.txt\0",
+            "R  new-name.txt\0old-name.txt\0",
+            "UU conflict.txt\0",
+            "D  untracked-again.txt\0",
+            "?? untracked-again.txt\0",
+            "?? untracked.txt\0",
+        );
+        let status = GitStatus::parse(output);
+        let entries = status
+            .entries
+            .iter()
+            .map(|(path, pair)| {
+                (
+                    path.0.to_str().unwrap(),
+                    pair.index_status,
+                    pair.worktree_status,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (
+                    "added-then-modified.txt",
+                    Some(GitFileStatus::Added),
+                    Some(GitFileStatus::Modified)
+                ),
+                (
+                    "conflict.txt",
+                    Some(GitFileStatus::Conflict),
+                    Some(GitFileStatus::Conflict)
+                ),
+                ("deleted.txt", None, Some(GitFileStatus::Deleted)),
+                ("modified.txt", None, Some(GitFileStatus::Modified)),
+                ("new-name.txt", Some(GitFileStatus::Added), None),
+                ("old-name.txt", Some(GitFileStatus::Deleted), None),
+                (
+                    "partly-staged.txt",
+                    Some(GitFileStatus::Modified),
+                    Some(GitFileStatus::Modified)
+                ),
+                ("staged-deleted.txt", Some(GitFileStatus::Deleted), None),
+                ("staged.txt", Some(GitFileStatus::Modified), None),
+                (
+                    "untracked-again.txt",
+                    Some(GitFileStatus::Deleted),
+                    Some(GitFileStatus::Added)
+                ),
+                ("untracked.txt", None, Some(GitFileStatus::Added)),
+            ]
+        );
+
+        assert_eq!(
+            status.get(Path::new("added-then-modified.txt")),
+            Some(GitFileStatus::Added)
+        );
+        assert_eq!(
+            status.get(Path::new("deleted.txt")),
+            Some(GitFileStatus::Deleted)
+        );
+        assert_eq!(
+            status.get(Path::new("untracked-again.txt")),
+            Some(GitFileStatus::Added)
+        );
+        assert_eq!(
+            status.get(Path::new("partly-staged.txt")),
+            Some(GitFileStatus::Modified)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    any::{Any, TypeId},
+    path::Path,
+};
+
+use anyhow::{Context as _, Result};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyElement, AnyView, AppContext, Context as _, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, SharedString, Subscription, Task, View, ViewContext, VisualContext as _,
+};
+use language::{Buffer, File as _};
+use project::{File, Item as _, Project, ProjectEntryId, ProjectPath};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle, ProjectItem, TabContentParams},
+    ItemNavHistory, WorkspaceId,
+};
+
+use crate::{
+    commit_view::{build_commit_editor, build_staged_multibuffer},
+    HistoryRepository,
+};
+
+/// The name of the file that git asks to be edited for the message of a new commit.
+const COMMIT_MESSAGE_FILE_NAME: &str = "COMMIT_EDITMSG";
+
+/// The message of a commit being made, opened in a [`CommitMessageEditor`] instead of a
+/// plain editor.
+pub struct CommitMessage {
+    buffer: Model<Buffer>,
+}
+
+impl project::Item for CommitMessage {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        if path.path.file_name()? != COMMIT_MESSAGE_FILE_NAME {
+            return None;
+        }
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer.await?;
+            cx.new_model(|_| CommitMessage { buffer })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        self.buffer.read(cx).entry_id(cx)
+    }
+
+    fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        self.buffer.read(cx).project_path(cx)
+    }
+}
+
+/// An editor for the message of a commit being made, with the changes staged for the
+/// commit shown next to it.
+pub struct CommitMessageEditor {
+    editor: View<Editor>,
+    /// A read-only editor with the staged changes, once they're loaded.
+    staged_changes: Option<View<Editor>>,
+    load_error: Option<SharedString>,
+    _load_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CommitMessageEditor {
+    fn new(project: Model<Project>, buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let editor =
+            cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+        let _subscriptions = vec![cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })];
+
+        // The message is written to the repository's metadata directory, and the buffers of
+        // the staged changes are attached to the message's worktree.
+        let file = File::from_dyn(buffer.read(cx).file());
+        let worktree = file.map(|file| file.worktree.clone());
+        let git_dir = file
+            .and_then(|file| file.as_local())
+            .and_then(|file| Some(file.abs_path(cx).parent()?.to_path_buf()));
+        let fs = project.read(cx).fs().clone();
+        let languages = project.read(cx).languages().clone();
+        let _load_task = cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let git_dir = git_dir.context("the commit message is not a local file")?;
+                let worktree = worktree.context("the commit message is not in a worktree")?;
+                let repo = cx
+                    .background_executor()
+                    .spawn(async move { fs.open_repo(&git_dir) })
+                    .await
+                    .context("no repository found for the commit message")?;
+                let repository = HistoryRepository {
+                    repo,
+                    worktree,
+                    work_directory: Path::new("").into(),
+                };
+                build_staged_multibuffer(&repository, languages, &mut cx).await
+            }
+            .await;
+            this.update(&mut cx, |this, cx| {
+                match result {
+                    Ok(multibuffer) => {
+                        this.staged_changes = Some(build_commit_editor(multibuffer, project, cx));
+                    }
+                    Err(error) => this.load_error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+
+        Self {
+            editor,
+            staged_changes: None,
+            load_error: None,
+            _load_task,
+            _subscriptions,
+        }
+    }
+
+    fn render_staged_changes(&self, cx: &ViewContext<Self>) -> AnyElement {
+        if let Some(staged_changes) = self.staged_changes.as_ref() {
+            let multibuffer = staged_changes.read(cx).buffer().read(cx);
+            if !multibuffer.excerpt_ids().is_empty() {
+                return staged_changes.clone().into_any_element();
+            }
+        }
+        let message = match (&self.load_error, &self.staged_changes) {
+            (Some(error), _) => error.clone(),
+            (None, Some(_)) => "No staged changes".into(),
+            (None, None) => "Loading staged changes...".into(),
+        };
+        h_flex()
+            .size_full()
+            .justify_center()
+            .child(
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .into_any_element()
+    }
+}
+
+impl ProjectItem for CommitMessageEditor {
+    type Item = CommitMessage;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let buffer = item.read(cx).buffer.clone();
+        Self::new(project, buffer, cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for CommitMessageEditor {}
+
+impl FocusableView for CommitMessageEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for CommitMessageEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        self.editor.tab_content(params, cx)
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGit))
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        self.editor.tab_tooltip_text(cx)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("commit message")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.editor.is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.editor.has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save_as(project, path, cx)
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
+
+impl Render for CommitMessageEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        let pane_header = |label: &'static str| {
+            div()
+                .w_1_2()
+                .px_2()
+                .py_1()
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+        };
+
+        v_flex()
+            .key_context("CommitMessage")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(pane_header("Commit Message"))
+                    .child(pane_header("Staged Changes")),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .child(div().w_1_2().h_full().child(self.editor.clone()))
+                    .child(div().w_px().h_full().bg(border_color))
+                    .child(div().w_1_2().h_full().child(self.render_staged_changes(cx))),
+            )
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    any::Any,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use anyhow::{Context as _, Result};
+use editor::{
+    actions::ExpandAllHunkDiffs, Editor, ExcerptRange, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT,
+};
+use git::{
+    commit::{CommitDetails, CommitDiff, CommitFileDiff},
+    repository::RepoPath,
+    Oid,
+};
+use gpui::{
+    AppContext, AsyncWindowContext, Context as _, Model, ModelContext, Task, View,
+    VisualContext as _, WeakView, WindowContext,
+};
+use language::{Buffer, Capability, LanguageRegistry, LocalFile};
+use project::{Project, Worktree};
+use text::BufferId;
+use workspace::Workspace;
+
+use crate::HistoryRepository;
+
+/// The file of a buffer that holds a file's contents at a past revision, which lets the
+/// editor show the file's path and detect its language without the buffer being saved to it.
+struct RevisionFile {
+    worktree: Model<Worktree>,
+    path: Arc<Path>,
+}
+
+impl language::File for RevisionFile {
+    fn as_local(&self) -> Option<&dyn LocalFile> {
+        None
+    }
+
+    fn mtime(&self) -> Option<SystemTime> {
+        None
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, cx: &AppContext) -> PathBuf {
+        Path::new(self.worktree.read(cx).root_name()).join(&self.path)
+    }
+
+    fn file_name<'a>(&'a self, cx: &'a AppContext) -> &'a OsStr {
+        self.path
+            .file_name()
+            .unwrap_or_else(|| OsStr::new(self.worktree.read(cx).root_name()))
+    }
+
+    fn worktree_id(&self) -> usize {
+        self.worktree.entity_id().as_u64() as usize
+    }
+
+    fn is_deleted(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self, cx: &AppContext) -> rpc::proto::File {
+        rpc::proto::File {
+            worktree_id: self.worktree.read(cx).id().to_proto(),
+            entry_id: None,
+            path: self.path.to_string_lossy().into(),
+            mtime: None,
+            is_deleted: false,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+/// Opens a file's contents at the given commit in a read-only editor.
+pub(crate) fn open_revision(
+    commit: &CommitDetails,
+    path: &RepoPath,
+    repository: &HistoryRepository,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let repo = repository.repo.clone();
+    let worktree = repository.worktree.clone();
+    let worktree_path = repository.worktree_path(path);
+    let sha = commit.sha;
+    let path = path.clone();
+    let languages = project.read(cx).languages().clone();
+    cx.spawn(|mut cx| async move {
+        let text = cx
+            .background_executor()
+            .spawn({
+                let path = path.clone();
+                async move { repo.load_revision_text(&sha.to_string(), &path) }
+            })
+            .await?
+            .with_context(|| format!("{} does not exist at {sha}", path.display()))?;
+        let buffer = build_revision_buffer(
+            text,
+            None,
+            worktree,
+            worktree_path.clone(),
+            languages,
+            &mut cx,
+        )
+        .await?;
+
+        let title = format!(
+            "{} @ {}",
+            worktree_path
+                .file_name()
+                .unwrap_or(worktree_path.as_os_str())
+                .to_string_lossy(),
+            sha.display_short()
+        );
+        let multibuffer =
+            cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title))?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::for_multibuffer(multibuffer, Some(project), true, cx);
+                editor.set_read_only(true);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+/// Opens the changes that a commit made, showing its message followed by an excerpt around
+/// every changed hunk of the files that it touched, with the hunks expanded.
+pub(crate) fn open_commit(
+    sha: Oid,
+    repository: &HistoryRepository,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let repository = repository.clone();
+    let languages = project.read(cx).languages().clone();
+    cx.spawn(|mut cx| async move {
+        let repo = repository.repo.clone();
+        let commit = cx
+            .background_executor()
+            .spawn(async move { repo.show_commit(&sha.to_string()) })
+            .await?;
+        let multibuffer = build_commit_multibuffer(commit, &repository, languages, &mut cx).await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let editor = build_commit_editor(multibuffer, project, cx);
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+/// Builds a read-only multibuffer with a commit's message followed by an excerpt around
+/// every changed hunk of the files that it touched.
+pub(crate) async fn build_commit_multibuffer(
+    commit: CommitDiff,
+    repository: &HistoryRepository,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Model<MultiBuffer>> {
+    let message_buffer = cx.new_model(|cx| {
+        let mut buffer = Buffer::local(commit_header_text(&commit.details), cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer
+    })?;
+    let file_buffers = build_file_diff_buffers(commit.files, repository, languages, cx).await?;
+
+    let title = format!(
+        "{} {}",
+        commit.details.sha.display_short(),
+        commit.details.summary()
+    );
+    cx.new_model(|cx| {
+        let mut multibuffer = MultiBuffer::new(0, Capability::ReadOnly).with_title(title);
+        multibuffer.push_excerpts(
+            message_buffer,
+            [ExcerptRange {
+                context: text::Anchor::MIN..text::Anchor::MAX,
+                primary: None,
+            }],
+            cx,
+        );
+        for buffer in file_buffers {
+            push_hunk_excerpts(&mut multibuffer, buffer, cx);
+        }
+        multibuffer
+    })
+}
+
+/// Builds a read-only multibuffer with an excerpt around every hunk of the changes staged
+/// in the repository's index.
+pub(crate) async fn build_staged_multibuffer(
+    repository: &HistoryRepository,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Model<MultiBuffer>> {
+    let repo = repository.repo.clone();
+    let files = cx
+        .background_executor()
+        .spawn(async move {
+            repo.staged_paths()?
+                .into_iter()
+                .map(|path| {
+                    // There's no HEAD before the first commit.
+                    let old_text = repo.load_revision_text("HEAD", &path).ok().flatten();
+                    let new_text = repo.load_index_text(&path);
+                    Ok(CommitFileDiff {
+                        path,
+                        old_text,
+                        new_text,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .await?;
+    let file_buffers = build_file_diff_buffers(files, repository, languages, cx).await?;
+
+    cx.new_model(|cx| {
+        let mut multibuffer =
+            MultiBuffer::new(0, Capability::ReadOnly).with_title("Staged Changes".into());
+        for buffer in file_buffers {
+            push_hunk_excerpts(&mut multibuffer, buffer, cx);
+        }
+        multibuffer
+    })
+}
+
+/// Builds a read-only buffer for the new contents of every file, diffed against its old
+/// contents.
+async fn build_file_diff_buffers(
+    files: Vec<CommitFileDiff>,
+    repository: &HistoryRepository,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Vec<Model<Buffer>>> {
+    let mut buffers = Vec::with_capacity(files.len());
+    for file in files {
+        let buffer = build_revision_buffer(
+            file.new_text.unwrap_or_default(),
+            Some(file.old_text.unwrap_or_default()),
+            repository.worktree.clone(),
+            repository.worktree_path(&file.path),
+            languages.clone(),
+            cx,
+        )
+        .await?;
+        buffers.push(buffer);
+    }
+    Ok(buffers)
+}
+
+/// Builds a read-only editor for a commit's multibuffer, with its hunks expanded.
+pub(crate) fn build_commit_editor(
+    multibuffer: Model<MultiBuffer>,
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> View<Editor> {
+    cx.new_view(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, Some(project), true, cx);
+        editor.set_read_only(true);
+        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+        editor
+    })
+}
+
+/// Adds excerpts around the git diff hunks of a buffer to a multibuffer.
+pub(crate) fn push_hunk_excerpts(
+    multibuffer: &mut MultiBuffer,
+    buffer: Model<Buffer>,
+    cx: &mut ModelContext<MultiBuffer>,
+) {
+    let hunk_ranges = buffer
+        .read(cx)
+        .snapshot()
+        .git_diff_hunks_in_row_range(0..u32::MAX)
+        .map(|hunk| hunk.buffer_range)
+        .collect();
+    multibuffer.push_excerpts_with_context_lines(
+        buffer,
+        hunk_ranges,
+        DEFAULT_MULTIBUFFER_CONTEXT,
+        cx,
+    );
+}
+
+/// Builds a read-only buffer for a file's contents at a revision, highlighted by the
+/// language of its path and diffed against `diff_base` when one is given.
+pub(crate) async fn build_revision_buffer(
+    text: String,
+    diff_base: Option<String>,
+    worktree: Model<Worktree>,
+    path: Arc<Path>,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Model<Buffer>> {
+    let language = languages.language_for_file_path(&path).await.ok();
+    let buffer = cx.new_model(|cx| {
+        let text_buffer =
+            text::Buffer::new(0, BufferId::from(cx.entity_id().as_non_zero_u64()), text);
+        let file = RevisionFile { worktree, path };
+        let mut buffer = Buffer::build(
+            text_buffer,
+            diff_base,
+            Some(Arc::new(file)),
+            Capability::ReadOnly,
+        );
+        buffer.set_language_registry(languages);
+        buffer.set_language(language, cx);
+        buffer
+    })?;
+    if let Some(diff_task) = buffer.update(cx, |buffer, cx| buffer.git_diff_recalc(cx))? {
+        diff_task.await;
+    }
+    Ok(buffer)
+}
+
+/// Returns the text shown above a commit's changes, in the format of `git show`.
+fn commit_header_text(commit: &CommitDetails) -> String {
+    let date = time::OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+        .ok()
+        .and_then(|date| {
+            date.format(&time::format_description::well_known::Rfc2822)
+                .ok()
+        })
+        .unwrap_or_default();
+    let mut text = format!(
+        "commit {}\nAuthor: {} <{}>\nDate:   {}\n\n",
+        commit.sha, commit.author_name, commit.author_email, date
+    );
+    for line in commit.message.lines() {
+        text.push_str("    ");
+        text.push_str(line);
+        text.push('\n');
+    }
+    text
+}
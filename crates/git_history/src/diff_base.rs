@@ -0,0 +1,407 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use diff_view::SideBySideDiff;
+use editor::{Editor, MultiBuffer};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::repository::RepoPath;
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, Task, View, ViewContext, WeakView,
+};
+use language::{Buffer, Capability};
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+use crate::{
+    active_repository,
+    commit_view::{build_revision_buffer, push_hunk_excerpts},
+    HistoryRepository,
+};
+
+/// What the revision picked in a [`RevisionPicker`] is used for.
+#[derive(Clone)]
+pub(crate) enum RevisionTarget {
+    /// Diff all of the project's buffers against the revision.
+    Project,
+    /// Diff a single buffer against the revision.
+    Buffer(Model<Buffer>),
+    /// Review the files that changed since the current branch diverged from the revision.
+    ReviewChanges,
+    /// Open a side-by-side diff of a buffer against its file's contents at the revision.
+    SideBySide(Model<Buffer>),
+}
+
+pub struct RevisionPicker {
+    picker: View<Picker<RevisionPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RevisionPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        target: RevisionTarget,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some((repository, path)) = active_repository(workspace, cx) else {
+            return;
+        };
+        let mut revisions = vec!["HEAD".to_string()];
+        match repository.repo.branches() {
+            Ok(mut branches) => {
+                branches.sort_unstable_by(|a, b| {
+                    b.is_head
+                        .cmp(&a.is_head)
+                        .then(b.unix_timestamp.cmp(&a.unix_timestamp))
+                });
+                revisions.extend(branches.into_iter().map(|branch| branch.name.to_string()));
+            }
+            Err(error) => log::error!("failed to list branches: {error:?}"),
+        }
+
+        let delegate = RevisionPickerDelegate {
+            workspace: cx.view().downgrade(),
+            project: workspace.project().clone(),
+            repository,
+            path,
+            target,
+            revisions,
+            matches: Vec::new(),
+            selected_index: 0,
+            last_query: String::new(),
+        };
+        workspace.toggle_modal(cx, |cx| RevisionPicker::new(delegate, cx));
+    }
+
+    fn new(delegate: RevisionPickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for RevisionPicker {}
+impl EventEmitter<DismissEvent> for RevisionPicker {}
+
+impl FocusableView for RevisionPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RevisionPicker {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct RevisionPickerDelegate {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    repository: HistoryRepository,
+    /// The path of the active file in the repository.
+    path: Option<RepoPath>,
+    target: RevisionTarget,
+    /// HEAD followed by the local branches, most recently committed to first.
+    revisions: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    last_query: String,
+}
+
+impl RevisionPickerDelegate {
+    /// Returns the query as a revision of its own, such as a tag or a commit SHA, when it is
+    /// not one of the listed revisions.
+    fn query_revision(&self) -> Option<&str> {
+        let query = self.last_query.trim();
+        (!query.is_empty() && !self.revisions.iter().any(|revision| revision == query))
+            .then_some(query)
+    }
+}
+
+impl PickerDelegate for RevisionPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.target {
+            RevisionTarget::Project => "Diff the project against a branch, tag or commit...",
+            RevisionTarget::Buffer(_) => "Diff this file against a branch, tag or commit...",
+            RevisionTarget::ReviewChanges => "Review the changes since a branch...",
+            RevisionTarget::SideBySide(_) => "Compare this file with a branch, tag or commit...",
+        }
+        .into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.query_revision().is_some() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .revisions
+            .iter()
+            .enumerate()
+            .map(|(ix, revision)| StringMatchCandidate {
+                id: ix,
+                char_bag: revision.chars().collect(),
+                string: revision.clone(),
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.last_query = query;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let revision = match self.matches.get(self.selected_index) {
+            Some(hit) => hit.string.clone(),
+            None => match self.query_revision() {
+                Some(revision) => revision.to_string(),
+                None => return,
+            },
+        };
+
+        let task = match &self.target {
+            RevisionTarget::Project => self.project.update(cx, |project, cx| {
+                project.set_diff_base_revision(Some(revision), cx)
+            }),
+            RevisionTarget::Buffer(buffer) => self.project.update(cx, |project, cx| {
+                project.set_buffer_diff_base_revision(buffer.clone(), Some(revision), cx)
+            }),
+            RevisionTarget::ReviewChanges => review_changes(
+                revision,
+                self.repository.clone(),
+                self.project.clone(),
+                self.workspace.clone(),
+                cx,
+            ),
+            RevisionTarget::SideBySide(buffer) => {
+                let Some(path) = self.path.clone() else {
+                    return;
+                };
+                open_side_by_side(
+                    revision,
+                    path,
+                    buffer.clone(),
+                    self.repository.clone(),
+                    self.project.clone(),
+                    self.workspace.clone(),
+                    cx,
+                )
+            }
+        };
+        task.detach_and_notify_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let label = match self.matches.get(ix) {
+            Some(hit) => {
+                HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element()
+            }
+            None => Label::new(format!("Use revision \"{}\"", self.query_revision()?))
+                .into_any_element(),
+        };
+        Some(
+            ListItem::new(SharedString::from(format!("revision-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(label),
+        )
+    }
+}
+
+/// Diffs the files that changed since the current branch diverged from `revision` against
+/// the commit where it diverged, and opens them with an excerpt around each of their hunks.
+/// The rest of the project stays diffed against its own base.
+fn review_changes(
+    revision: String,
+    repository: HistoryRepository,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    cx.spawn(|mut cx| async move {
+        let repo = repository.repo.clone();
+        let (merge_base, changed_paths) = cx
+            .background_executor()
+            .spawn({
+                let revision = revision.clone();
+                async move {
+                    let merge_base = repo.merge_base(&revision)?;
+                    let changed_paths = repo.changed_paths(&merge_base)?;
+                    anyhow::Ok((merge_base, changed_paths))
+                }
+            })
+            .await?;
+        anyhow::ensure!(!changed_paths.is_empty(), "No changes since {revision}");
+
+        let worktree_id = repository
+            .worktree
+            .read_with(&cx, |worktree, _| worktree.id())?;
+        let open_buffers = project.update(&mut cx, |project, cx| {
+            changed_paths
+                .iter()
+                .map(|path| {
+                    let project_path = ProjectPath {
+                        worktree_id,
+                        path: repository.worktree_path(path),
+                    };
+                    project.open_buffer(project_path, cx)
+                })
+                .collect::<Vec<_>>()
+        })?;
+        let buffers = futures::future::join_all(open_buffers)
+            .await
+            .into_iter()
+            .filter_map(|buffer| buffer.log_err())
+            .collect::<Vec<_>>();
+        let set_diff_bases = project.update(&mut cx, |project, cx| {
+            buffers
+                .iter()
+                .map(|buffer| {
+                    project.set_buffer_diff_base_revision(
+                        buffer.clone(),
+                        Some(merge_base.clone()),
+                        cx,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })?;
+        for set_diff_base in set_diff_bases {
+            set_diff_base.await?;
+        }
+        for buffer in &buffers {
+            if let Some(diff_task) =
+                buffer.update(&mut cx, |buffer, cx| buffer.git_diff_recalc(cx))?
+            {
+                diff_task.await;
+            }
+        }
+
+        let replica_id = project.update(&mut cx, |project, _| project.replica_id())?;
+        let multibuffer = cx.new_model(|cx| {
+            let mut multibuffer = MultiBuffer::new(replica_id, Capability::ReadWrite)
+                .with_title(format!("Changes since {revision}"));
+            for buffer in buffers {
+                push_hunk_excerpts(&mut multibuffer, buffer, cx);
+            }
+            multibuffer
+        })?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let editor =
+                cx.new_view(|cx| Editor::for_multibuffer(multibuffer, Some(project), true, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+/// Opens a side-by-side diff of a buffer against its file's contents at `revision`.
+fn open_side_by_side(
+    revision: String,
+    path: RepoPath,
+    buffer: Model<Buffer>,
+    repository: HistoryRepository,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let languages = project.read(cx).languages().clone();
+    cx.spawn(|mut cx| async move {
+        let repo = repository.repo.clone();
+        let text = cx
+            .background_executor()
+            .spawn({
+                let (revision, path) = (revision.clone(), path.clone());
+                async move { repo.load_revision_text(&revision, &path) }
+            })
+            .await?
+            .with_context(|| format!("{} does not exist at {revision}", path.display()))?;
+        let worktree_path = repository.worktree_path(&path);
+        let name = worktree_path
+            .file_name()
+            .unwrap_or(worktree_path.as_os_str())
+            .to_string_lossy()
+            .into_owned();
+        let old_buffer = build_revision_buffer(
+            text,
+            None,
+            repository.worktree.clone(),
+            worktree_path,
+            languages,
+            &mut cx,
+        )
+        .await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            SideBySideDiff::open(
+                old_buffer,
+                buffer,
+                format!("{name} @ {revision}"),
+                name,
+                workspace,
+                cx,
+            );
+        })
+    })
+}
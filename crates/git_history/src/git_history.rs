@@ -0,0 +1,645 @@
+mod commit_message;
+mod commit_view;
+mod diff_base;
+mod rebase;
+mod stash;
+
+use std::{path::Path, sync::Arc};
+
+use commit_message::CommitMessageEditor;
+use diff_base::{RevisionPicker, RevisionTarget};
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::{
+    commit::CommitDetails,
+    repository::{GitRepository, RepoPath},
+};
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Subscription, Task, View, ViewContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath, Worktree};
+use rebase::RebaseTodoEditor;
+use stash::StashPicker;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+actions!(
+    git_history,
+    [
+        FileHistory,
+        RepositoryLog,
+        SelectDiffBase,
+        SelectFileDiffBase,
+        ResetDiffBase,
+        ReviewBranchChanges,
+        CompareWithRevision,
+        StashChanges,
+        BrowseStashes
+    ]
+);
+
+/// How many commits are loaded at a time.
+const PAGE_SIZE: usize = 200;
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<CommitMessageEditor>(cx);
+    workspace::register_project_item::<RebaseTodoEditor>(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &FileHistory, cx| {
+            GitHistory::toggle(workspace, true, cx);
+        });
+        workspace.register_action(|workspace, _: &RepositoryLog, cx| {
+            GitHistory::toggle(workspace, false, cx);
+        });
+        workspace.register_action(|workspace, _: &SelectDiffBase, cx| {
+            RevisionPicker::toggle(workspace, RevisionTarget::Project, cx);
+        });
+        workspace.register_action(|workspace, _: &SelectFileDiffBase, cx| {
+            if let Some(buffer) = active_buffer(workspace, cx) {
+                RevisionPicker::toggle(workspace, RevisionTarget::Buffer(buffer), cx);
+            }
+        });
+        workspace.register_action(|workspace, _: &ResetDiffBase, cx| {
+            let buffer = active_buffer(workspace, cx);
+            let tasks = workspace.project().update(cx, |project, cx| {
+                let mut tasks = vec![project.set_diff_base_revision(None, cx)];
+                if let Some(buffer) = buffer {
+                    tasks.push(project.set_buffer_diff_base_revision(buffer, None, cx));
+                }
+                tasks
+            });
+            cx.background_executor()
+                .spawn(async move {
+                    for task in tasks {
+                        task.await?;
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+        });
+        workspace.register_action(|workspace, _: &ReviewBranchChanges, cx| {
+            RevisionPicker::toggle(workspace, RevisionTarget::ReviewChanges, cx);
+        });
+        workspace.register_action(|workspace, _: &CompareWithRevision, cx| {
+            if let Some(buffer) = active_buffer(workspace, cx) {
+                RevisionPicker::toggle(workspace, RevisionTarget::SideBySide(buffer), cx);
+            }
+        });
+        workspace.register_action(|workspace, _: &StashChanges, cx| {
+            if let Some((repository, _)) = active_repository(workspace, cx) {
+                stash::stash_changes(None, &repository, cx).detach_and_notify_err(cx);
+            }
+        });
+        workspace.register_action(|workspace, _: &BrowseStashes, cx| {
+            StashPicker::toggle(workspace, cx);
+        });
+    })
+    .detach();
+}
+
+/// A git repository, along with the worktree that contains it.
+#[derive(Clone)]
+struct HistoryRepository {
+    repo: Arc<dyn GitRepository>,
+    worktree: Model<Worktree>,
+    /// The repository's working directory, relative to the worktree's root.
+    work_directory: Arc<Path>,
+}
+
+impl HistoryRepository {
+    /// Returns the path of a file in the repository relative to the worktree's root.
+    fn worktree_path(&self, path: &RepoPath) -> Arc<Path> {
+        self.work_directory.join(&path.0).into()
+    }
+}
+
+/// Returns the buffer of the active editor, when it edits a single buffer.
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    workspace
+        .active_item_as::<Editor>(cx)?
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+}
+
+/// Returns the repository containing the active item's file, along with the file's path in
+/// it, or the repository at the root of the first visible worktree when there's no active
+/// file.
+fn active_repository(
+    workspace: &Workspace,
+    cx: &AppContext,
+) -> Option<(HistoryRepository, Option<RepoPath>)> {
+    let project = workspace.project().read(cx);
+    let active_path = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx));
+    let project_path = match &active_path {
+        Some(project_path) => project_path.clone(),
+        None => ProjectPath {
+            worktree_id: project.visible_worktrees(cx).next()?.read(cx).id(),
+            path: Path::new("").into(),
+        },
+    };
+    let (repo, repo_path) = project.repository_for_project_path(&project_path, cx)?;
+    let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+    let work_directory = project_path
+        .path
+        .ancestors()
+        .nth(repo_path.components().count())
+        .unwrap_or(Path::new(""))
+        .into();
+    let repository = HistoryRepository {
+        repo,
+        worktree,
+        work_directory,
+    };
+    Some((repository, active_path.map(|_| repo_path)))
+}
+
+pub struct GitHistory {
+    picker: View<Picker<GitHistoryDelegate>>,
+    _subscription: Subscription,
+}
+
+impl GitHistory {
+    /// Lists the history of the active file, or of the repository that contains it when
+    /// `file_history` is not set.
+    fn toggle(workspace: &mut Workspace, file_history: bool, cx: &mut ViewContext<Workspace>) {
+        let Some((repository, repo_path)) = active_repository(workspace, cx) else {
+            return;
+        };
+        if file_history && repo_path.is_none() {
+            return;
+        }
+
+        let delegate = GitHistoryDelegate {
+            workspace: cx.view().downgrade(),
+            project: workspace.project().clone(),
+            repository,
+            path: repo_path.filter(|_| file_history),
+            commits: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            has_more: true,
+            error: None,
+            load_task: None,
+        };
+        workspace.toggle_modal(cx, |cx| GitHistory::new(delegate, cx));
+    }
+
+    fn new(delegate: GitHistoryDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| {
+            let mut picker = Picker::uniform_list(delegate, cx);
+            picker.delegate.load_more(cx);
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for GitHistory {}
+impl EventEmitter<DismissEvent> for GitHistory {}
+
+impl FocusableView for GitHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for GitHistory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(40.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct GitHistoryDelegate {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    repository: HistoryRepository,
+    /// The file whose history is listed, or `None` when listing the repository's log.
+    path: Option<RepoPath>,
+    commits: Vec<CommitDetails>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    /// Whether older commits may remain to be loaded, which adds an entry to load them
+    /// after the matches.
+    has_more: bool,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+}
+
+impl GitHistoryDelegate {
+    /// Loads the next page of commits.
+    fn load_more(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if self.load_task.is_some() {
+            return;
+        }
+        let repo = self.repository.repo.clone();
+        let path = self.path.clone();
+        let skip = self.commits.len();
+        self.load_task = Some(cx.spawn(|picker, mut cx| async move {
+            let commits = cx
+                .background_executor()
+                .spawn(async move { repo.log(path.as_ref(), skip, PAGE_SIZE) })
+                .await;
+            picker
+                .update(&mut cx, |picker, cx| {
+                    let delegate = &mut picker.delegate;
+                    delegate.load_task = None;
+                    match commits {
+                        Ok(commits) => {
+                            delegate.has_more = commits.len() == PAGE_SIZE;
+                            delegate.commits.extend(commits);
+                        }
+                        Err(error) => {
+                            delegate.has_more = false;
+                            delegate.error = Some(error.to_string().into());
+                        }
+                    }
+                    picker.refresh(cx);
+                })
+                .log_err();
+        }));
+    }
+
+    fn is_load_more_entry(&self, ix: usize) -> bool {
+        self.has_more && ix == self.matches.len()
+    }
+}
+
+impl PickerDelegate for GitHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match &self.path {
+            Some(path) => format!("Search the history of {}...", path.display()).into(),
+            None => "Search commits...".into(),
+        }
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if let Some(error) = &self.error {
+            error.clone()
+        } else if self.load_task.is_some() {
+            "Loading commits...".into()
+        } else {
+            "No commits found".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.has_more as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(ix, commit)| {
+                let string = format!(
+                    "{} {} {}",
+                    commit.summary(),
+                    commit.author_name,
+                    commit.sha.display_short()
+                );
+                StringMatchCandidate {
+                    id: ix,
+                    char_bag: string.chars().collect(),
+                    string,
+                }
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await;
+                // Keep the history in chronological order.
+                matches.sort_unstable_by_key(|string_match| string_match.candidate_id);
+                matches
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if self.is_load_more_entry(self.selected_index) {
+            self.load_more(cx);
+            return;
+        }
+        let Some(commit) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|string_match| self.commits.get(string_match.candidate_id))
+        else {
+            return;
+        };
+
+        let task = match &self.path {
+            Some(path) if !secondary => commit_view::open_revision(
+                commit,
+                path,
+                &self.repository,
+                self.project.clone(),
+                self.workspace.clone(),
+                cx,
+            ),
+            _ => commit_view::open_commit(
+                commit.sha,
+                &self.repository,
+                self.project.clone(),
+                self.workspace.clone(),
+                cx,
+            ),
+        };
+        task.detach_and_notify_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        if self.is_load_more_entry(ix) {
+            let label = if self.load_task.is_some() {
+                "Loading commits..."
+            } else {
+                "Load more commits"
+            };
+            return Some(
+                ListItem::new("git-history-load-more")
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(selected)
+                    .start_slot(Label::new(label).color(Color::Muted)),
+            );
+        }
+
+        let hit = self.matches.get(ix)?;
+        let commit = self.commits.get(hit.candidate_id)?;
+        let summary = commit.summary();
+        let highlights = hit
+            .positions
+            .iter()
+            .copied()
+            .filter(|position| *position < summary.len())
+            .collect();
+        Some(
+            ListItem::new(SharedString::from(format!("git-history-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(commit.sha.display_short())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(HighlightedLabel::new(summary.to_string(), highlights)),
+                )
+                .end_slot(
+                    Label::new(format!(
+                        "{}, {}",
+                        commit.author_name,
+                        relative_timestamp(commit.commit_timestamp)
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                ),
+        )
+    }
+}
+
+fn relative_timestamp(unix_timestamp: i64) -> String {
+    let Ok(timestamp) = OffsetDateTime::from_unix_timestamp(unix_timestamp) else {
+        return "unknown date".to_string();
+    };
+    let local_offset = chrono::Local::now().offset().local_minus_utc();
+    time_format::format_localized_timestamp(
+        timestamp,
+        OffsetDateTime::now_utc(),
+        UtcOffset::from_whole_seconds(local_offset).unwrap_or(UtcOffset::UTC),
+        time_format::TimestampFormat::Relative,
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use git::{
+        commit::{CommitDiff, CommitFileDiff},
+        Oid,
+    };
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use workspace::AppState;
+
+    pub(crate) fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(byte: u8, message: &str, files: Vec<CommitFileDiff>) -> CommitDiff {
+        CommitDiff {
+            details: CommitDetails {
+                sha: Oid::from_bytes(&[byte; 20]).unwrap(),
+                author_name: "Someone".into(),
+                author_email: "someone@example.com".into(),
+                commit_timestamp: 0,
+                message: message.into(),
+            },
+            files,
+        }
+    }
+
+    fn file_diff(path: &str, old_text: Option<&str>, new_text: Option<&str>) -> CommitFileDiff {
+        CommitFileDiff {
+            path: RepoPath::from(Path::new(path)),
+            old_text: old_text.map(Into::into),
+            new_text: new_text.map(Into::into),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_file_history_and_commit_view(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                ".git": {},
+                "a.txt": "one\nTWO\nthree\n",
+                "b.txt": "b\n",
+            }),
+        )
+        .await;
+        let change_a = commit(
+            0x22,
+            "Change a",
+            vec![file_diff(
+                "a.txt",
+                Some("one\ntwo\nthree\n"),
+                Some("one\nTWO\nthree\n"),
+            )],
+        );
+        let add_b = commit(0x11, "Add b", vec![file_diff("b.txt", None, Some("b\n"))]);
+        let change_a_sha = change_a.details.sha;
+        fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+            state.commits = vec![change_a, add_b];
+            state.revision_texts.insert(
+                (change_a_sha.to_string(), RepoPath::from(Path::new("a.txt"))),
+                "one\nTWO\nthree\n".into(),
+            );
+        });
+        let project = Project::test(fs, ["/repo".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from("/repo/a.txt"), true, cx)
+            })
+            .await
+            .unwrap();
+
+        // The file history only lists the commits that changed the active file.
+        let history = toggle_history(&workspace, true, cx);
+        assert_eq!(commit_summaries(&history, cx), ["Change a"]);
+
+        // Confirming a commit opens the file as it was at that commit.
+        history.update(cx, |history, cx| {
+            history
+                .picker
+                .update(cx, |picker, cx| picker.delegate.confirm(false, cx))
+        });
+        cx.run_until_parked();
+        let editor = active_editor(&workspace, cx);
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one\nTWO\nthree\n");
+            assert!(editor.read_only(cx));
+            assert_eq!(
+                editor.buffer().read(cx).title(cx),
+                format!("a.txt @ {}", change_a_sha.display_short())
+            );
+        });
+
+        // The repository log lists every commit, and opens the changes of the selected one.
+        let history = toggle_history(&workspace, false, cx);
+        assert_eq!(commit_summaries(&history, cx), ["Change a", "Add b"]);
+        history.update(cx, |history, cx| {
+            history
+                .picker
+                .update(cx, |picker, cx| picker.delegate.confirm(true, cx))
+        });
+        cx.run_until_parked();
+        let editor = active_editor(&workspace, cx);
+        editor.update(cx, |editor, cx| {
+            let text = editor.text(cx);
+            assert!(
+                text.starts_with(&format!("commit {change_a_sha}\nAuthor: Someone")),
+                "unexpected commit text: {text:?}"
+            );
+            assert!(text.contains("    Change a\n"));
+            assert!(text.contains("one\nTWO\nthree"));
+        });
+    }
+
+    fn toggle_history(
+        workspace: &View<Workspace>,
+        file_history: bool,
+        cx: &mut VisualTestContext,
+    ) -> View<GitHistory> {
+        workspace.update(cx, |workspace, cx| {
+            GitHistory::toggle(workspace, file_history, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<GitHistory>(cx).unwrap()
+        })
+    }
+
+    fn commit_summaries(history: &View<GitHistory>, cx: &mut VisualTestContext) -> Vec<String> {
+        history.update(cx, |history, cx| {
+            let delegate = &history.picker.read(cx).delegate;
+            delegate
+                .matches
+                .iter()
+                .map(|string_match| {
+                    delegate.commits[string_match.candidate_id]
+                        .summary()
+                        .to_string()
+                })
+                .collect()
+        })
+    }
+
+    fn active_editor(workspace: &View<Workspace>, cx: &mut VisualTestContext) -> View<Editor> {
+        workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        })
+    }
+}
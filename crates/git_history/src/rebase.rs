@@ -0,0 +1,673 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Result;
+use collections::HashMap;
+use editor::{
+    actions::{MoveLineDown, MoveLineUp},
+    Editor, EditorEvent,
+};
+use git::{
+    commit::CommitDetails,
+    rebase::{parse_todo_line, RebaseAction},
+    repository::GitRepository,
+};
+use gpui::{
+    actions, AnyElement, AnyView, AppContext, Context as _, EventEmitter, FocusHandle,
+    FocusableView, HighlightStyle, Model, Render, SharedString, StrikethroughStyle, Subscription,
+    Task, View, ViewContext, VisualContext as _,
+};
+use language::{Buffer, Point};
+use project::{Item as _, Project, ProjectEntryId, ProjectPath};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle, ProjectItem, TabContentParams},
+    ItemNavHistory, WorkspaceId,
+};
+
+use crate::relative_timestamp;
+
+actions!(
+    rebase_todo,
+    [
+        PickCommit,
+        RewordCommit,
+        EditCommit,
+        SquashCommit,
+        FixupCommit,
+        DropCommit,
+        MoveCommitUp,
+        MoveCommitDown
+    ]
+);
+
+/// The name of the file that git asks to be edited during an interactive rebase.
+const REBASE_TODO_FILE_NAME: &str = "git-rebase-todo";
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(50);
+
+enum RebaseActionHighlight {}
+enum RebaseShaHighlight {}
+enum RebaseCommentHighlight {}
+enum DroppedCommitHighlight {}
+enum UnknownCommitHighlight {}
+
+/// The todo list of an interactive rebase, opened in a [`RebaseTodoEditor`] instead of a
+/// plain editor.
+pub struct RebaseTodo {
+    buffer: Model<Buffer>,
+}
+
+impl project::Item for RebaseTodo {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        if path.path.file_name()? != REBASE_TODO_FILE_NAME {
+            return None;
+        }
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer.await?;
+            cx.new_model(|_| RebaseTodo { buffer })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        self.buffer.read(cx).entry_id(cx)
+    }
+
+    fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        self.buffer.read(cx).project_path(cx)
+    }
+}
+
+/// An editor for the todo list of an interactive rebase, with actions that change the
+/// command of the selected commits or move them, and the details of the commit under the
+/// cursor resolved from the repository.
+pub struct RebaseTodoEditor {
+    editor: View<Editor>,
+    repo: Option<Arc<dyn GitRepository>>,
+    /// The commits of the todo list by their SHA as written, or `None` for the SHAs that
+    /// don't resolve to a commit.
+    commits: HashMap<String, Option<CommitDetails>>,
+    /// The SHA of the commit on the line of the cursor.
+    selected_sha: Option<String>,
+    _open_repo_task: Task<()>,
+    _refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RebaseTodoEditor {
+    fn new(project: Model<Project>, buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let editor =
+            cx.new_view(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+        let _subscriptions = vec![cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone());
+            match event {
+                EditorEvent::BufferEdited => this.schedule_refresh(cx),
+                EditorEvent::SelectionsChanged { .. } => this.update_selected_sha(cx),
+                _ => {}
+            }
+        })];
+
+        // The todo list lives in `.git/rebase-merge`, next to the repository's metadata.
+        let git_dir = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .and_then(|file| Some(file.abs_path(cx).parent()?.parent()?.to_path_buf()));
+        let fs = project.read(cx).fs().clone();
+        let open_repo = cx
+            .background_executor()
+            .spawn(async move { fs.open_repo(&git_dir?) });
+        let _open_repo_task = cx.spawn(|this, mut cx| async move {
+            let repo = open_repo.await;
+            this.update(&mut cx, |this, cx| {
+                this.repo = repo;
+                this.schedule_refresh(cx);
+            })
+            .ok();
+        });
+
+        Self {
+            editor,
+            repo: None,
+            commits: HashMap::default(),
+            selected_sha: None,
+            _open_repo_task,
+            _refresh_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Highlights the todo list, and resolves the commits that were added to it once it
+    /// stops changing.
+    fn schedule_refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.highlight_todo_list(cx);
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        self._refresh_task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let Ok(unresolved) = this.update(&mut cx, |this, cx| {
+                let text = this.editor.read(cx).text(cx);
+                text.lines()
+                    .filter_map(|line| {
+                        let sha_range = parse_todo_line(line)?.sha_range?;
+                        Some(line[sha_range].to_string())
+                    })
+                    .filter(|sha| !this.commits.contains_key(sha))
+                    .collect::<Vec<_>>()
+            }) else {
+                return;
+            };
+            if unresolved.is_empty() {
+                return;
+            }
+            let resolved = cx
+                .background_executor()
+                .spawn(async move {
+                    unresolved
+                        .into_iter()
+                        .map(|sha| {
+                            let commit = repo.commit_details(&sha).ok();
+                            (sha, commit)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.commits.extend(resolved);
+                this.highlight_todo_list(cx);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn highlight_todo_list(&mut self, cx: &mut ViewContext<Self>) {
+        let syntax = cx.theme().syntax().clone();
+        let error_color = cx.theme().status().error;
+        let commits = &self.commits;
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let text = snapshot.text();
+            let mut actions = Vec::new();
+            let mut shas = Vec::new();
+            let mut comments = Vec::new();
+            let mut dropped = Vec::new();
+            let mut unknown = Vec::new();
+            for (row, line) in text.lines().enumerate() {
+                let row = row as u32;
+                let range = |columns: Range<usize>| {
+                    snapshot.anchor_after(Point::new(row, columns.start as u32))
+                        ..snapshot.anchor_before(Point::new(row, columns.end as u32))
+                };
+                if line.trim_start().starts_with('#') {
+                    comments.push(range(0..line.len()));
+                    continue;
+                }
+                let Some(todo_line) = parse_todo_line(line) else {
+                    continue;
+                };
+                actions.push(range(todo_line.action_range.clone()));
+                if todo_line.action == RebaseAction::Drop {
+                    dropped.push(range(todo_line.action_range.end..line.len()));
+                }
+                if let Some(sha_range) = todo_line.sha_range {
+                    if let Some(None) = commits.get(&line[sha_range.clone()]) {
+                        unknown.push(range(sha_range.clone()));
+                    }
+                    shas.push(range(sha_range));
+                }
+            }
+
+            editor.highlight_text::<RebaseActionHighlight>(actions, syntax.get("keyword"), cx);
+            editor.highlight_text::<RebaseShaHighlight>(shas, syntax.get("constant"), cx);
+            editor.highlight_text::<RebaseCommentHighlight>(comments, syntax.get("comment"), cx);
+            editor.highlight_text::<DroppedCommitHighlight>(
+                dropped,
+                HighlightStyle {
+                    strikethrough: Some(StrikethroughStyle {
+                        thickness: px(1.),
+                        color: None,
+                    }),
+                    fade_out: Some(0.5),
+                    ..Default::default()
+                },
+                cx,
+            );
+            editor.highlight_text::<UnknownCommitHighlight>(
+                unknown,
+                HighlightStyle {
+                    color: Some(error_color),
+                    ..Default::default()
+                },
+                cx,
+            );
+        });
+        self.update_selected_sha(cx);
+    }
+
+    fn update_selected_sha(&mut self, cx: &mut ViewContext<Self>) {
+        let editor = self.editor.read(cx);
+        let row = editor.selections.newest::<Point>(cx).head().row;
+        let text = editor.text(cx);
+        let selected_sha = text.lines().nth(row as usize).and_then(|line| {
+            let sha_range = parse_todo_line(line)?.sha_range?;
+            Some(line[sha_range].to_string())
+        });
+        if selected_sha != self.selected_sha {
+            self.selected_sha = selected_sha;
+            cx.notify();
+        }
+    }
+
+    /// Changes the command of the commits on the selected lines.
+    fn set_action(&mut self, action: RebaseAction, cx: &mut ViewContext<Self>) {
+        let Some(keyword) = action.keyword() else {
+            return;
+        };
+        self.editor.update(cx, |editor, cx| {
+            let text = editor.text(cx);
+            let lines = text.lines().collect::<Vec<_>>();
+            let mut edits = Vec::new();
+            let mut last_row = None;
+            for selection in editor.selections.all::<Point>(cx) {
+                let mut end_row = selection.end.row;
+                // A selection ending at the start of a line doesn't select that line.
+                if selection.end.column == 0 && end_row > selection.start.row {
+                    end_row -= 1;
+                }
+                for row in selection.start.row..=end_row {
+                    if last_row.map_or(false, |last_row| row <= last_row) {
+                        continue;
+                    }
+                    last_row = Some(row);
+                    let Some(todo_line) = lines
+                        .get(row as usize)
+                        .and_then(|line| parse_todo_line(line))
+                    else {
+                        continue;
+                    };
+                    if todo_line.action == RebaseAction::Other || todo_line.action == action {
+                        continue;
+                    }
+                    // The flags of `fixup -C` and `fixup -c` only apply to fixups, so they're
+                    // replaced along with the command.
+                    let Some(sha_range) = todo_line.sha_range else {
+                        continue;
+                    };
+                    edits.push((
+                        Point::new(row, todo_line.action_range.start as u32)
+                            ..Point::new(row, sha_range.start as u32),
+                        format!("{keyword} "),
+                    ));
+                }
+            }
+            editor.edit(edits, cx);
+        });
+    }
+
+    fn pick(&mut self, _: &PickCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Pick, cx);
+    }
+
+    fn reword(&mut self, _: &RewordCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Reword, cx);
+    }
+
+    fn edit(&mut self, _: &EditCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Edit, cx);
+    }
+
+    fn squash(&mut self, _: &SquashCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Squash, cx);
+    }
+
+    fn fixup(&mut self, _: &FixupCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Fixup, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, cx: &mut ViewContext<Self>) {
+        self.set_action(RebaseAction::Drop, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveCommitUp, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.move_line_up(&MoveLineUp, cx));
+    }
+
+    fn move_down(&mut self, _: &MoveCommitDown, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.move_line_down(&MoveLineDown, cx));
+    }
+
+    fn render_commit_details(&self, cx: &ViewContext<Self>) -> AnyElement {
+        let placeholder = |text: &'static str| {
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(Label::new(text).size(LabelSize::Small).color(Color::Muted))
+                .into_any_element()
+        };
+        if self.repo.is_none() {
+            return placeholder("No repository found for this rebase");
+        }
+        let Some(sha) = self.selected_sha.as_ref() else {
+            return placeholder("No commit on this line");
+        };
+        let Some(commit) = self.commits.get(sha) else {
+            return placeholder("Loading commit...");
+        };
+        let Some(commit) = commit else {
+            return placeholder("Unknown commit");
+        };
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(commit.sha.display_short())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(commit.author_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(relative_timestamp(commit.commit_timestamp))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(Label::new(commit.summary().to_string()))
+            .children(commit.message.split_once('\n').and_then(|(_, body)| {
+                let body = body.trim();
+                (!body.is_empty()).then(|| {
+                    Label::new(body.to_string())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                })
+            }))
+            .into_any_element()
+    }
+}
+
+impl ProjectItem for RebaseTodoEditor {
+    type Item = RebaseTodo;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let buffer = item.read(cx).buffer.clone();
+        Self::new(project, buffer, cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for RebaseTodoEditor {}
+
+impl FocusableView for RebaseTodoEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for RebaseTodoEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        self.editor.tab_content(params, cx)
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGit))
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        self.editor.tab_tooltip_text(cx)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("rebase todo")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.editor.is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.editor.has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save_as(project, path, cx)
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
+
+impl Render for RebaseTodoEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        h_flex()
+            .key_context("RebaseTodo")
+            .on_action(cx.listener(Self::pick))
+            .on_action(cx.listener(Self::reword))
+            .on_action(cx.listener(Self::edit))
+            .on_action(cx.listener(Self::squash))
+            .on_action(cx.listener(Self::fixup))
+            .on_action(cx.listener(Self::drop_commit))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(div().flex_1().h_full().child(self.editor.clone()))
+            .child(div().w_px().h_full().bg(border_color))
+            .child(
+                div()
+                    .w(rems(28.))
+                    .h_full()
+                    .child(self.render_commit_details(cx)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::{commit::CommitDiff, Oid};
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::Path;
+
+    use crate::tests::init_test;
+
+    #[gpui::test]
+    async fn test_rebase_todo_editor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                "rebase-merge": {
+                    "git-rebase-todo": "pick 1111111 First\n\
+                        fixup -C 2222222 Second\n\
+                        exec cargo test\n\
+                        pick 3333333 Third\n\
+                        # Rebase 0000000..3333333 onto 0000000\n",
+                },
+            }),
+        )
+        .await;
+        fs.with_git_state(Path::new("/repo"), false, |state| {
+            state.commits = vec![CommitDiff {
+                details: CommitDetails {
+                    sha: Oid::from_bytes(&[0x11; 20]).unwrap(),
+                    author_name: "Someone".into(),
+                    author_email: "someone@example.com".into(),
+                    commit_timestamp: 0,
+                    message: "First\n\nThe body.".into(),
+                },
+                files: Vec::new(),
+            }];
+        });
+        let project = Project::test(fs, ["/repo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/repo/rebase-merge/git-rebase-todo", cx)
+            })
+            .await
+            .unwrap();
+        let (todo_editor, cx) =
+            cx.add_window_view(|cx| RebaseTodoEditor::new(project.clone(), buffer.clone(), cx));
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+
+        // The SHAs are resolved from the repository, and the ones that don't resolve are
+        // remembered as unknown.
+        todo_editor.update(cx, |todo_editor, _| {
+            assert_eq!(
+                todo_editor.selected_sha.as_deref(),
+                Some("1111111"),
+                "the cursor starts on the first line"
+            );
+            assert_eq!(
+                todo_editor.commits["1111111"]
+                    .as_ref()
+                    .map(|commit| commit.summary()),
+                Some("First")
+            );
+            assert!(todo_editor.commits["2222222"].is_none());
+            assert!(todo_editor.commits["3333333"].is_none());
+        });
+
+        // Changing the command of the selected lines replaces the flags of fixups, and
+        // skips the commands that don't take a commit.
+        todo_editor.update(cx, |todo_editor, cx| {
+            todo_editor.editor.update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |selections| {
+                    selections.select_ranges([Point::new(0, 0)..Point::new(2, 4)])
+                });
+            });
+            todo_editor.set_action(RebaseAction::Squash, cx);
+        });
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "squash 1111111 First\n\
+                squash 2222222 Second\n\
+                exec cargo test\n\
+                pick 3333333 Third\n\
+                # Rebase 0000000..3333333 onto 0000000\n"
+        );
+
+        // A selection that ends at the start of a line doesn't change that line.
+        todo_editor.update(cx, |todo_editor, cx| {
+            todo_editor.editor.update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |selections| {
+                    selections.select_ranges([Point::new(1, 3)..Point::new(3, 0)])
+                });
+            });
+            todo_editor.set_action(RebaseAction::Drop, cx);
+        });
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "squash 1111111 First\n\
+                drop 2222222 Second\n\
+                exec cargo test\n\
+                pick 3333333 Third\n\
+                # Rebase 0000000..3333333 onto 0000000\n"
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::{stash::StashEntry, Oid};
+use gpui::{
+    rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Subscription, Task, View, ViewContext,
+};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+use crate::{
+    active_repository,
+    commit_view::{build_commit_editor, build_commit_multibuffer},
+    relative_timestamp, HistoryRepository,
+};
+
+/// Saves the repository's local changes to a new stash, described by `message` when one
+/// is given.
+pub(crate) fn stash_changes(
+    message: Option<String>,
+    repository: &HistoryRepository,
+    cx: &mut WindowContext,
+) -> Task<Result<()>> {
+    let repo = repository.repo.clone();
+    cx.background_executor()
+        .spawn(async move { repo.stash_push(message.as_deref(), false) })
+}
+
+#[derive(Clone, Copy)]
+enum StashOperation {
+    Apply,
+    Pop,
+    Drop,
+}
+
+/// A picker of the repository's stashes, with a preview of the changes of the selected one.
+pub struct StashPicker {
+    picker: View<Picker<StashPickerDelegate>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl StashPicker {
+    pub(crate) fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some((repository, _)) = active_repository(workspace, cx) else {
+            return;
+        };
+        let delegate = StashPickerDelegate {
+            project: workspace.project().clone(),
+            repository,
+            stashes: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            last_query: String::new(),
+            preview: None,
+            preview_sha: None,
+            _preview_task: None,
+        };
+        workspace.toggle_modal(cx, |cx| StashPicker::new(delegate, cx));
+    }
+
+    fn new(delegate: StashPickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx).modal(false));
+        picker.update(cx, |picker, cx| picker.delegate.reload_stashes(cx));
+        let _subscriptions = vec![
+            cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent)),
+            // The preview is rendered by this view, so it's re-rendered when it changes.
+            cx.observe(&picker, |_, _, cx| cx.notify()),
+        ];
+        Self {
+            picker,
+            _subscriptions,
+        }
+    }
+}
+
+impl ModalView for StashPicker {}
+impl EventEmitter<DismissEvent> for StashPicker {}
+
+impl FocusableView for StashPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashPicker {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let preview = self.picker.read(cx).delegate.preview.clone();
+        h_flex()
+            .h(rems(32.))
+            .items_start()
+            .elevation_3(cx)
+            .child(div().w(rems(34.)).h_full().child(self.picker.clone()))
+            .child(
+                div()
+                    .w(rems(48.))
+                    .h_full()
+                    .border_l_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .map(|this| match preview {
+                        Some(editor) => this.child(editor),
+                        None => this.child(
+                            h_flex().size_full().justify_center().child(
+                                Label::new("No stash selected")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        ),
+                    }),
+            )
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct StashPickerDelegate {
+    project: Model<Project>,
+    repository: HistoryRepository,
+    stashes: Vec<StashEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    last_query: String,
+    /// An editor showing the changes of the selected stash, once they're loaded.
+    preview: Option<View<Editor>>,
+    preview_sha: Option<Oid>,
+    _preview_task: Option<Task<()>>,
+}
+
+impl StashPickerDelegate {
+    fn selected_stash(&self) -> Option<&StashEntry> {
+        let hit = self.matches.get(self.selected_index)?;
+        self.stashes.get(hit.candidate_id)
+    }
+
+    /// Returns the query as the message of a new stash, when there is one.
+    fn stash_message(&self) -> Option<&str> {
+        let query = self.last_query.trim();
+        (!query.is_empty()).then_some(query)
+    }
+
+    fn reload_stashes(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let repo = self.repository.repo.clone();
+        cx.spawn(|picker, mut cx| async move {
+            let stashes = cx
+                .background_executor()
+                .spawn(async move { repo.stash_list() })
+                .await?;
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.stashes = stashes;
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    /// Loads the changes of the selected stash into the preview, unless they're shown
+    /// already.
+    fn update_preview(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(stash) = self.selected_stash() else {
+            self.preview = None;
+            self.preview_sha = None;
+            self._preview_task = None;
+            return;
+        };
+        let sha = stash.sha;
+        if self.preview_sha == Some(sha) {
+            return;
+        }
+        self.preview_sha = Some(sha);
+
+        let repository = self.repository.clone();
+        let project = self.project.clone();
+        let languages = project.read(cx).languages().clone();
+        self._preview_task = Some(cx.spawn(|picker, mut cx| async move {
+            let repo = repository.repo.clone();
+            let Some(commit) = cx
+                .background_executor()
+                .spawn(async move { repo.show_commit(&sha.to_string()) })
+                .await
+                .log_err()
+            else {
+                return;
+            };
+            let Some(multibuffer) =
+                build_commit_multibuffer(commit, &repository, languages, &mut cx)
+                    .await
+                    .log_err()
+            else {
+                return;
+            };
+            picker
+                .update(&mut cx, |picker, cx| {
+                    if picker.delegate.preview_sha == Some(sha) {
+                        picker.delegate.preview =
+                            Some(build_commit_editor(multibuffer, project, cx));
+                        cx.notify();
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    fn run_operation(&mut self, operation: StashOperation, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(sha) = self.selected_stash().map(|stash| stash.sha) else {
+            return;
+        };
+        let repo = self.repository.repo.clone();
+        cx.spawn(|picker, mut cx| async move {
+            cx.background_executor()
+                .spawn(async move {
+                    // The stashes may have changed since the list was loaded, which shifts
+                    // their indices, so the stash is looked up again by its sha.
+                    let index = repo
+                        .stash_list()?
+                        .into_iter()
+                        .find(|stash| stash.sha == sha)
+                        .map(|stash| stash.index)
+                        .context("the stash no longer exists")?;
+                    match operation {
+                        StashOperation::Apply => repo.stash_apply(index),
+                        StashOperation::Pop => repo.stash_pop(index),
+                        StashOperation::Drop => repo.stash_drop(index),
+                    }
+                })
+                .await?;
+            picker.update(&mut cx, |picker, cx| match operation {
+                StashOperation::Drop => picker.delegate.reload_stashes(cx),
+                StashOperation::Apply | StashOperation::Pop => cx.emit(DismissEvent),
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+}
+
+impl PickerDelegate for StashPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search stashes, or type a message to stash your changes...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + self.stash_message().is_some() as usize
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+        self.update_preview(cx);
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .stashes
+            .iter()
+            .enumerate()
+            .map(|(ix, stash)| StringMatchCandidate {
+                id: ix,
+                char_bag: stash.message.chars().collect(),
+                string: stash.message.clone(),
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, cx| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.last_query = query;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                    delegate.update_preview(cx);
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if self.selected_stash().is_some() {
+            let operation = if secondary {
+                StashOperation::Pop
+            } else {
+                StashOperation::Apply
+            };
+            self.run_operation(operation, cx);
+        } else if let Some(message) = self.stash_message() {
+            stash_changes(Some(message.to_string()), &self.repository, cx)
+                .detach_and_notify_err(cx);
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(SharedString::from(format!("stash-{ix}")))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+        let Some(hit) = self.matches.get(ix) else {
+            let label = format!("Stash changes as \"{}\"", self.stash_message()?);
+            return Some(item.start_slot(Label::new(label)));
+        };
+        let stash = self.stashes.get(hit.candidate_id)?;
+        Some(
+            item.start_slot(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(stash.reference())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(HighlightedLabel::new(
+                        stash.message.clone(),
+                        hit.positions.clone(),
+                    )),
+            )
+            .end_slot(
+                Label::new(relative_timestamp(stash.timestamp))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        self.selected_stash()?;
+        Some(
+            h_flex()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("stash-drop", "Drop").on_click(cx.listener(|picker, _, cx| {
+                        picker.delegate.run_operation(StashOperation::Drop, cx)
+                    })),
+                )
+                .child(
+                    Button::new("stash-pop", "Pop").on_click(cx.listener(|picker, _, cx| {
+                        picker.delegate.run_operation(StashOperation::Pop, cx)
+                    })),
+                )
+                .child(
+                    Button::new("stash-apply", "Apply")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.run_operation(StashOperation::Apply, cx)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{FakeGitRepositoryState, GitFileStatus, GitRepository, RepoPath};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use std::path::Path;
+
+    use crate::tests::init_test;
+
+    #[gpui::test]
+    async fn test_stash_picker(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/repo", json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        let dot_git = Path::new("/repo/.git");
+        let modify_a = |state: &mut FakeGitRepositoryState| {
+            state
+                .worktree_statuses
+                .insert(RepoPath::from(Path::new("a.txt")), GitFileStatus::Modified);
+        };
+        fs.with_git_state(dot_git, false, |state| {
+            state.branch_name = Some("main".into());
+            modify_a(state);
+        });
+        let repo = fs.open_repo(dot_git).unwrap();
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Without any matching stash, confirming the query stashes the changes with it as
+        // the message.
+        let picker = toggle_stash_picker(&workspace, cx);
+        picker.update(cx, |picker, cx| picker.set_query("wip", cx));
+        cx.run_until_parked();
+        picker.update(cx, |picker, cx| picker.delegate.confirm(false, cx));
+        cx.run_until_parked();
+        assert_eq!(stash_messages(&*repo), ["On main: wip"]);
+
+        // A stash that's pushed after the list was loaded shifts the indices of the listed
+        // ones, and the selected stash is still the one that's dropped.
+        let picker = toggle_stash_picker(&workspace, cx);
+        fs.with_git_state(dot_git, false, modify_a);
+        repo.stash_push(Some("other"), false).unwrap();
+        assert_eq!(stash_messages(&*repo), ["On main: other", "On main: wip"]);
+        picker.update(cx, |picker, cx| {
+            assert_eq!(
+                picker.delegate.selected_stash().unwrap().message,
+                "On main: wip"
+            );
+            picker.delegate.run_operation(StashOperation::Drop, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(stash_messages(&*repo), ["On main: other"]);
+    }
+
+    fn toggle_stash_picker(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<StashPickerDelegate>> {
+        workspace.update(cx, |workspace, cx| StashPicker::toggle(workspace, cx));
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<StashPicker>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn stash_messages(repo: &dyn GitRepository) -> Vec<String> {
+        repo.stash_list()
+            .unwrap()
+            .into_iter()
+            .map(|stash| stash.message)
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+use editor::Editor;
+use futures::channel::oneshot;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, View, ViewContext,
+};
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+/// A modal asking for a credential, such as a password or an SSH key's passphrase, that a
+/// git operation needs.
+pub(crate) struct CredentialPrompt {
+    prompt: SharedString,
+    editor: View<Editor>,
+    tx: Option<oneshot::Sender<Option<String>>>,
+}
+
+impl CredentialPrompt {
+    pub(crate) fn register(workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>) {
+        workspace.set_prompt_for_credential(Box::new(|workspace, prompt, cx| {
+            let (tx, rx) = oneshot::channel();
+            workspace.toggle_modal(cx, |cx| CredentialPrompt::new(prompt, tx, cx));
+            rx
+        }));
+    }
+
+    fn new(
+        prompt: String,
+        tx: oneshot::Sender<Option<String>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        // Everything but user names is secret.
+        let masked = !prompt.trim_start().to_lowercase().starts_with("username");
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_masked(masked, cx);
+            editor
+        });
+        Self {
+            prompt: prompt.into(),
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(Some(self.editor.read(cx).text(cx))).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for CredentialPrompt {}
+impl EventEmitter<DismissEvent> for CredentialPrompt {}
+
+impl FocusableView for CredentialPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for CredentialPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("CredentialPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w_96()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(self.prompt.clone()).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .h_px()
+                    .w_full()
+                    .bg(cx.theme().colors().element_background),
+            )
+            .child(div().px_2().py_1p5().child(self.editor.clone()))
+    }
+}
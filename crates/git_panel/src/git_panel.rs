@@ -0,0 +1,933 @@
+mod credential_prompt;
+mod git_panel_settings;
+mod remote;
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use credential_prompt::CredentialPrompt;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use git::{
+    remote::PullStrategy,
+    repository::{GitFileStatus, GitRepository, RepoPath},
+    status::GitStatusPair,
+};
+use git_panel_settings::{GitPanelDockPosition, GitPanelSettings};
+use gpui::{
+    actions, uniform_list, Action, AnyElement, AppContext, AsyncWindowContext, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, Model, Render, Subscription, Task,
+    UniformListScrollHandle, View, WeakView,
+};
+use language::Capability;
+use project::{Fs, Project, ProjectPath};
+use remote::{run_remote_operation, RemoteOperation};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{prelude::*, Checkbox, CheckboxWithLabel, ListItem, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::{NotifyResultExt, NotifyTaskExt},
+    Workspace,
+};
+
+actions!(
+    git_panel,
+    [
+        ToggleFocus,
+        StageAll,
+        UnstageAll,
+        Commit,
+        ToggleAmend,
+        OpenMergeConflicts,
+        Fetch,
+        Pull,
+        PullRebase,
+        Push
+    ]
+);
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub struct GitPanel {
+    fs: Arc<dyn Fs>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    width: Option<Pixels>,
+    active: bool,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    commit_editor: View<Editor>,
+    repository: Option<PanelRepository>,
+    entries: Vec<GitPanelEntry>,
+    amend: bool,
+    pending_serialization: Task<Option<()>>,
+    update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The repository containing the active entry, which the panel shows.
+struct PanelRepository {
+    /// The project path of the repository's working directory.
+    work_directory: ProjectPath,
+    repo: Arc<dyn GitRepository>,
+    branch_name: Option<String>,
+}
+
+impl PanelRepository {
+    fn project_path(&self, repo_path: &RepoPath) -> ProjectPath {
+        ProjectPath {
+            worktree_id: self.work_directory.worktree_id,
+            path: self.work_directory.path.join(&repo_path.0).into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum GitPanelEntry {
+    Header(GitFileStatus),
+    File(ChangedFile),
+}
+
+#[derive(Clone, Debug)]
+struct ChangedFile {
+    repo_path: RepoPath,
+    status: GitFileStatus,
+    /// Whether the file's changes are staged, which is indeterminate when only some are.
+    staged: Selection,
+}
+
+impl ChangedFile {
+    fn has_staged_changes(&self) -> bool {
+        self.staged != Selection::Unselected
+    }
+
+    fn has_unstaged_changes(&self) -> bool {
+        self.staged != Selection::Selected
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<GitPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &Fetch, cx| {
+            run_remote_operation(RemoteOperation::Fetch, workspace, cx).detach_and_notify_err(cx);
+        });
+        workspace.register_action(|workspace, _: &Pull, cx| {
+            run_remote_operation(RemoteOperation::Pull(PullStrategy::Merge), workspace, cx)
+                .detach_and_notify_err(cx);
+        });
+        workspace.register_action(|workspace, _: &PullRebase, cx| {
+            run_remote_operation(RemoteOperation::Pull(PullStrategy::Rebase), workspace, cx)
+                .detach_and_notify_err(cx);
+        });
+        workspace.register_action(|workspace, _: &Push, cx| {
+            run_remote_operation(RemoteOperation::Push, workspace, cx).detach_and_notify_err(cx);
+        });
+        CredentialPrompt::register(workspace, cx);
+    })
+    .detach();
+}
+
+impl GitPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+            .await
+            .context("loading git panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedGitPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| {
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::auto_height(6, cx);
+                editor.set_placeholder_text("Commit message", cx);
+                editor
+            });
+
+            let project_subscription =
+                cx.subscribe(&project, |git_panel, _, event, cx| match event {
+                    project::Event::WorktreeAdded
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeUpdatedGitRepositories => {
+                        git_panel.update_entries(Some(UPDATE_DEBOUNCE), cx);
+                    }
+                    project::Event::ActiveEntryChanged(_) => {
+                        let work_directory = git_panel
+                            .project
+                            .read(cx)
+                            .active_repository(cx)
+                            .map(|(_, work_directory)| work_directory);
+                        let shown_work_directory = git_panel
+                            .repository
+                            .as_ref()
+                            .map(|repository| &repository.work_directory);
+                        if work_directory.as_ref() != shown_work_directory {
+                            git_panel.update_entries(None, cx);
+                        }
+                    }
+                    _ => {}
+                });
+
+            let mut git_panel_settings = *GitPanelSettings::get_global(cx);
+            let settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
+                let new_settings = *GitPanelSettings::get_global(cx);
+                if git_panel_settings != new_settings {
+                    git_panel_settings = new_settings;
+                    cx.notify();
+                }
+            });
+
+            let mut git_panel = Self {
+                fs,
+                workspace,
+                project,
+                width: None,
+                active: false,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                commit_editor,
+                repository: None,
+                entries: Vec::new(),
+                amend: false,
+                pending_serialization: Task::ready(None),
+                update_task: Task::ready(()),
+                _subscriptions: vec![project_subscription, settings_subscription],
+            };
+            git_panel.update_entries(None, cx);
+            git_panel
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self, _: &ViewContext<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitPanel");
+        dispatch_context
+    }
+
+    /// Reloads the changed files and their staged state from the repository, after
+    /// `debounce` when it is set.
+    fn update_entries(&mut self, debounce: Option<Duration>, cx: &mut ViewContext<Self>) {
+        let Some((repo, work_directory)) = self.project.read(cx).active_repository(cx) else {
+            self.repository = None;
+            self.entries.clear();
+            self.update_task = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        self.update_task = cx.spawn(|git_panel, mut cx| async move {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+
+            let Some((branch_name, entries)) = cx
+                .background_executor()
+                .spawn({
+                    let repo = repo.clone();
+                    async move {
+                        let statuses = repo.statuses(Path::new(""))?;
+                        anyhow::Ok((repo.branch_name(), changed_file_entries(&statuses.entries)))
+                    }
+                })
+                .await
+                .log_err()
+            else {
+                return;
+            };
+
+            git_panel
+                .update(&mut cx, |git_panel, cx| {
+                    git_panel.repository = Some(PanelRepository {
+                        work_directory,
+                        repo,
+                        branch_name,
+                    });
+                    git_panel.entries = entries;
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    fn changed_files(&self) -> impl Iterator<Item = &ChangedFile> {
+        self.entries.iter().filter_map(|entry| match entry {
+            GitPanelEntry::Header(_) => None,
+            GitPanelEntry::File(file) => Some(file),
+        })
+    }
+
+    /// Runs a repository operation in the background, showing its error if it fails, and
+    /// reloads the changed files afterwards.
+    fn run_git_operation(
+        &mut self,
+        operation: impl FnOnce(&dyn GitRepository) -> anyhow::Result<()> + Send + 'static,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Option<()>> {
+        let Some(repo) = self
+            .repository
+            .as_ref()
+            .map(|repository| repository.repo.clone())
+        else {
+            return Task::ready(None);
+        };
+        cx.spawn(|git_panel, mut cx| async move {
+            let result = cx
+                .background_executor()
+                .spawn(async move { operation(repo.as_ref()) })
+                .await
+                .notify_async_err(&mut cx);
+            git_panel
+                .update(&mut cx, |git_panel, cx| git_panel.update_entries(None, cx))
+                .ok();
+            result
+        })
+    }
+
+    fn toggle_staged(&mut self, repo_path: RepoPath, stage: bool, cx: &mut ViewContext<Self>) {
+        self.run_git_operation(
+            move |repo| {
+                if stage {
+                    repo.stage_paths(&[repo_path])
+                } else {
+                    repo.unstage_paths(&[repo_path])
+                }
+            },
+            cx,
+        )
+        .detach();
+    }
+
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .changed_files()
+            .filter(|file| file.has_unstaged_changes())
+            .map(|file| file.repo_path.clone())
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            self.run_git_operation(move |repo| repo.stage_paths(&paths), cx)
+                .detach();
+        }
+    }
+
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        let paths = self
+            .changed_files()
+            .filter(|file| file.has_staged_changes())
+            .map(|file| file.repo_path.clone())
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            self.run_git_operation(move |repo| repo.unstage_paths(&paths), cx)
+                .detach();
+        }
+    }
+
+    fn toggle_amend(&mut self, _: &ToggleAmend, cx: &mut ViewContext<Self>) {
+        self.amend = !self.amend;
+        cx.notify();
+    }
+
+    fn commit(&mut self, _: &Commit, cx: &mut ViewContext<Self>) {
+        let message = self.commit_editor.read(cx).text(cx);
+        let amend = self.amend;
+        let commit = self.run_git_operation(move |repo| repo.commit(&message, amend), cx);
+        cx.spawn(|git_panel, mut cx| async move {
+            if commit.await.is_some() {
+                git_panel
+                    .update(&mut cx, |git_panel, cx| {
+                        git_panel.amend = false;
+                        git_panel
+                            .commit_editor
+                            .update(cx, |editor, cx| editor.clear(cx));
+                        cx.notify();
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Opens a multibuffer with an excerpt for every merge conflict in the conflicted files.
+    fn open_merge_conflicts(&mut self, _: &OpenMergeConflicts, cx: &mut ViewContext<Self>) {
+        let Some(repository) = self.repository.as_ref() else {
+            return;
+        };
+        let open_buffers = self
+            .changed_files()
+            .filter(|file| file.status == GitFileStatus::Conflict)
+            .map(|file| {
+                let project_path = repository.project_path(&file.repo_path);
+                self.project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx))
+            })
+            .collect::<Vec<_>>();
+        if open_buffers.is_empty() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let buffers = futures::future::join_all(open_buffers)
+                .await
+                .into_iter()
+                .filter_map(|buffer| buffer.log_err())
+                .collect::<Vec<_>>();
+            let replica_id = project.update(&mut cx, |project, _| project.replica_id())?;
+            let multibuffer = cx.new_model(|cx| {
+                let mut multibuffer = MultiBuffer::new(replica_id, Capability::ReadWrite)
+                    .with_title("Merge Conflicts".to_string());
+                for buffer in buffers {
+                    let conflicts = buffer.read(cx).snapshot().merge_conflicts();
+                    multibuffer.push_excerpts_with_context_lines(
+                        buffer,
+                        conflicts
+                            .into_iter()
+                            .map(|conflict| conflict.range)
+                            .collect(),
+                        DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    );
+                }
+                multibuffer
+            })?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let editor =
+                    cx.new_view(|cx| Editor::for_multibuffer(multibuffer, Some(project), true, cx));
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_file(&mut self, repo_path: &RepoPath, cx: &mut ViewContext<Self>) {
+        let Some(project_path) = self
+            .repository
+            .as_ref()
+            .map(|repository| repository.project_path(repo_path))
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let branch_name = match &self.repository {
+            Some(repository) => repository
+                .branch_name
+                .clone()
+                .unwrap_or_else(|| "Detached HEAD".to_string()),
+            None => "No Git repository".to_string(),
+        };
+        let has_unstaged = self.changed_files().any(ChangedFile::has_unstaged_changes);
+        let has_staged = self.changed_files().any(ChangedFile::has_staged_changes);
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Icon::new(IconName::FileGit).color(Color::Muted))
+                    .child(Label::new(branch_name).color(Color::Muted)),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("fetch", IconName::ArrowCircle)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.repository.is_none())
+                            .tooltip(|cx| Tooltip::for_action("Fetch", &Fetch, cx))
+                            .on_click(|_, cx| cx.dispatch_action(Fetch.boxed_clone())),
+                    )
+                    .child(
+                        IconButton::new("pull", IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.repository.is_none())
+                            .tooltip(|cx| Tooltip::for_action("Pull", &Pull, cx))
+                            .on_click(|_, cx| cx.dispatch_action(Pull.boxed_clone())),
+                    )
+                    .child(
+                        IconButton::new("push", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.repository.is_none())
+                            .tooltip(|cx| Tooltip::for_action("Push", &Push, cx))
+                            .on_click(|_, cx| cx.dispatch_action(Push.boxed_clone())),
+                    )
+                    .child(
+                        Button::new("stage-all", "Stage All")
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_unstaged)
+                            .on_click(
+                                cx.listener(|git_panel, _, cx| git_panel.stage_all(&StageAll, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("unstage-all", "Unstage All")
+                            .label_size(LabelSize::Small)
+                            .disabled(!has_staged)
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.unstage_all(&UnstageAll, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &GitPanelEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        match entry {
+            GitPanelEntry::Header(status) => h_flex()
+                .id(ix)
+                .px_2()
+                .pt_2()
+                .justify_between()
+                .child(
+                    Label::new(match status {
+                        GitFileStatus::Conflict => "Conflicts",
+                        GitFileStatus::Modified => "Modified",
+                        GitFileStatus::Added => "Added",
+                        GitFileStatus::Deleted => "Deleted",
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .when(*status == GitFileStatus::Conflict, |header| {
+                    header.child(
+                        Button::new("open-merge-conflicts", "Resolve")
+                            .label_size(LabelSize::Small)
+                            .tooltip(|cx| {
+                                Tooltip::for_action(
+                                    "Open All Merge Conflicts",
+                                    &OpenMergeConflicts,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.open_merge_conflicts(&OpenMergeConflicts, cx)
+                            })),
+                    )
+                })
+                .into_any_element(),
+            GitPanelEntry::File(file) => {
+                let stage = file.has_unstaged_changes();
+                let repo_path = file.repo_path.clone();
+                ListItem::new(ix)
+                    .start_slot(
+                        Checkbox::new(("staged", ix), file.staged).on_click(cx.listener({
+                            let repo_path = repo_path.clone();
+                            move |git_panel, _, cx| {
+                                git_panel.toggle_staged(repo_path.clone(), stage, cx);
+                                cx.stop_propagation();
+                            }
+                        })),
+                    )
+                    .child(
+                        Label::new(file.repo_path.display().to_string())
+                            .color(status_color(file.status)),
+                    )
+                    .on_click(cx.listener(move |git_panel, _, cx| {
+                        git_panel.open_file(&repo_path, cx);
+                    }))
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_commit_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let can_commit = self.amend || self.changed_files().any(ChangedFile::has_staged_changes);
+        v_flex()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .p_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.commit_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(CheckboxWithLabel::new(
+                        "amend",
+                        Label::new("Amend"),
+                        self.amend.into(),
+                        cx.listener(|git_panel, selection: &Selection, cx| {
+                            git_panel.amend = *selection == Selection::Selected;
+                            cx.notify();
+                        }),
+                    ))
+                    .child(
+                        Button::new("commit", if self.amend { "Amend" } else { "Commit" })
+                            .style(ButtonStyle::Filled)
+                            .disabled(self.repository.is_none() || !can_commit)
+                            .tooltip(|cx| Tooltip::for_action("Commit Staged Changes", &Commit, cx))
+                            .on_click(
+                                cx.listener(|git_panel, _, cx| git_panel.commit(&Commit, cx)),
+                            ),
+                    ),
+            )
+    }
+}
+
+/// Groups the changed files of a repository by status, each group under a header, marking
+/// the files whose changes are all or partly staged.
+fn changed_file_entries(statuses: &[(RepoPath, GitStatusPair)]) -> Vec<GitPanelEntry> {
+    let files = statuses
+        .iter()
+        .map(|(repo_path, status)| {
+            let staged = match (status.index_status, status.worktree_status) {
+                (None, _) | (Some(GitFileStatus::Conflict), _) => Selection::Unselected,
+                (Some(_), None) => Selection::Selected,
+                (Some(_), Some(_)) => Selection::Indeterminate,
+            };
+            ChangedFile {
+                repo_path: repo_path.clone(),
+                status: status.combined(),
+                staged,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut entries = Vec::new();
+    for group_status in [
+        GitFileStatus::Conflict,
+        GitFileStatus::Modified,
+        GitFileStatus::Added,
+        GitFileStatus::Deleted,
+    ] {
+        let mut group = files
+            .iter()
+            .filter(|file| file.status == group_status)
+            .cloned()
+            .map(GitPanelEntry::File)
+            .peekable();
+        if group.peek().is_some() {
+            entries.push(GitPanelEntry::Header(group_status));
+            entries.extend(group);
+        }
+    }
+    entries
+}
+
+fn status_color(status: GitFileStatus) -> Color {
+    match status {
+        GitFileStatus::Added => Color::Created,
+        GitFileStatus::Modified => Color::Modified,
+        GitFileStatus::Deleted => Color::Deleted,
+        GitFileStatus::Conflict => Color::Conflict,
+    }
+}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "Git Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match GitPanelSettings::get_global(cx).dock {
+            GitPanelDockPosition::Left => DockPosition::Left,
+            GitPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => GitPanelDockPosition::Left,
+                    DockPosition::Right => GitPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active && !self.active {
+            self.update_entries(None, cx);
+        }
+        self.active = active;
+        self.serialize(cx);
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.commit_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = if self.entries.is_empty() {
+            v_flex()
+                .flex_1()
+                .justify_center()
+                .child(
+                    h_flex().justify_center().child(
+                        Label::new(if self.repository.is_some() {
+                            "No changes"
+                        } else {
+                            "No Git repository"
+                        })
+                        .color(Color::Muted),
+                    ),
+                )
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.view().clone(),
+                "git-panel-entries",
+                self.entries.len(),
+                |git_panel, range, cx| {
+                    range
+                        .filter_map(|ix| {
+                            let entry = git_panel.entries.get(ix)?;
+                            Some(git_panel.render_entry(ix, entry, cx))
+                        })
+                        .collect()
+                },
+            )
+            .flex_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("git-panel")
+            .size_full()
+            .key_context(self.dispatch_context(cx))
+            .on_action(cx.listener(Self::stage_all))
+            .on_action(cx.listener(Self::unstage_all))
+            .on_action(cx.listener(Self::toggle_amend))
+            .on_action(cx.listener(Self::commit))
+            .on_action(cx.listener(Self::open_merge_conflicts))
+            .track_focus(&self.focus_handle)
+            .child(self.render_header(cx))
+            .child(entries)
+            .child(self.render_commit_editor(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed_files(
+        statuses: &[(&str, Option<GitFileStatus>, Option<GitFileStatus>)],
+    ) -> Vec<String> {
+        let statuses = statuses
+            .iter()
+            .map(|(path, index_status, worktree_status)| {
+                (
+                    RepoPath::from(Path::new(path)),
+                    GitStatusPair {
+                        index_status: *index_status,
+                        worktree_status: *worktree_status,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        changed_file_entries(&statuses)
+            .into_iter()
+            .map(|entry| match entry {
+                GitPanelEntry::Header(status) => format!("{status:?}"),
+                GitPanelEntry::File(file) => format!(
+                    "  {}{}",
+                    file.repo_path.display(),
+                    match file.staged {
+                        Selection::Selected => " (staged)",
+                        Selection::Indeterminate => " (partly staged)",
+                        Selection::Unselected => "",
+                    }
+                ),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_changed_file_entries() {
+        use GitFileStatus::*;
+
+        assert_eq!(
+            changed_files(&[
+                ("a.txt", None, Some(Modified)),
+                ("b.txt", Some(Added), None),
+                ("c.txt", Some(Conflict), Some(Conflict)),
+                ("d.txt", None, Some(Modified)),
+                ("e.txt", Some(Modified), None),
+            ]),
+            [
+                "Conflict",
+                "  c.txt",
+                "Modified",
+                "  a.txt",
+                "  d.txt",
+                "  e.txt (staged)",
+                "Added",
+                "  b.txt (staged)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deleted_and_renamed_files() {
+        use GitFileStatus::*;
+
+        // A rename is staged as the deletion of the old path and the addition of the new one.
+        assert_eq!(
+            changed_files(&[
+                ("deleted.txt", None, Some(Deleted)),
+                ("new-name.txt", Some(Added), None),
+                ("old-name.txt", Some(Deleted), None),
+                ("staged-deletion.txt", Some(Deleted), None),
+            ]),
+            [
+                "Added",
+                "  new-name.txt (staged)",
+                "Deleted",
+                "  deleted.txt",
+                "  old-name.txt (staged)",
+                "  staged-deletion.txt (staged)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partly_staged_files() {
+        use GitFileStatus::*;
+
+        assert_eq!(
+            changed_files(&[
+                ("added-then-modified.txt", Some(Added), Some(Modified)),
+                ("modified-twice.txt", Some(Modified), Some(Modified)),
+                ("modified-then-deleted.txt", Some(Modified), Some(Deleted)),
+                ("untracked-again.txt", Some(Deleted), Some(Added)),
+            ]),
+            [
+                "Modified",
+                "  modified-twice.txt (partly staged)",
+                "Added",
+                "  added-then-modified.txt (partly staged)",
+                "  untracked-again.txt (partly staged)",
+                "Deleted",
+                "  modified-then-deleted.txt (partly staged)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt as _,
+};
+use git::remote::{PullStrategy, RemoteDelegate};
+use gpui::{Task, ViewContext};
+use workspace::Workspace;
+
+/// An operation talking to the remote of the active repository.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RemoteOperation {
+    Fetch,
+    Pull(PullStrategy),
+    Push,
+}
+
+impl RemoteOperation {
+    fn title(&self, branch_name: Option<&str>) -> String {
+        let verb = match self {
+            RemoteOperation::Fetch => return "Fetching".to_string(),
+            RemoteOperation::Pull(_) => "Pulling",
+            RemoteOperation::Push => "Pushing",
+        };
+        match branch_name {
+            Some(branch_name) => format!("{verb} {branch_name}"),
+            None => verb.to_string(),
+        }
+    }
+}
+
+/// Forwards the progress and the prompts of an operation running in the background to the
+/// foreground, where they're shown.
+struct ForwardingDelegate {
+    progress_tx: mpsc::UnboundedSender<String>,
+    prompt_tx: mpsc::UnboundedSender<(String, oneshot::Sender<Option<String>>)>,
+}
+
+impl RemoteDelegate for ForwardingDelegate {
+    fn progress(&self, message: &str) {
+        self.progress_tx.unbounded_send(message.to_string()).ok();
+    }
+
+    /// Blocks the thread of the operation until the prompt is answered in the foreground.
+    fn ask(&self, prompt: &str) -> Option<String> {
+        let (answer_tx, answer_rx) = oneshot::channel();
+        self.prompt_tx
+            .unbounded_send((prompt.to_string(), answer_tx))
+            .ok()?;
+        futures::executor::block_on(answer_rx).ok().flatten()
+    }
+}
+
+/// Runs an operation on the remote of the active repository in the background. Its
+/// progress is shown by the activity indicator, and the credentials it needs are asked for
+/// with the workspace's credential prompt.
+pub(crate) fn run_remote_operation(
+    operation: RemoteOperation,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let Some((repo, _)) = project.read(cx).active_repository(cx) else {
+        return Task::ready(Err(anyhow!("no git repository in the project")));
+    };
+    let title = operation.title(repo.branch_name().as_deref());
+    let operation_id = project.update(cx, |project, cx| {
+        project.start_git_remote_operation(title, cx)
+    });
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded();
+    let (prompt_tx, mut prompt_rx) = mpsc::unbounded();
+    let delegate = ForwardingDelegate {
+        progress_tx,
+        prompt_tx,
+    };
+    // The operation blocks until git exits, and the delegate until the user answers its
+    // prompts, so it runs on a thread of its own rather than on the background executor.
+    // The channels close once the operation is done with the delegate, which ends the
+    // tasks forwarding them.
+    let (result_tx, result_rx) = oneshot::channel();
+    let spawned = std::thread::Builder::new()
+        .name("git remote operation".into())
+        .spawn(move || {
+            let result = match operation {
+                RemoteOperation::Fetch => repo.fetch(&delegate),
+                RemoteOperation::Pull(strategy) => repo.pull(strategy, &delegate),
+                RemoteOperation::Push => repo.push(&delegate),
+            };
+            result_tx.send(result).ok();
+        });
+    if let Err(error) = spawned {
+        project.update(cx, |project, cx| {
+            project.finish_git_remote_operation(operation_id, cx)
+        });
+        return Task::ready(Err(anyhow!("failed to start the git operation: {error}")));
+    }
+    let progress_task = cx.spawn({
+        let project = project.clone();
+        |_, mut cx| async move {
+            while let Some(message) = progress_rx.next().await {
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.update_git_remote_operation(operation_id, message, cx)
+                    })
+                    .ok();
+            }
+        }
+    });
+    let prompt_task = cx.spawn(|workspace, mut cx| async move {
+        while let Some((prompt, answer_tx)) = prompt_rx.next().await {
+            let Ok(answer_rx) = workspace.update(&mut cx, |workspace, cx| {
+                workspace.prompt_for_credential(prompt, cx)
+            }) else {
+                break;
+            };
+            answer_tx.send(answer_rx.await.ok().flatten()).ok();
+        }
+    });
+
+    cx.spawn(|_, mut cx| async move {
+        let result = result_rx
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("the git operation stopped unexpectedly")));
+        progress_task.await;
+        prompt_task.await;
+        project.update(&mut cx, |project, cx| {
+            project.finish_git_remote_operation(operation_id, cx)
+        })?;
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::remote::UpstreamStatus;
+    use gpui::{Model, TestAppContext, View, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_run_remote_operation(cx: &mut TestAppContext) {
+        // The operations run on a thread of their own.
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/repo", json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+            state.branch_name = Some("main".into());
+            state.upstream_status = Some(UpstreamStatus {
+                ahead: 1,
+                behind: 2,
+            });
+        });
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        // Pushing is rejected while the branch is behind its upstream, until it's pulled.
+        let error = run(RemoteOperation::Push, &workspace, cx)
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("updates were rejected"),
+            "unexpected error: {error}"
+        );
+        assert_no_operations(&project, cx);
+        run(RemoteOperation::Pull(PullStrategy::Merge), &workspace, cx)
+            .await
+            .unwrap();
+        assert_no_operations(&project, cx);
+        run(RemoteOperation::Push, &workspace, cx).await.unwrap();
+        assert_no_operations(&project, cx);
+    }
+
+    /// Starts an operation, which is listed by the project until it's done.
+    fn run(
+        operation: RemoteOperation,
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Task<Result<()>> {
+        workspace.update(cx, |workspace, cx| {
+            let task = run_remote_operation(operation, workspace, cx);
+            let titles = workspace
+                .project()
+                .read(cx)
+                .git_remote_operations()
+                .map(|operation| operation.title.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(titles, [operation.title(Some("main"))]);
+            task
+        })
+    }
+
+    fn assert_no_operations(project: &Model<Project>, cx: &mut VisualTestContext) {
+        project.update(cx, |project, _| {
+            assert_eq!(project.git_remote_operations().count(), 0);
+        });
+    }
+}
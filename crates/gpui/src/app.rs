@@ -578,6 +578,12 @@ impl AppContext {
         self.platform.read_from_clipboard()
     }
 
+    /// Reads an image from the platform clipboard, returning its encoded bytes, such as a
+    /// PNG, when the clipboard holds one.
+    pub fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        self.platform.read_image_from_clipboard()
+    }
+
     /// Writes credentials to the platform keychain.
     pub fn write_credentials(
         &self,
@@ -174,6 +174,8 @@ pub(crate) trait Platform: 'static {
     #[cfg(target_os = "linux")]
     fn read_from_primary(&self) -> Option<ClipboardItem>;
     fn read_from_clipboard(&self) -> Option<ClipboardItem>;
+    /// Reads an image from the clipboard, returning its encoded bytes, such as a PNG.
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>>;
 
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>>;
     fn read_credentials(&self, url: &str) -> Task<Result<Option<(String, Vec<u8>)>>>;
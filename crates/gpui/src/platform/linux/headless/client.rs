@@ -99,6 +99,10 @@ impl LinuxClient for HeadlessClient {
         None
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        None
+    }
+
     fn run(&self) {
         let mut event_loop = self
             .0
@@ -53,6 +53,8 @@ pub(crate) const SCROLL_LINES: f64 = 3.0;
 pub(crate) const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
 pub(crate) const DOUBLE_CLICK_DISTANCE: Pixels = px(5.0);
 pub(crate) const KEYRING_LABEL: &str = "zed-github-account";
+/// The clipboard format images are read in.
+pub(crate) const IMAGE_PNG_MIME_TYPE: &str = "image/png";
 
 const FILE_PICKER_PORTAL_MISSING: &str =
     "Couldn't open file picker due to missing xdg-desktop-portal implementation.";
@@ -76,6 +78,7 @@ pub trait LinuxClient {
     fn write_to_clipboard(&self, item: ClipboardItem);
     fn read_from_primary(&self) -> Option<ClipboardItem>;
     fn read_from_clipboard(&self) -> Option<ClipboardItem>;
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>>;
     fn active_window(&self) -> Option<AnyWindowHandle>;
     fn window_stack(&self) -> Option<Vec<AnyWindowHandle>>;
     fn run(&self);
@@ -503,6 +506,10 @@ impl<P: LinuxClient + 'static> Platform for P {
         self.read_from_clipboard()
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        self.read_image_from_clipboard()
+    }
+
     fn add_recent_document(&self, _path: &Path) {}
 }
 
@@ -604,6 +611,14 @@ pub(super) unsafe fn read_fd(mut fd: FileDescriptor) -> Result<String> {
     Ok(result)
 }
 
+pub(super) unsafe fn read_fd_bytes(mut fd: FileDescriptor) -> Result<Vec<u8>> {
+    let mut file = File::from_raw_fd(fd.as_raw_fd());
+
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
 impl CursorStyle {
     pub(super) fn to_shape(&self) -> Shape {
         match self {
@@ -742,6 +742,10 @@ impl LinuxClient for WaylandClient {
         self.0.borrow_mut().clipboard.read()
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        self.0.borrow_mut().clipboard.read_image()
+    }
+
     fn active_window(&self) -> Option<AnyWindowHandle> {
         self.0
             .borrow_mut()
@@ -9,7 +9,10 @@ use filedescriptor::Pipe;
 use wayland_client::{protocol::wl_data_offer::WlDataOffer, Connection};
 use wayland_protocols::wp::primary_selection::zv1::client::zwp_primary_selection_offer_v1::ZwpPrimarySelectionOfferV1;
 
-use crate::{platform::linux::platform::read_fd, ClipboardItem, WaylandClientStatePtr};
+use crate::{
+    platform::linux::platform::{read_fd, read_fd_bytes, IMAGE_PNG_MIME_TYPE},
+    ClipboardItem, WaylandClientStatePtr,
+};
 
 pub(crate) const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
 pub(crate) const FILE_LIST_MIME_TYPE: &str = "text/uri-list";
@@ -155,6 +158,31 @@ impl Clipboard {
         }
     }
 
+    pub fn read_image(&mut self) -> Option<Vec<u8>> {
+        let offer = self.current_offer.clone()?;
+        if !offer.has_mime_type(IMAGE_PNG_MIME_TYPE) {
+            return None;
+        }
+
+        let mime_type = IMAGE_PNG_MIME_TYPE.to_string();
+        let pipe = Pipe::new().unwrap();
+        offer.inner.receive(mime_type, unsafe {
+            BorrowedFd::borrow_raw(pipe.write.as_raw_fd())
+        });
+        let fd = pipe.read;
+        drop(pipe.write);
+
+        self.connection.flush().unwrap();
+
+        match unsafe { read_fd_bytes(fd) } {
+            Ok(bytes) => Some(bytes).filter(|bytes| !bytes.is_empty()),
+            Err(err) => {
+                log::error!("error reading clipboard pipe: {err:?}");
+                None
+            }
+        }
+    }
+
     pub fn read_primary(&mut self) -> Option<ClipboardItem> {
         let offer = self.current_primary_offer.clone()?;
         if let Some(cached) = self.cached_primary_read.clone() {
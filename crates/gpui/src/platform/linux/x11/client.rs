@@ -26,7 +26,7 @@ use xim::{AttributeName, InputStyle};
 use xkbc::x11::ffi::{XKB_X11_MIN_MAJOR_XKB_VERSION, XKB_X11_MIN_MINOR_XKB_VERSION};
 use xkbcommon::xkb::{self as xkbc, LayoutIndex, ModMask};
 
-use crate::platform::linux::LinuxClient;
+use crate::platform::linux::{LinuxClient, IMAGE_PNG_MIME_TYPE};
 use crate::platform::{LinuxCommon, PlatformWindow};
 use crate::{
     modifiers_from_xinput_info, point, px, AnyWindowHandle, Bounds, ClipboardItem, CursorStyle,
@@ -1325,6 +1325,29 @@ impl LinuxClient for X11Client {
             .ok()
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        let state = self.0.borrow_mut();
+        let png_atom = state
+            .clipboard
+            .getter
+            .connection
+            .intern_atom(false, IMAGE_PNG_MIME_TYPE.as_bytes())
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        state
+            .clipboard
+            .load(
+                state.clipboard.getter.atoms.clipboard,
+                png_atom,
+                state.clipboard.getter.atoms.property,
+                Duration::from_secs(3),
+            )
+            .ok()
+            .filter(|bytes| !bytes.is_empty())
+    }
+
     fn run(&self) {
         let mut event_loop = self
             .0
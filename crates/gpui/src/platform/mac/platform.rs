@@ -926,6 +926,16 @@ impl Platform for MacPlatform {
         }
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        let state = self.0.lock();
+        unsafe {
+            ["public.png", "public.tiff"].into_iter().find_map(|kind| {
+                self.read_from_pasteboard(state.pasteboard, ns_string(kind))
+                    .map(|bytes| bytes.to_vec())
+            })
+        }
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
         let url = url.to_string();
         let username = username.to_string();
@@ -30,11 +30,11 @@ use windows::{
         System::{
             Com::*,
             DataExchange::{
-                CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard,
-                RegisterClipboardFormatW, SetClipboardData,
+                CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable,
+                OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
             },
             LibraryLoader::*,
-            Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE},
+            Memory::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE},
             Ole::*,
             SystemInformation::*,
             Threading::*,
@@ -56,6 +56,7 @@ pub(crate) struct WindowsPlatform {
     text_system: Arc<DirectWriteTextSystem>,
     clipboard_hash_format: u32,
     clipboard_metadata_format: u32,
+    clipboard_png_format: u32,
     windows_version: WindowsVersion,
     bitmap_factory: ManuallyDrop<IWICImagingFactory>,
     validation_number: usize,
@@ -111,6 +112,7 @@ impl WindowsPlatform {
         let clipboard_hash_format = register_clipboard_format(CLIPBOARD_HASH_FORMAT).unwrap();
         let clipboard_metadata_format =
             register_clipboard_format(CLIPBOARD_METADATA_FORMAT).unwrap();
+        let clipboard_png_format = register_clipboard_format(CLIPBOARD_PNG_FORMAT).unwrap();
         let windows_version = WindowsVersion::new().expect("Error retrieve windows version");
         let validation_number = rand::random::<usize>();
 
@@ -123,6 +125,7 @@ impl WindowsPlatform {
             text_system,
             clipboard_hash_format,
             clipboard_metadata_format,
+            clipboard_png_format,
             windows_version,
             bitmap_factory,
             validation_number,
@@ -538,6 +541,10 @@ impl Platform for WindowsPlatform {
         read_from_clipboard(self.clipboard_hash_format, self.clipboard_metadata_format)
     }
 
+    fn read_image_from_clipboard(&self) -> Option<Vec<u8>> {
+        read_image_from_clipboard(self.clipboard_png_format)
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
         let mut password = password.to_vec();
         let mut username = username.encode_utf16().chain(Some(0)).collect_vec();
@@ -795,6 +802,31 @@ fn read_from_clipboard_inner(hash_format: u32, metadata_format: u32) -> Result<C
     }
 }
 
+fn read_image_from_clipboard(png_format: u32) -> Option<Vec<u8>> {
+    let result = read_image_from_clipboard_inner(png_format).log_err();
+    unsafe { CloseClipboard().log_err() };
+    result.flatten()
+}
+
+fn read_image_from_clipboard_inner(png_format: u32) -> Result<Option<Vec<u8>>> {
+    unsafe {
+        OpenClipboard(None)?;
+        if IsClipboardFormatAvailable(png_format).is_err() {
+            return Ok(None);
+        }
+        let handle = GetClipboardData(png_format)?;
+        let global = HGLOBAL(handle.0 as _);
+        let size = GlobalSize(global);
+        let data = GlobalLock(global);
+        if data.is_null() {
+            return Ok(None);
+        }
+        let bytes = std::slice::from_raw_parts(data.cast::<u8>(), size).to_vec();
+        let _ = GlobalUnlock(global);
+        Ok(Some(bytes))
+    }
+}
+
 fn read_hash_from_clipboard(hash_format: u32) -> Option<u64> {
     unsafe {
         let handle = GetClipboardData(hash_format).log_err()?;
@@ -818,6 +850,7 @@ fn read_metadata_from_clipboard(metadata_format: u32) -> Option<String> {
 // clipboard
 pub const CLIPBOARD_HASH_FORMAT: PCWSTR = windows::core::w!("zed-text-hash");
 pub const CLIPBOARD_METADATA_FORMAT: PCWSTR = windows::core::w!("zed-metadata");
+pub const CLIPBOARD_PNG_FORMAT: PCWSTR = windows::core::w!("PNG");
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use gpui::{actions, AppContext, Model, ModelContext, Task};
+use language::Buffer;
+use project::{CallHierarchyItem, Location, Project};
+use workspace::Workspace;
+
+use crate::{Hierarchy, HierarchyView};
+
+actions!(call_hierarchy, [ShowIncomingCalls, ShowOutgoingCalls]);
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, cx| {
+            HierarchyView::<CallHierarchy>::deploy(workspace, CallDirection::Incoming, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+            HierarchyView::<CallHierarchy>::deploy(workspace, CallDirection::Outgoing, cx)
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CallDirection {
+    /// Expanding an item lists its callers.
+    Incoming,
+    /// Expanding an item lists its callees.
+    Outgoing,
+}
+
+struct CallHierarchy;
+
+impl Hierarchy for CallHierarchy {
+    type Item = CallHierarchyItem;
+    type Direction = CallDirection;
+
+    fn direction_label(direction: CallDirection) -> &'static str {
+        match direction {
+            CallDirection::Incoming => "Incoming Calls",
+            CallDirection::Outgoing => "Outgoing Calls",
+        }
+    }
+
+    fn reverse(direction: CallDirection) -> CallDirection {
+        match direction {
+            CallDirection::Incoming => CallDirection::Outgoing,
+            CallDirection::Outgoing => CallDirection::Incoming,
+        }
+    }
+
+    fn name(item: &CallHierarchyItem) -> &str {
+        &item.lsp_item.name
+    }
+
+    fn detail(item: &CallHierarchyItem) -> Option<&str> {
+        item.lsp_item.detail.as_deref()
+    }
+
+    fn location(item: &CallHierarchyItem) -> &Location {
+        &item.location
+    }
+
+    fn prepare(
+        project: &mut Project,
+        buffer: &Model<Buffer>,
+        position: language::Anchor,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        project.prepare_call_hierarchy(buffer, position, cx)
+    }
+
+    fn children(
+        project: &mut Project,
+        item: &CallHierarchyItem,
+        direction: CallDirection,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<(CallHierarchyItem, Vec<Location>)>>> {
+        let calls = match direction {
+            CallDirection::Incoming => project.incoming_calls(item, cx),
+            CallDirection::Outgoing => project.outgoing_calls(item, cx),
+        };
+        cx.spawn(|_, _| async move {
+            Ok(calls
+                .await?
+                .into_iter()
+                .map(|call| (call.item, call.call_sites))
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,556 @@
+mod call_hierarchy;
+mod type_hierarchy;
+
+use anyhow::Result;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, uniform_list, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext, Model,
+    ModelContext, SharedString, Task, UniformListScrollHandle, View, ViewContext, WeakView,
+    WindowContext,
+};
+use language::{Buffer, ToPoint as _};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{Location, Project};
+use std::ops::Range;
+use ui::{prelude::*, ListItem};
+use util::ResultExt;
+use workspace::{item::Item, SplitDirection, Workspace};
+
+actions!(
+    hierarchy_view,
+    [ToggleDirection, ExpandSelectedEntry, CollapseSelectedEntry]
+);
+
+pub fn init(cx: &mut AppContext) {
+    call_hierarchy::init(cx);
+    type_hierarchy::init(cx);
+}
+
+/// Symbols that a language server relates to each other in two directions, such as
+/// callers and callees, or supertypes and subtypes.
+trait Hierarchy: 'static {
+    type Item: Clone;
+    type Direction: Copy + PartialEq;
+
+    /// Describes the children of an item in the given direction, e.g. "Incoming Calls".
+    fn direction_label(direction: Self::Direction) -> &'static str;
+
+    fn reverse(direction: Self::Direction) -> Self::Direction;
+
+    fn name(item: &Self::Item) -> &str;
+
+    fn detail(item: &Self::Item) -> Option<&str>;
+
+    fn location(item: &Self::Item) -> &Location;
+
+    /// Resolves the items at a position, which become the roots of the tree.
+    fn prepare(
+        project: &mut Project,
+        buffer: &Model<Buffer>,
+        position: language::Anchor,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<Self::Item>>>;
+
+    /// Fetches the children of an item, along with the locations that relate each child to
+    /// the item, such as the call sites between a caller and a callee.
+    fn children(
+        project: &mut Project,
+        item: &Self::Item,
+        direction: Self::Direction,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<(Self::Item, Vec<Location>)>>>;
+}
+
+/// A tree of the items related to the symbols under the cursor, expanded lazily one level
+/// at a time.
+struct HierarchyView<H: Hierarchy> {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    direction: H::Direction,
+    roots: Vec<HierarchyNode<H>>,
+    /// The paths of the expanded nodes' descendants, in display order.
+    visible_entries: Vec<Vec<usize>>,
+    selected_entry_ix: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+struct HierarchyNode<H: Hierarchy> {
+    item: H::Item,
+    /// The locations that relate this node to its parent, empty for roots.
+    locations: Vec<Location>,
+    /// The node's children in the view's direction, `None` until they are first requested.
+    children: Option<Vec<HierarchyNode<H>>>,
+    expanded: bool,
+    load_task: Option<Task<()>>,
+}
+
+impl<H: Hierarchy> HierarchyNode<H> {
+    fn new(item: H::Item, locations: Vec<Location>) -> Self {
+        Self {
+            item,
+            locations,
+            children: None,
+            expanded: false,
+            load_task: None,
+        }
+    }
+}
+
+impl<H: Hierarchy> HierarchyView<H> {
+    fn deploy(workspace: &mut Workspace, direction: H::Direction, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        let Some((buffer, position)) = editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+
+        let project = workspace.project().clone();
+        let items = project.update(cx, |project, cx| H::prepare(project, &buffer, position, cx));
+        cx.spawn(|workspace, mut cx| async move {
+            let items = items.await?;
+            if items.is_empty() {
+                return anyhow::Ok(());
+            }
+            workspace.update(&mut cx, |workspace, cx| {
+                let workspace_handle = workspace.weak_handle();
+                let view =
+                    cx.new_view(|cx| Self::new(workspace_handle, project, direction, items, cx));
+                workspace.split_item(SplitDirection::Right, Box::new(view), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        direction: H::Direction,
+        items: Vec<H::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            workspace,
+            project,
+            direction,
+            roots: items
+                .into_iter()
+                .map(|item| HierarchyNode::new(item, Vec::new()))
+                .collect(),
+            visible_entries: Vec::new(),
+            selected_entry_ix: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        };
+        this.expand_roots(cx);
+        this
+    }
+
+    fn expand_roots(&mut self, cx: &mut ViewContext<Self>) {
+        for ix in 0..self.roots.len() {
+            self.expand_entry(&[ix], cx);
+        }
+        self.selected_entry_ix = (!self.visible_entries.is_empty()).then_some(0);
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&HierarchyNode<H>> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get(*first)?;
+        for ix in rest {
+            node = node.children.as_ref()?.get(*ix)?;
+        }
+        Some(node)
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut HierarchyNode<H>> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for ix in rest {
+            node = node.children.as_mut()?.get_mut(*ix)?;
+        }
+        Some(node)
+    }
+
+    fn expand_entry(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        let direction = self.direction;
+        let project = self.project.clone();
+        let Some(node) = self.node_mut(path) else {
+            return;
+        };
+        node.expanded = true;
+        if node.children.is_none() && node.load_task.is_none() {
+            let children = project.update(cx, |project, cx| {
+                H::children(project, &node.item, direction, cx)
+            });
+            let path = path.to_vec();
+            node.load_task = Some(cx.spawn(|this, mut cx| async move {
+                let children = children.await.log_err().unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    if let Some(node) = this.node_mut(&path) {
+                        node.children = Some(
+                            children
+                                .into_iter()
+                                .map(|(item, locations)| HierarchyNode::new(item, locations))
+                                .collect(),
+                        );
+                        node.load_task = None;
+                    }
+                    this.update_visible_entries();
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        self.update_visible_entries();
+        cx.notify();
+    }
+
+    fn collapse_entry(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        if let Some(node) = self.node_mut(path) {
+            node.expanded = false;
+            self.update_visible_entries();
+            cx.notify();
+        }
+    }
+
+    fn toggle_entry(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        if self.node(path).map_or(false, |node| node.expanded) {
+            self.collapse_entry(path, cx);
+        } else {
+            self.expand_entry(path, cx);
+        }
+    }
+
+    fn update_visible_entries(&mut self) {
+        fn push_entries<H: Hierarchy>(
+            nodes: &[HierarchyNode<H>],
+            path: &mut Vec<usize>,
+            entries: &mut Vec<Vec<usize>>,
+        ) {
+            for (ix, node) in nodes.iter().enumerate() {
+                path.push(ix);
+                entries.push(path.clone());
+                if node.expanded {
+                    if let Some(children) = &node.children {
+                        push_entries(children, path, entries);
+                    }
+                }
+                path.pop();
+            }
+        }
+
+        let selected_path = self
+            .selected_entry_ix
+            .and_then(|ix| self.visible_entries.get(ix).cloned());
+        self.visible_entries.clear();
+        push_entries(&self.roots, &mut Vec::new(), &mut self.visible_entries);
+
+        // Keep the selection on the same node, or on its closest visible ancestor.
+        self.selected_entry_ix = selected_path.and_then(|mut path| loop {
+            if let Some(ix) = self.visible_entries.iter().position(|entry| *entry == path) {
+                break Some(ix);
+            }
+            path.pop();
+            if path.is_empty() {
+                break None;
+            }
+        });
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, cx: &mut ViewContext<Self>) {
+        self.direction = H::reverse(self.direction);
+        for root in &mut self.roots {
+            *root = HierarchyNode::new(root.item.clone(), Vec::new());
+        }
+        self.selected_entry_ix = None;
+        self.expand_roots(cx);
+        cx.emit(());
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let ix = self.selected_entry_ix.map_or(0, |ix| {
+            (ix + 1).min(self.visible_entries.len().saturating_sub(1))
+        });
+        self.select_entry(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let ix = self.selected_entry_ix.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_entry(ix, cx);
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix < self.visible_entries.len() {
+            self.selected_entry_ix = Some(ix);
+            self.scroll_handle.scroll_to_item(ix);
+            cx.notify();
+        }
+    }
+
+    fn selected_path(&self) -> Option<Vec<usize>> {
+        self.visible_entries.get(self.selected_entry_ix?).cloned()
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selected_path() {
+            self.expand_entry(&path, cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        let Some(mut path) = self.selected_path() else {
+            return;
+        };
+        if self.node(&path).map_or(false, |node| node.expanded) {
+            self.collapse_entry(&path, cx);
+        } else if path.len() > 1 {
+            path.pop();
+            if let Some(ix) = self.visible_entries.iter().position(|entry| *entry == path) {
+                self.select_entry(ix, cx);
+            }
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selected_path() {
+            self.open_entry(&path, cx);
+        }
+    }
+
+    /// Navigates to the first location relating an entry to its parent, or to the item
+    /// itself for the roots.
+    fn open_entry(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        let Some(node) = self.node(path) else {
+            return;
+        };
+        let location = node
+            .locations
+            .first()
+            .unwrap_or(H::location(&node.item))
+            .clone();
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.adjacent_pane(cx);
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                location.buffer.clone(),
+                true,
+                true,
+                cx,
+            );
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([location.range.clone()]);
+                });
+            });
+        });
+    }
+
+    fn open_locations(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        let Some(node) = self.node(path) else {
+            return;
+        };
+        let locations = node.locations.clone();
+        let title = format!(
+            "{}: {}",
+            H::direction_label(self.direction),
+            H::name(&node.item)
+        );
+        let replica_id = self.project.read(cx).replica_id();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                Editor::open_locations_in_multibuffer(
+                    workspace, locations, replica_id, title, false, cx,
+                );
+            })
+            .ok();
+    }
+
+    fn title(&self) -> SharedString {
+        let name = self
+            .roots
+            .first()
+            .map(|root| H::name(&root.item))
+            .unwrap_or_default();
+        format!("{}: {name}", H::direction_label(self.direction)).into()
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyView");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_entries(&self, range: Range<usize>, cx: &mut ViewContext<Self>) -> Vec<ListItem> {
+        range
+            .filter_map(|ix| self.render_entry(ix, self.visible_entries.get(ix)?.clone(), cx))
+            .collect()
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        path: Vec<usize>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<ListItem> {
+        let node = self.node(&path)?;
+        let item_location = H::location(&node.item);
+        let buffer = item_location.buffer.read(cx);
+        let row = item_location.range.start.to_point(buffer).row + 1;
+        let location = match buffer.file() {
+            Some(file) => format!("{}:{row}", file.file_name(cx).to_string_lossy()),
+            None => format!("untitled:{row}"),
+        };
+        let toggle = match &node.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let location_count = node.locations.len();
+
+        Some(
+            ListItem::new(ix)
+                .indent_level(path.len() - 1)
+                .selected(self.selected_entry_ix == Some(ix))
+                .toggle(toggle)
+                .on_toggle(cx.listener({
+                    let path = path.clone();
+                    move |this, _, cx| this.toggle_entry(&path, cx)
+                }))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(H::name(&node.item).to_string()))
+                        .when_some(H::detail(&node.item), |row, detail| {
+                            row.child(
+                                Label::new(detail.to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_slot(if node.load_task.is_some() {
+                    Some(
+                        Label::new("Loading…")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .into_any_element(),
+                    )
+                } else if location_count > 1 {
+                    Some(
+                        Button::new(("locations", ix), format!("{location_count} references"))
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener({
+                                let path = path.clone();
+                                move |this, _, cx| this.open_locations(&path, cx)
+                            }))
+                            .into_any_element(),
+                    )
+                } else {
+                    None
+                })
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.selected_entry_ix = Some(ix);
+                    this.open_entry(&path, cx);
+                })),
+        )
+    }
+}
+
+impl<H: Hierarchy> Render for HierarchyView<H> {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let toggle_label = format!("Show {}", H::direction_label(H::reverse(self.direction)));
+
+        v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.title()))
+                    .child(
+                        Button::new("toggle-hierarchy-direction", toggle_label)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.toggle_direction(&ToggleDirection, cx)
+                            })),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "hierarchy-entries",
+                    self.visible_entries.len(),
+                    |this, range, cx| this.render_entries(range, cx),
+                )
+                .size_full()
+                .track_scroll(self.scroll_handle.clone()),
+            )
+    }
+}
+
+impl<H: Hierarchy> EventEmitter<()> for HierarchyView<H> {}
+
+impl<H: Hierarchy> FocusableView for HierarchyView<H> {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<H: Hierarchy> Item for HierarchyView<H> {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(workspace::item::ItemEvent::UpdateTab)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(self.title())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn clone_on_split(
+        &self,
+        _: Option<workspace::WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let items = self.roots.iter().map(|root| root.item.clone()).collect();
+        Some(cx.new_view(|cx| {
+            Self::new(
+                self.workspace.clone(),
+                self.project.clone(),
+                self.direction,
+                items,
+                cx,
+            )
+        }))
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use gpui::{actions, AppContext, Model, ModelContext, Task};
+use language::Buffer;
+use project::{Location, Project, TypeHierarchyItem};
+use workspace::Workspace;
+
+use crate::{Hierarchy, HierarchyView};
+
+actions!(type_hierarchy, [ShowSupertypes, ShowSubtypes]);
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowSupertypes, cx| {
+            HierarchyView::<TypeHierarchy>::deploy(workspace, TypeDirection::Supertypes, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, cx| {
+            HierarchyView::<TypeHierarchy>::deploy(workspace, TypeDirection::Subtypes, cx)
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TypeDirection {
+    /// Expanding an item lists the types it extends or implements.
+    Supertypes,
+    /// Expanding an item lists the types that extend or implement it.
+    Subtypes,
+}
+
+struct TypeHierarchy;
+
+impl Hierarchy for TypeHierarchy {
+    type Item = TypeHierarchyItem;
+    type Direction = TypeDirection;
+
+    fn direction_label(direction: TypeDirection) -> &'static str {
+        match direction {
+            TypeDirection::Supertypes => "Supertypes",
+            TypeDirection::Subtypes => "Subtypes",
+        }
+    }
+
+    fn reverse(direction: TypeDirection) -> TypeDirection {
+        match direction {
+            TypeDirection::Supertypes => TypeDirection::Subtypes,
+            TypeDirection::Subtypes => TypeDirection::Supertypes,
+        }
+    }
+
+    fn name(item: &TypeHierarchyItem) -> &str {
+        &item.lsp_item.name
+    }
+
+    fn detail(item: &TypeHierarchyItem) -> Option<&str> {
+        item.lsp_item.detail.as_deref()
+    }
+
+    fn location(item: &TypeHierarchyItem) -> &Location {
+        &item.location
+    }
+
+    fn prepare(
+        project: &mut Project,
+        buffer: &Model<Buffer>,
+        position: language::Anchor,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        project.prepare_type_hierarchy(buffer, position, cx)
+    }
+
+    fn children(
+        project: &mut Project,
+        item: &TypeHierarchyItem,
+        direction: TypeDirection,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<Vec<(TypeHierarchyItem, Vec<Location>)>>> {
+        let types = match direction {
+            TypeDirection::Supertypes => project.supertypes(item, cx),
+            TypeDirection::Subtypes => project.subtypes(item, cx),
+        };
+        cx.spawn(|_, _| async move {
+            Ok(types
+                .await?
+                .into_iter()
+                .map(|item| (item, Vec::new()))
+                .collect())
+        })
+    }
+}
@@ -8,7 +8,10 @@ use persistence::IMAGE_VIEWER;
 use ui::prelude::*;
 
 use project::{Project, ProjectEntryId, ProjectPath};
-use std::{ffi::OsStr, path::PathBuf};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 use workspace::{
     item::{Item, ProjectItem, SerializableItem, TabContentParams},
     ItemId, Pane, Workspace, WorkspaceId,
@@ -68,6 +71,13 @@ pub struct ImageView {
     focus_handle: FocusHandle,
 }
 
+impl ImageView {
+    /// The absolute path of the image being viewed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
 impl Item for ImageView {
     type Event = ();
 
@@ -8,6 +8,7 @@ use crate::{
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
     language_settings::{language_settings, IndentGuideSettings, LanguageSettings},
     markdown::parse_markdown,
+    merge_conflict::{self, MergeConflict},
     outline::OutlineItem,
     syntax_map::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
@@ -2727,6 +2728,11 @@ impl BufferSnapshot {
             .map(Outline::new)
     }
 
+    /// Returns the regions of the buffer delimited by merge conflict markers.
+    pub fn merge_conflicts(&self) -> Vec<MergeConflict> {
+        merge_conflict::merge_conflicts(&self.text)
+    }
+
     /// Returns all the symbols that contain the given position.
     ///
     /// This method allows passing an optional [SyntaxTheme] to
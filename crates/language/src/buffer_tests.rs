@@ -862,6 +862,61 @@ async fn test_symbols_containing(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+fn test_merge_conflicts(cx: &mut AppContext) {
+    let text = indoc! {"
+        fn main() {
+        <<<<<<< HEAD
+            println!(\"ours\");
+        =======
+            println!(\"theirs\");
+        >>>>>>> feature
+        }
+        <<<<<<< HEAD
+        let a = 1;
+        ||||||| base
+        let a = 0;
+        =======
+        let a = 2;
+        >>>>>>> feature
+        <<<<<<< unterminated
+        ======="};
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let conflicts = snapshot.merge_conflicts();
+    assert_eq!(conflicts.len(), 2);
+
+    let text_for =
+        |range: &Range<Anchor>| snapshot.text_for_range(range.clone()).collect::<String>();
+    assert_eq!(
+        text_for(&conflicts[0].range),
+        "<<<<<<< HEAD\n    println!(\"ours\");\n=======\n    println!(\"theirs\");\n>>>>>>> feature\n"
+    );
+    assert_eq!(text_for(&conflicts[0].ours), "    println!(\"ours\");\n");
+    assert_eq!(
+        text_for(&conflicts[0].theirs),
+        "    println!(\"theirs\");\n"
+    );
+    assert_eq!(conflicts[0].base, None);
+    assert_eq!(conflicts[0].ours_label, "HEAD");
+    assert_eq!(conflicts[0].theirs_label, "feature");
+
+    assert_eq!(text_for(&conflicts[1].ours), "let a = 1;\n");
+    assert_eq!(
+        conflicts[1].base.as_ref().map(text_for).as_deref(),
+        Some("let a = 0;\n")
+    );
+    assert_eq!(text_for(&conflicts[1].theirs), "let a = 2;\n");
+    assert_eq!(
+        conflicts[1].resolved_text(MergeConflictResolution::Both, &snapshot),
+        "let a = 1;\nlet a = 2;\n"
+    );
+    assert_eq!(
+        conflicts[1].resolved_text(MergeConflictResolution::Theirs, &snapshot),
+        "let a = 2;\n"
+    );
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut AppContext) {
     let mut assert = |selection_text, range_markers| {
@@ -125,6 +125,15 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// How the semantic tokens of language servers are highlighted.
+    pub semantic_tokens: SemanticTokens,
+    /// Whether to show the code lenses of language servers above the lines they describe.
+    pub code_lens: bool,
+    /// How to fold by the folding ranges of language servers.
+    pub folding_ranges: FoldingRangeSettings,
+    /// Whether to request document links from language servers, which make text such as
+    /// paths and dependency names navigable.
+    pub document_links: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
 }
@@ -347,6 +356,23 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// How to highlight the semantic tokens of language servers that provide them,
+    /// relative to the syntax highlighting.
+    ///
+    /// Default: overlay
+    pub semantic_tokens: Option<SemanticTokens>,
+    /// Whether to show the code lenses of language servers above the lines they describe,
+    /// such as "Run test" or "3 references".
+    ///
+    /// Default: true
+    pub code_lens: Option<bool>,
+    /// How to fold by the folding ranges of language servers.
+    pub folding_ranges: Option<FoldingRangeSettings>,
+    /// Whether to request document links from language servers, which make text such as
+    /// paths and dependency names navigable with a secondary click.
+    ///
+    /// Default: true
+    pub document_links: Option<bool>,
     /// Task configuration for this language.
     ///
     /// Default: {}
@@ -385,6 +411,19 @@ pub enum SoftWrap {
     PreferredLineLength,
 }
 
+/// Controls how the semantic tokens of language servers are highlighted.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SemanticTokens {
+    /// Do not request semantic tokens.
+    Off,
+    /// Layer the highlights of semantic tokens over the syntax highlights.
+    Overlay,
+    /// Highlight the text covered by semantic tokens with their highlights only,
+    /// replacing its syntax highlights.
+    Replace,
+}
+
 /// Controls the behavior of formatting files when they are saved.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FormatOnSave {
@@ -764,6 +803,34 @@ fn scroll_debounce_ms() -> u64 {
     50
 }
 
+/// The settings for folding by the folding ranges of language servers.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FoldingRangeSettings {
+    /// Whether to fold by the folding ranges of language servers that provide them,
+    /// instead of by indentation.
+    ///
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// The kinds of folding ranges to fold when a buffer is opened.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub folded_by_default: Vec<FoldingRangeKind>,
+}
+
+/// The kinds of folding ranges that language servers report.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingRangeKind {
+    /// A comment.
+    Comment,
+    /// A block of imports.
+    Imports,
+    /// A region delimited by markers such as `#region`.
+    Region,
+}
+
 /// The task settings for a particular language.
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct LanguageTaskConfig {
@@ -1042,6 +1109,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.code_lens, src.code_lens);
+    merge(&mut settings.folding_ranges, src.folding_ranges.clone());
+    merge(&mut settings.document_links, src.document_links);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
@@ -0,0 +1,120 @@
+use std::ops::Range;
+use text::{Anchor, BufferSnapshot, Point};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region of a buffer delimited by git's merge conflict markers.
+///
+/// The ranges of each side start at the beginning of the line following its marker and end
+/// at the beginning of the next marker, so their text includes the trailing newline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The whole conflict, from the start of the `<<<<<<<` line to the end of the
+    /// `>>>>>>>` line.
+    pub range: Range<Anchor>,
+    /// The lines between `<<<<<<<` and `|||||||` or `=======`.
+    pub ours: Range<Anchor>,
+    /// The lines between `|||||||` and `=======`, present in the `diff3` conflict style.
+    pub base: Option<Range<Anchor>>,
+    /// The lines between `=======` and `>>>>>>>`.
+    pub theirs: Range<Anchor>,
+    /// The text following the `<<<<<<<` marker, usually `HEAD`.
+    pub ours_label: String,
+    /// The text following the `>>>>>>>` marker, usually the merged branch or commit.
+    pub theirs_label: String,
+}
+
+/// Which sides of a [`MergeConflict`] to keep when resolving it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl MergeConflict {
+    /// Returns the text that replaces the conflict's range when it is resolved.
+    pub fn resolved_text(
+        &self,
+        resolution: MergeConflictResolution,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        let mut text = String::new();
+        if resolution != MergeConflictResolution::Theirs {
+            text.extend(buffer.text_for_range(self.ours.clone()));
+        }
+        if resolution != MergeConflictResolution::Ours {
+            text.extend(buffer.text_for_range(self.theirs.clone()));
+        }
+        text
+    }
+}
+
+/// Returns the label of a marker line, or `None` if the line is not the given marker.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let label = line.strip_prefix(marker)?;
+    if label.is_empty() {
+        Some(label)
+    } else if label.starts_with([' ', '\t']) {
+        Some(label.trim())
+    } else {
+        None
+    }
+}
+
+pub(crate) fn merge_conflicts(buffer: &BufferSnapshot) -> Vec<MergeConflict> {
+    struct PendingConflict {
+        start_row: u32,
+        ours_label: String,
+        base_row: Option<u32>,
+        separator_row: Option<u32>,
+    }
+
+    let line_start = |row: u32| Point::new(row, 0);
+    let mut conflicts = Vec::new();
+    let mut pending = None::<PendingConflict>;
+    let mut lines = buffer.as_rope().chunks().lines();
+    let mut row = 0;
+    while let Some(line) = lines.next() {
+        let line = line.trim_end_matches('\r');
+        if let Some(label) = marker_label(line, OURS_MARKER) {
+            pending = Some(PendingConflict {
+                start_row: row,
+                ours_label: label.to_string(),
+                base_row: None,
+                separator_row: None,
+            });
+        } else if let Some(conflict) = pending.as_mut() {
+            if let Some(separator_row) = conflict.separator_row {
+                if let Some(label) = marker_label(line, THEIRS_MARKER) {
+                    let ours_end = conflict.base_row.unwrap_or(separator_row);
+                    let end = line_start(row + 1).min(buffer.max_point());
+                    conflicts.push(MergeConflict {
+                        range: buffer.anchor_before(line_start(conflict.start_row))
+                            ..buffer.anchor_after(end),
+                        ours: buffer.anchor_before(line_start(conflict.start_row + 1))
+                            ..buffer.anchor_before(line_start(ours_end)),
+                        base: conflict.base_row.map(|base_row| {
+                            buffer.anchor_before(line_start(base_row + 1))
+                                ..buffer.anchor_before(line_start(separator_row))
+                        }),
+                        theirs: buffer.anchor_before(line_start(separator_row + 1))
+                            ..buffer.anchor_before(line_start(row)),
+                        ours_label: std::mem::take(&mut conflict.ours_label),
+                        theirs_label: label.to_string(),
+                    });
+                    pending = None;
+                }
+            } else if line == SEPARATOR_MARKER {
+                conflict.separator_row = Some(row);
+            } else if conflict.base_row.is_none() && marker_label(line, BASE_MARKER).is_some() {
+                conflict.base_row = Some(row);
+            }
+        }
+        row += 1;
+    }
+    conflicts
+}
@@ -8,7 +8,7 @@ pub mod settings;
 
 use anyhow::Result;
 use client::Client;
-use futures::{future::BoxFuture, stream::BoxStream};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt as _, StreamExt as _};
 use gpui::{AnyView, AppContext, AsyncAppContext, FocusHandle, SharedString, Task, WindowContext};
 pub use model::*;
 use project::Fs;
@@ -17,7 +17,7 @@ pub use registry::*;
 pub use request::*;
 pub use role::*;
 use schemars::JsonSchema;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{future::Future, sync::Arc};
 
 pub fn init(client: Arc<Client>, fs: Arc<dyn Fs>, cx: &mut AppContext) {
@@ -25,6 +25,68 @@ pub fn init(client: Arc<Client>, fs: Arc<dyn Fs>, cx: &mut AppContext) {
     registry::init(client, cx);
 }
 
+/// An event of a streamed completion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageModelCompletionEvent {
+    Text(String),
+    /// The tokens used by the completion so far, for the providers that report them.
+    UsageUpdate(TokenUsage),
+}
+
+/// The tokens used by a completion.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// The input tokens that were neither written to nor read from the cache.
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    /// The input tokens written to the cache, which later requests can read.
+    pub cache_creation_input_tokens: u32,
+    /// The input tokens read from the cache.
+    pub cache_read_input_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> u32 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_creation_input_tokens
+            + self.cache_read_input_tokens
+    }
+}
+
+impl std::ops::Add for TokenUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens + other.input_tokens,
+            output_tokens: self.output_tokens + other.output_tokens,
+            cache_creation_input_tokens: self.cache_creation_input_tokens
+                + other.cache_creation_input_tokens,
+            cache_read_input_tokens: self.cache_read_input_tokens + other.cache_read_input_tokens,
+        }
+    }
+}
+
+/// Subtracts the counts one by one, stopping at zero. This gives the tokens a completion
+/// used since an earlier update of its usage.
+impl std::ops::Sub for TokenUsage {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.saturating_sub(other.input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(other.output_tokens),
+            cache_creation_input_tokens: self
+                .cache_creation_input_tokens
+                .saturating_sub(other.cache_creation_input_tokens),
+            cache_read_input_tokens: self
+                .cache_read_input_tokens
+                .saturating_sub(other.cache_read_input_tokens),
+        }
+    }
+}
+
 pub trait LanguageModel: Send + Sync {
     fn id(&self) -> LanguageModelId;
     fn name(&self) -> LanguageModelName;
@@ -44,7 +106,7 @@ pub trait LanguageModel: Send + Sync {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>>;
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>>;
 
     fn use_any_tool(
         &self,
@@ -54,9 +116,40 @@ pub trait LanguageModel: Send + Sync {
         schema: serde_json::Value,
         cx: &AsyncAppContext,
     ) -> BoxFuture<'static, Result<serde_json::Value>>;
+
+    /// Sends a request that lets the model call any of the given tools, and returns its
+    /// reply once it's complete.
+    fn use_tools(
+        &self,
+        request: LanguageModelRequest,
+        tools: Vec<LanguageModelRequestTool>,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>>;
 }
 
 impl dyn LanguageModel {
+    /// Streams a completion's text, leaving out the other events.
+    pub fn stream_completion_text(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let events = self.stream_completion(request, cx);
+        async move {
+            Ok(events
+                .await?
+                .filter_map(|event| async move {
+                    match event {
+                        Ok(LanguageModelCompletionEvent::Text(text)) => Some(Ok(text)),
+                        Ok(LanguageModelCompletionEvent::UsageUpdate(_)) => None,
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed())
+        }
+        .boxed()
+    }
+
     pub fn use_tool<T: LanguageModelTool>(
         &self,
         request: LanguageModelRequest,
@@ -1,12 +1,14 @@
 use crate::{
-    settings::AllLanguageModelSettings, LanguageModel, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    settings::AllLanguageModelSettings, LanguageModel, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelRequestTool, LanguageModelToolUse, LanguageModelToolUseResponse, RateLimiter,
+    Role, TokenUsage,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::BTreeMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use futures::{future, future::BoxFuture, stream::BoxStream, FutureExt, Stream, StreamExt};
 use gpui::{
     AnyView, AppContext, AsyncAppContext, FocusHandle, FocusableView, FontStyle, ModelContext,
     Subscription, Task, TextStyle, View, WhiteSpace,
@@ -206,12 +208,103 @@ pub struct AnthropicModel {
     request_limiter: RateLimiter,
 }
 
+/// Maps the events of an Anthropic completion to completion events, reporting the tokens
+/// used as they come in.
+pub fn map_to_completion_events(
+    events: impl Stream<Item = Result<anthropic::Event>>,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    let mut usage = TokenUsage::default();
+    events.filter_map(move |event| {
+        let event = match event {
+            Ok(anthropic::Event::ContentBlockStart {
+                content_block: anthropic::Content::Text { text, .. },
+                ..
+            })
+            | Ok(anthropic::Event::ContentBlockDelta {
+                delta: anthropic::ContentDelta::TextDelta { text },
+                ..
+            }) => Some(Ok(LanguageModelCompletionEvent::Text(text))),
+            Ok(anthropic::Event::MessageStart { message }) => {
+                update_usage(&mut usage, &message.usage);
+                Some(Ok(LanguageModelCompletionEvent::UsageUpdate(usage)))
+            }
+            Ok(anthropic::Event::MessageDelta {
+                usage: new_usage, ..
+            }) => {
+                update_usage(&mut usage, &new_usage);
+                Some(Ok(LanguageModelCompletionEvent::UsageUpdate(usage)))
+            }
+            Ok(anthropic::Event::Error { error }) => Some(Err(anthropic::api_error_to_err(error))),
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        };
+        future::ready(event)
+    })
+}
+
+/// Updates the usage with the counts an event reports. The output tokens are cumulative,
+/// and the other counts are only reported once, when the message starts.
+fn update_usage(usage: &mut TokenUsage, new: &anthropic::Usage) {
+    if let Some(input_tokens) = new.input_tokens {
+        usage.input_tokens = input_tokens;
+    }
+    if let Some(output_tokens) = new.output_tokens {
+        usage.output_tokens = output_tokens;
+    }
+    if let Some(cache_creation_input_tokens) = new.cache_creation_input_tokens {
+        usage.cache_creation_input_tokens = cache_creation_input_tokens;
+    }
+    if let Some(cache_read_input_tokens) = new.cache_read_input_tokens {
+        usage.cache_read_input_tokens = cache_read_input_tokens;
+    }
+}
+
+/// Prepares a request that lets the model call any of the given tools.
+pub fn add_anthropic_tools(request: &mut anthropic::Request, tools: Vec<LanguageModelRequestTool>) {
+    request.tool_choice = Some(anthropic::ToolChoice::Auto);
+    request.tools = tools
+        .into_iter()
+        .map(|tool| anthropic::Tool {
+            name: tool.name,
+            description: tool.description,
+            input_schema: tool.input_schema,
+        })
+        .collect();
+}
+
+/// Collects the text and the tool calls of a reply to a request made with
+/// [`add_anthropic_tools`].
+pub fn into_tool_use_response(response: anthropic::Response) -> LanguageModelToolUseResponse {
+    let mut usage = TokenUsage::default();
+    update_usage(&mut usage, &response.usage);
+    let mut tool_use_response = LanguageModelToolUseResponse {
+        usage: Some(usage),
+        ..Default::default()
+    };
+    for content in response.content {
+        match content {
+            anthropic::Content::Text { text, .. } => tool_use_response.text.push_str(&text),
+            anthropic::Content::ToolUse { id, name, input } => tool_use_response
+                .tool_uses
+                .push(LanguageModelToolUse { id, name, input }),
+            anthropic::Content::Image { .. } | anthropic::Content::ToolResult { .. } => {}
+        }
+    }
+    tool_use_response
+}
+
 pub fn count_anthropic_tokens(
     request: LanguageModelRequest,
     cx: &AppContext,
 ) -> BoxFuture<'static, Result<usize>> {
     cx.background_executor()
         .spawn(async move {
+            // Tiktoken only counts text, so the tokens of images are estimated separately.
+            let image_tokens = request
+                .messages
+                .iter()
+                .map(|message| message.estimate_image_tokens())
+                .sum::<usize>();
             let messages = request
                 .messages
                 .into_iter()
@@ -221,7 +314,7 @@ pub fn count_anthropic_tokens(
                         Role::Assistant => "assistant".into(),
                         Role::System => "system".into(),
                     },
-                    content: Some(message.content),
+                    content: Some(message.string_contents()),
                     name: None,
                     function_call: None,
                 })
@@ -229,7 +322,8 @@ pub fn count_anthropic_tokens(
 
             // Tiktoken doesn't yet support these models, so we manually use the
             // same tokenizer as GPT-4.
-            tiktoken_rs::num_tokens_from_messages("gpt-4", &messages)
+            let text_tokens = tiktoken_rs::num_tokens_from_messages("gpt-4", &messages)?;
+            Ok(text_tokens + image_tokens)
         })
         .boxed()
 }
@@ -326,12 +420,12 @@ impl LanguageModel for AnthropicModel {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let request = request.into_anthropic(self.model.id().into());
         let request = self.stream_completion(request, cx);
         let future = self.request_limiter.stream(async move {
             let response = request.await?;
-            Ok(anthropic::extract_text_from_events(response))
+            Ok(map_to_completion_events(response))
         });
         async move { Ok(future.await?.boxed()) }.boxed()
     }
@@ -376,6 +470,21 @@ impl LanguageModel for AnthropicModel {
             })
             .boxed()
     }
+
+    fn use_tools(
+        &self,
+        request: LanguageModelRequest,
+        tools: Vec<LanguageModelRequestTool>,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        let mut request = request.into_anthropic(self.model.tool_model_id().into());
+        add_anthropic_tools(&mut request, tools);
+
+        let response = self.request_completion(request, cx);
+        self.request_limiter
+            .run(async move { Ok(into_tool_use_response(response.await?)) })
+            .boxed()
+    }
 }
 
 struct ConfigurationView {
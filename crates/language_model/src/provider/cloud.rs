@@ -1,8 +1,9 @@
 use super::open_ai::count_open_ai_tokens;
 use crate::{
-    settings::AllLanguageModelSettings, CloudModel, LanguageModel, LanguageModelId,
-    LanguageModelName, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter,
+    settings::AllLanguageModelSettings, CloudModel, LanguageModel, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelProviderState, LanguageModelRequest, LanguageModelRequestTool,
+    LanguageModelToolUseResponse, RateLimiter,
 };
 use anyhow::{anyhow, Context as _, Result};
 use client::Client;
@@ -18,7 +19,9 @@ use ui::prelude::*;
 
 use crate::LanguageModelProvider;
 
-use super::anthropic::count_anthropic_tokens;
+use super::anthropic::{
+    add_anthropic_tools, count_anthropic_tokens, into_tool_use_response, map_to_completion_events,
+};
 
 pub const PROVIDER_ID: &str = "zed.dev";
 pub const PROVIDER_NAME: &str = "Zed AI";
@@ -266,7 +269,7 @@ impl LanguageModel for CloudLanguageModel {
         &self,
         request: LanguageModelRequest,
         _: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         match &self.model {
             CloudModel::Anthropic(model) => {
                 let client = self.client.clone();
@@ -279,7 +282,7 @@ impl LanguageModel for CloudLanguageModel {
                             request,
                         })
                         .await?;
-                    Ok(anthropic::extract_text_from_events(
+                    Ok(map_to_completion_events(
                         stream.map(|item| Ok(serde_json::from_str(&item?.event)?)),
                     ))
                 });
@@ -287,7 +290,10 @@ impl LanguageModel for CloudLanguageModel {
             }
             CloudModel::OpenAi(model) => {
                 let client = self.client.clone();
-                let request = request.into_open_ai(model.id().into());
+                let mut request = request.into_open_ai(model.id().into());
+                request.stream_options = Some(open_ai::StreamOptions {
+                    include_usage: true,
+                });
                 let future = self.request_limiter.stream(async move {
                     let request = serde_json::to_string(&request)?;
                     let stream = client
@@ -296,7 +302,7 @@ impl LanguageModel for CloudLanguageModel {
                             request,
                         })
                         .await?;
-                    Ok(open_ai::extract_text_from_events(
+                    Ok(super::open_ai::map_to_completion_events(
                         stream.map(|item| Ok(serde_json::from_str(&item?.event)?)),
                     ))
                 });
@@ -313,7 +319,7 @@ impl LanguageModel for CloudLanguageModel {
                             request,
                         })
                         .await?;
-                    Ok(google_ai::extract_text_from_events(
+                    Ok(super::google::map_to_completion_events(
                         stream.map(|item| Ok(serde_json::from_str(&item?.event)?)),
                     ))
                 });
@@ -380,6 +386,42 @@ impl LanguageModel for CloudLanguageModel {
             }
         }
     }
+
+    fn use_tools(
+        &self,
+        request: LanguageModelRequest,
+        tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        match &self.model {
+            CloudModel::Anthropic(model) => {
+                let client = self.client.clone();
+                let mut request = request.into_anthropic(model.tool_model_id().into());
+                add_anthropic_tools(&mut request, tools);
+
+                self.request_limiter
+                    .run(async move {
+                        let request = serde_json::to_string(&request)?;
+                        let response = client
+                            .request(proto::CompleteWithLanguageModel {
+                                provider: proto::LanguageModelProvider::Anthropic as i32,
+                                request,
+                            })
+                            .await?;
+                        let response: anthropic::Response =
+                            serde_json::from_str(&response.completion)?;
+                        Ok(into_tool_use_response(response))
+                    })
+                    .boxed()
+            }
+            CloudModel::OpenAi(_) => {
+                future::ready(Err(anyhow!("tool use not implemented for OpenAI"))).boxed()
+            }
+            CloudModel::Google(_) => {
+                future::ready(Err(anyhow!("tool use not implemented for Google AI"))).boxed()
+            }
+        }
+    }
 }
 
 struct ConfigurationView {
@@ -26,8 +26,10 @@ use ui::{
 use crate::settings::AllLanguageModelSettings;
 use crate::LanguageModelProviderState;
 use crate::{
-    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
-    LanguageModelProviderId, LanguageModelProviderName, LanguageModelRequest, RateLimiter, Role,
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelRequest, LanguageModelRequestTool, LanguageModelToolUseResponse, RateLimiter,
+    Role,
 };
 
 use super::open_ai::count_open_ai_tokens;
@@ -188,9 +190,9 @@ impl LanguageModel for CopilotChatLanguageModel {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         if let Some(message) = request.messages.last() {
-            if message.content.trim().is_empty() {
+            if message.contents_empty() {
                 const EMPTY_PROMPT_MSG: &str =
                     "Empty prompts aren't allowed. Please provide a non-empty prompt.";
                 return futures::future::ready(Err(anyhow::anyhow!(EMPTY_PROMPT_MSG))).boxed();
@@ -239,7 +241,13 @@ impl LanguageModel for CopilotChatLanguageModel {
             }).await
         });
 
-        async move { Ok(future.await?.boxed()) }.boxed()
+        async move {
+            Ok(future
+                .await?
+                .map(|text| text.map(LanguageModelCompletionEvent::Text))
+                .boxed())
+        }
+        .boxed()
     }
 
     fn use_any_tool(
@@ -252,6 +260,15 @@ impl LanguageModel for CopilotChatLanguageModel {
     ) -> BoxFuture<'static, Result<serde_json::Value>> {
         future::ready(Err(anyhow!("not implemented"))).boxed()
     }
+
+    fn use_tools(
+        &self,
+        _request: LanguageModelRequest,
+        _tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        future::ready(Err(anyhow!("not implemented"))).boxed()
+    }
 }
 
 impl CopilotChatLanguageModel {
@@ -267,7 +284,8 @@ impl CopilotChatLanguageModel {
                         Role::Assistant => CopilotChatRole::Assistant,
                         Role::System => CopilotChatRole::System,
                     },
-                    content: msg.content,
+                    // Copilot Chat doesn't accept images.
+                    content: msg.string_contents(),
                 })
                 .collect(),
         )
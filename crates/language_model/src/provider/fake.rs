@@ -1,7 +1,8 @@
 use crate::{
-    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
-    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest,
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelProviderState, LanguageModelRequest, LanguageModelRequestTool,
+    LanguageModelToolUseResponse,
 };
 use anyhow::anyhow;
 use collections::HashMap;
@@ -166,13 +167,18 @@ impl LanguageModel for FakeLanguageModel {
         &self,
         request: LanguageModelRequest,
         _: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let (tx, rx) = mpsc::unbounded();
         self.current_completion_txs
             .lock()
             .unwrap()
             .insert(serde_json::to_string(&request).unwrap(), tx);
-        async move { Ok(rx.map(Ok).boxed()) }.boxed()
+        async move {
+            Ok(rx
+                .map(|text| Ok(LanguageModelCompletionEvent::Text(text)))
+                .boxed())
+        }
+        .boxed()
     }
 
     fn use_any_tool(
@@ -185,4 +191,13 @@ impl LanguageModel for FakeLanguageModel {
     ) -> BoxFuture<'static, Result<serde_json::Value>> {
         future::ready(Err(anyhow!("not implemented"))).boxed()
     }
+
+    fn use_tools(
+        &self,
+        _request: LanguageModelRequest,
+        _tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        future::ready(Err(anyhow!("not implemented"))).boxed()
+    }
 }
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use collections::BTreeMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use futures::{future::BoxFuture, stream, FutureExt, Stream, StreamExt};
 use google_ai::stream_generate_content;
 use gpui::{
     AnyView, AppContext, AsyncAppContext, FocusHandle, FocusableView, FontStyle, ModelContext,
@@ -18,9 +18,10 @@ use ui::{prelude::*, Indicator};
 use util::ResultExt;
 
 use crate::{
-    settings::AllLanguageModelSettings, LanguageModel, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter,
+    settings::AllLanguageModelSettings, LanguageModel, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelRequestTool, LanguageModelToolUseResponse, RateLimiter, TokenUsage,
 };
 
 const PROVIDER_ID: &str = "google";
@@ -254,7 +255,10 @@ impl LanguageModel for GoogleLanguageModel {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<futures::stream::BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<
+        'static,
+        Result<futures::stream::BoxStream<'static, Result<LanguageModelCompletionEvent>>>,
+    > {
         let request = request.into_google(self.model.id().to_string());
 
         let http_client = self.http_client.clone();
@@ -270,7 +274,7 @@ impl LanguageModel for GoogleLanguageModel {
             let response =
                 stream_generate_content(http_client.as_ref(), &api_url, &api_key, request);
             let events = response.await?;
-            Ok(google_ai::extract_text_from_events(events).boxed())
+            Ok(map_to_completion_events(events))
         });
         async move { Ok(future.await?.boxed()) }.boxed()
     }
@@ -285,6 +289,51 @@ impl LanguageModel for GoogleLanguageModel {
     ) -> BoxFuture<'static, Result<serde_json::Value>> {
         future::ready(Err(anyhow!("not implemented"))).boxed()
     }
+
+    fn use_tools(
+        &self,
+        _request: LanguageModelRequest,
+        _tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        future::ready(Err(anyhow!("not implemented"))).boxed()
+    }
+}
+
+/// Maps the chunks of a Google AI response to completion events. Every chunk reports the
+/// tokens used so far.
+pub fn map_to_completion_events(
+    events: impl Stream<Item = Result<google_ai::GenerateContentResponse>>,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    events.flat_map(|event| {
+        let mut completion_events = Vec::new();
+        match event {
+            Ok(event) => {
+                let text = event
+                    .candidates
+                    .and_then(|candidates| candidates.into_iter().next())
+                    .and_then(|candidate| candidate.content.parts.into_iter().next())
+                    .and_then(|part| match part {
+                        google_ai::Part::TextPart(google_ai::TextPart { text }) => Some(text),
+                        _ => None,
+                    });
+                if let Some(text) = text {
+                    completion_events.push(Ok(LanguageModelCompletionEvent::Text(text)));
+                }
+                if let Some(usage) = event.usage_metadata {
+                    completion_events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                        TokenUsage {
+                            input_tokens: usage.prompt_token_count.unwrap_or(0),
+                            output_tokens: usage.candidates_token_count.unwrap_or(0),
+                            ..Default::default()
+                        },
+                    )));
+                }
+            }
+            Err(error) => completion_events.push(Err(error)),
+        }
+        stream::iter(completion_events)
+    })
 }
 
 struct ConfigurationView {
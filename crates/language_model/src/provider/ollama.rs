@@ -10,9 +10,10 @@ use std::{future, sync::Arc, time::Duration};
 use ui::{prelude::*, ButtonLike, ElevationIndex, Indicator};
 
 use crate::{
-    settings::AllLanguageModelSettings, LanguageModel, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    settings::AllLanguageModelSettings, LanguageModel, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelRequestTool, LanguageModelToolUseResponse, RateLimiter, Role,
 };
 
 const OLLAMA_DOWNLOAD_URL: &str = "https://ollama.com/download";
@@ -172,14 +173,21 @@ impl OllamaLanguageModel {
                 .messages
                 .into_iter()
                 .map(|msg| match msg.role {
-                    Role::User => ChatMessage::User {
-                        content: msg.content,
-                    },
+                    Role::User => {
+                        let images = msg
+                            .images()
+                            .map(|image| image.source.clone())
+                            .collect::<Vec<_>>();
+                        ChatMessage::User {
+                            content: msg.string_contents(),
+                            images: (!images.is_empty()).then_some(images),
+                        }
+                    }
                     Role::Assistant => ChatMessage::Assistant {
-                        content: msg.content,
+                        content: msg.string_contents(),
                     },
                     Role::System => ChatMessage::System {
-                        content: msg.content,
+                        content: msg.string_contents(),
                     },
                 })
                 .collect(),
@@ -230,9 +238,8 @@ impl LanguageModel for OllamaLanguageModel {
         let token_count = request
             .messages
             .iter()
-            .map(|msg| msg.content.chars().count())
-            .sum::<usize>()
-            / 4;
+            .map(|msg| msg.string_contents().chars().count() / 4 + msg.estimate_image_tokens())
+            .sum::<usize>();
 
         async move { Ok(token_count) }.boxed()
     }
@@ -241,7 +248,7 @@ impl LanguageModel for OllamaLanguageModel {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let request = self.to_ollama_request(request);
 
         let http_client = self.http_client.clone();
@@ -261,7 +268,7 @@ impl LanguageModel for OllamaLanguageModel {
                     match response {
                         Ok(delta) => {
                             let content = match delta.message {
-                                ChatMessage::User { content } => content,
+                                ChatMessage::User { content, .. } => content,
                                 ChatMessage::Assistant { content } => content,
                                 ChatMessage::System { content } => content,
                             };
@@ -274,7 +281,13 @@ impl LanguageModel for OllamaLanguageModel {
             Ok(stream)
         });
 
-        async move { Ok(future.await?.boxed()) }.boxed()
+        async move {
+            Ok(future
+                .await?
+                .map(|text| text.map(LanguageModelCompletionEvent::Text))
+                .boxed())
+        }
+        .boxed()
     }
 
     fn use_any_tool(
@@ -287,6 +300,15 @@ impl LanguageModel for OllamaLanguageModel {
     ) -> BoxFuture<'static, Result<serde_json::Value>> {
         future::ready(Err(anyhow!("not implemented"))).boxed()
     }
+
+    fn use_tools(
+        &self,
+        _request: LanguageModelRequest,
+        _tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        future::ready(Err(anyhow!("not implemented"))).boxed()
+    }
 }
 
 struct ConfigurationView {
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use collections::BTreeMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
 use gpui::{
     AnyView, AppContext, AsyncAppContext, FocusHandle, FocusableView, FontStyle, ModelContext,
     Subscription, Task, TextStyle, View, WhiteSpace,
@@ -18,9 +18,10 @@ use ui::{prelude::*, Indicator};
 use util::ResultExt;
 
 use crate::{
-    settings::AllLanguageModelSettings, LanguageModel, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    settings::AllLanguageModelSettings, LanguageModel, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelRequestTool, LanguageModelToolUseResponse, RateLimiter, Role, TokenUsage,
 };
 
 const PROVIDER_ID: &str = "openai";
@@ -223,8 +224,11 @@ impl LanguageModel for OpenAiLanguageModel {
         &self,
         request: LanguageModelRequest,
         cx: &AsyncAppContext,
-    ) -> BoxFuture<'static, Result<futures::stream::BoxStream<'static, Result<String>>>> {
-        let request = request.into_open_ai(self.model.id().into());
+    ) -> BoxFuture<
+        'static,
+        Result<futures::stream::BoxStream<'static, Result<LanguageModelCompletionEvent>>>,
+    > {
+        let mut request = request.into_open_ai(self.model.id().into());
 
         let http_client = self.http_client.clone();
         let Ok((api_key, api_url, low_speed_timeout)) = cx.read_model(&self.state, |state, cx| {
@@ -237,6 +241,12 @@ impl LanguageModel for OpenAiLanguageModel {
         }) else {
             return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
         };
+        // OpenAI-compatible servers configured with another URL may reject the options.
+        if api_url.trim_end_matches('/') == open_ai::OPEN_AI_API_URL {
+            request.stream_options = Some(open_ai::StreamOptions {
+                include_usage: true,
+            });
+        }
 
         let future = self.request_limiter.stream(async move {
             let api_key = api_key.ok_or_else(|| anyhow!("missing api key"))?;
@@ -248,7 +258,7 @@ impl LanguageModel for OpenAiLanguageModel {
                 low_speed_timeout,
             );
             let response = request.await?;
-            Ok(open_ai::extract_text_from_events(response).boxed())
+            Ok(map_to_completion_events(response))
         });
 
         async move { Ok(future.await?.boxed()) }.boxed()
@@ -264,6 +274,40 @@ impl LanguageModel for OpenAiLanguageModel {
     ) -> BoxFuture<'static, Result<serde_json::Value>> {
         future::ready(Err(anyhow!("not implemented"))).boxed()
     }
+
+    fn use_tools(
+        &self,
+        _request: LanguageModelRequest,
+        _tools: Vec<LanguageModelRequestTool>,
+        _cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<LanguageModelToolUseResponse>> {
+        future::ready(Err(anyhow!("not implemented"))).boxed()
+    }
+}
+
+/// Maps the events of an OpenAI completion to completion events. The usage of the
+/// completion comes in a last event, when it's requested with the stream options.
+pub fn map_to_completion_events(
+    events: impl Stream<Item = Result<open_ai::ResponseStreamEvent>>,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    events.filter_map(|event| {
+        let event = match event {
+            Ok(mut event) => match event.usage {
+                Some(usage) => Some(Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                    input_tokens: usage.prompt_tokens,
+                    output_tokens: usage.completion_tokens,
+                    ..Default::default()
+                }))),
+                None => event
+                    .choices
+                    .pop()
+                    .and_then(|choice| choice.delta.content)
+                    .map(|text| Ok(LanguageModelCompletionEvent::Text(text))),
+            },
+            Err(error) => Some(Err(error)),
+        };
+        future::ready(event)
+    })
 }
 
 pub fn count_open_ai_tokens(
@@ -273,6 +317,12 @@ pub fn count_open_ai_tokens(
 ) -> BoxFuture<'static, Result<usize>> {
     cx.background_executor()
         .spawn(async move {
+            // Tiktoken only counts text, so the tokens of images are estimated separately.
+            let image_tokens = request
+                .messages
+                .iter()
+                .map(|message| message.estimate_image_tokens())
+                .sum::<usize>();
             let messages = request
                 .messages
                 .into_iter()
@@ -282,17 +332,18 @@ pub fn count_open_ai_tokens(
                         Role::Assistant => "assistant".into(),
                         Role::System => "system".into(),
                     },
-                    content: Some(message.content),
+                    content: Some(message.string_contents()),
                     name: None,
                     function_call: None,
                 })
                 .collect::<Vec<_>>();
 
-            if let open_ai::Model::Custom { .. } = model {
+            let text_tokens = if let open_ai::Model::Custom { .. } = model {
                 tiktoken_rs::num_tokens_from_messages("gpt-4", &messages)
             } else {
                 tiktoken_rs::num_tokens_from_messages(model.id(), &messages)
-            }
+            }?;
+            Ok(text_tokens + image_tokens)
         })
         .boxed()
 }
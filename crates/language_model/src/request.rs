@@ -1,10 +1,162 @@
-use crate::role::Role;
+use std::io::Cursor;
+
+use crate::{role::Role, TokenUsage};
+use anyhow::{Context as _, Result};
+use base64::Engine as _;
+use image::{imageops::FilterType, DynamicImage, GenericImageView as _, ImageFormat};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+/// An image attached to a message, stored as a base64-encoded PNG.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct LanguageModelImage {
+    pub source: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl LanguageModelImage {
+    /// The longest side, in pixels, that images are sent with. Providers downscale larger
+    /// images anyway, so they're downscaled before being sent to save bandwidth.
+    const MAX_DIMENSION: u32 = 1568;
+
+    pub const MEDIA_TYPE: &'static str = "image/png";
+
+    /// Creates an image from its encoded bytes, in any format the `image` crate supports,
+    /// such as PNG, JPEG, GIF or TIFF.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let image = image::load_from_memory(bytes).context("failed to decode image")?;
+        let image = Self::downscale(image);
+        let (width, height) = image.dimensions();
+
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .context("failed to encode image")?;
+        Ok(Self {
+            source: base64::engine::general_purpose::STANDARD.encode(png),
+            width,
+            height,
+        })
+    }
+
+    fn downscale(image: DynamicImage) -> DynamicImage {
+        let (width, height) = image.dimensions();
+        if width.max(height) <= Self::MAX_DIMENSION {
+            image
+        } else {
+            image.resize(
+                Self::MAX_DIMENSION,
+                Self::MAX_DIMENSION,
+                FilterType::Triangle,
+            )
+        }
+    }
+
+    /// Returns the encoded bytes of the image.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(base64::engine::general_purpose::STANDARD.decode(&self.source)?)
+    }
+
+    /// Returns the image as a `data:` URL.
+    pub fn to_data_url(&self) -> String {
+        format!("data:{};base64,{}", Self::MEDIA_TYPE, self.source)
+    }
+
+    /// Estimates the number of tokens the image takes up, using Anthropic's formula, which
+    /// is close enough for the other providers.
+    pub fn estimate_tokens(&self) -> usize {
+        (self.width as usize * self.height as usize) / 750
+    }
+}
+
+/// A call the model made to a tool.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct LanguageModelToolUse {
+    /// The identifier the result of the call refers to.
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+}
+
+/// The result of a call to a tool, sent back to the model.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct LanguageModelToolResult {
+    pub tool_use_id: String,
+    pub content: String,
+    /// Whether the call failed, in which case the content describes why.
+    pub is_error: bool,
+}
+
+/// A part of the content of a message.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum MessageContent {
+    Text(String),
+    Image(LanguageModelImage),
+    ToolUse(LanguageModelToolUse),
+    /// The result of a tool call, which is always sent by the user, whatever the role of
+    /// the message it's in.
+    ToolResult(LanguageModelToolResult),
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelRequestMessage {
     pub role: Role,
-    pub content: String,
+    pub content: Vec<MessageContent>,
+    /// Whether the request up to and including this message is worth caching, because
+    /// follow-up requests are likely to start with it. Providers that support caching
+    /// mark the end of the message as a cache breakpoint.
+    pub cache: bool,
+}
+
+impl LanguageModelRequestMessage {
+    /// Returns the text of the message, along with the results of its tool calls, leaving
+    /// its images and the calls themselves out.
+    pub fn string_contents(&self) -> String {
+        let mut contents = String::new();
+        for content in &self.content {
+            match content {
+                MessageContent::Text(text) => contents.push_str(text),
+                MessageContent::ToolResult(result) => contents.push_str(&result.content),
+                MessageContent::Image(_) | MessageContent::ToolUse(_) => {}
+            }
+        }
+        contents
+    }
+
+    /// Returns whether the message has neither text, other than whitespace, nor images or
+    /// tool calls.
+    pub fn contents_empty(&self) -> bool {
+        self.content.iter().all(|content| match content {
+            MessageContent::Text(text) => text.trim().is_empty(),
+            MessageContent::Image(_)
+            | MessageContent::ToolUse(_)
+            | MessageContent::ToolResult(_) => false,
+        })
+    }
+
+    pub fn images(&self) -> impl Iterator<Item = &LanguageModelImage> {
+        self.content.iter().filter_map(|content| match content {
+            MessageContent::Image(image) => Some(image),
+            _ => None,
+        })
+    }
+
+    /// Estimates the number of tokens the images of the message take up.
+    pub fn estimate_image_tokens(&self) -> usize {
+        self.images().map(LanguageModelImage::estimate_tokens).sum()
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -23,18 +175,19 @@ impl LanguageModelRequest {
                 .into_iter()
                 .map(|msg| match msg.role {
                     Role::User => open_ai::RequestMessage::User {
-                        content: msg.content,
+                        content: Self::open_ai_user_content(msg.content),
                     },
                     Role::Assistant => open_ai::RequestMessage::Assistant {
-                        content: Some(msg.content),
+                        content: Some(msg.string_contents()),
                         tool_calls: Vec::new(),
                     },
                     Role::System => open_ai::RequestMessage::System {
-                        content: msg.content,
+                        content: msg.string_contents(),
                     },
                 })
                 .collect(),
             stream: true,
+            stream_options: None,
             stop: self.stop,
             temperature: self.temperature,
             tools: Vec::new(),
@@ -42,6 +195,43 @@ impl LanguageModelRequest {
         }
     }
 
+    /// Sends a user message as plain text, unless it has images, which need the multipart
+    /// form. The results of tool calls are sent as text.
+    fn open_ai_user_content(content: Vec<MessageContent>) -> open_ai::MessageContent {
+        if !content
+            .iter()
+            .any(|content| matches!(content, MessageContent::Image(_)))
+        {
+            let mut text = String::new();
+            for content in content {
+                match content {
+                    MessageContent::Text(content) => text.push_str(&content),
+                    MessageContent::ToolResult(result) => text.push_str(&result.content),
+                    MessageContent::Image(_) | MessageContent::ToolUse(_) => {}
+                }
+            }
+            return open_ai::MessageContent::Plain(text);
+        }
+
+        open_ai::MessageContent::Multipart(
+            content
+                .into_iter()
+                .filter_map(|content| match content {
+                    MessageContent::Text(text) => Some(open_ai::MessagePart::Text { text }),
+                    MessageContent::Image(image) => Some(open_ai::MessagePart::ImageUrl {
+                        image_url: open_ai::ImageUrl {
+                            url: image.to_data_url(),
+                        },
+                    }),
+                    MessageContent::ToolResult(result) => Some(open_ai::MessagePart::Text {
+                        text: result.content,
+                    }),
+                    MessageContent::ToolUse(_) => None,
+                })
+                .collect(),
+        )
+    }
+
     pub fn into_google(self, model: String) -> google_ai::GenerateContentRequest {
         google_ai::GenerateContentRequest {
             model,
@@ -49,9 +239,29 @@ impl LanguageModelRequest {
                 .messages
                 .into_iter()
                 .map(|msg| google_ai::Content {
-                    parts: vec![google_ai::Part::TextPart(google_ai::TextPart {
-                        text: msg.content,
-                    })],
+                    parts: msg
+                        .content
+                        .into_iter()
+                        .filter_map(|content| match content {
+                            MessageContent::Text(text) => {
+                                Some(google_ai::Part::TextPart(google_ai::TextPart { text }))
+                            }
+                            MessageContent::Image(image) => {
+                                Some(google_ai::Part::InlineDataPart(google_ai::InlineDataPart {
+                                    inline_data: google_ai::GenerativeContentBlob {
+                                        mime_type: LanguageModelImage::MEDIA_TYPE.to_string(),
+                                        data: image.source,
+                                    },
+                                }))
+                            }
+                            MessageContent::ToolResult(result) => {
+                                Some(google_ai::Part::TextPart(google_ai::TextPart {
+                                    text: result.content,
+                                }))
+                            }
+                            MessageContent::ToolUse(_) => None,
+                        })
+                        .collect(),
                     role: match msg.role {
                         Role::User => google_ai::Role::User,
                         Role::Assistant => google_ai::Role::Model,
@@ -72,52 +282,112 @@ impl LanguageModelRequest {
     }
 
     pub fn into_anthropic(self, model: String) -> anthropic::Request {
-        let mut new_messages: Vec<LanguageModelRequestMessage> = Vec::new();
+        // When there are more cached prefixes than breakpoints, the last ones are kept, as
+        // they're the longest.
+        let cached_message_count = self
+            .messages
+            .iter()
+            .filter(|message| {
+                message.cache && message.role != Role::System && !message.contents_empty()
+            })
+            .count();
+        let mut breakpoints_to_skip =
+            cached_message_count.saturating_sub(anthropic::MAX_CACHE_BREAKPOINTS);
+
+        let mut new_messages: Vec<anthropic::Message> = Vec::new();
         let mut system_message = String::new();
 
         for message in self.messages {
-            if message.content.is_empty() {
+            if message.contents_empty() {
                 continue;
             }
 
-            match message.role {
-                Role::User | Role::Assistant => {
-                    if let Some(last_message) = new_messages.last_mut() {
-                        if last_message.role == message.role {
-                            last_message.content.push_str("\n\n");
-                            last_message.content.push_str(&message.content);
-                            continue;
-                        }
-                    }
-
-                    new_messages.push(message);
-                }
+            let role = match message.role {
+                Role::User => anthropic::Role::User,
+                Role::Assistant => anthropic::Role::Assistant,
                 Role::System => {
                     if !system_message.is_empty() {
                         system_message.push_str("\n\n");
                     }
-                    system_message.push_str(&message.content);
+                    system_message.push_str(&message.string_contents());
+                    continue;
+                }
+            };
+            let cache = message.cache && breakpoints_to_skip == 0;
+            if message.cache && breakpoints_to_skip > 0 {
+                breakpoints_to_skip -= 1;
+            }
+
+            // Consecutive messages with the same role are merged, as Anthropic requires the
+            // roles to alternate.
+            if let Some(last_message) = new_messages
+                .last_mut()
+                .filter(|last_message| last_message.role == role)
+            {
+                Self::push_anthropic_text(&mut last_message.content, "\n\n".into());
+            }
+
+            for part in message.content {
+                // The results of tool calls are sent by the user, which splits the assistant's
+                // messages around them.
+                let part_role = match part {
+                    MessageContent::ToolResult(_) => anthropic::Role::User,
+                    _ => role,
+                };
+                if new_messages
+                    .last()
+                    .map_or(true, |last_message| last_message.role != part_role)
+                {
+                    // Whitespace would be dropped, leaving a message without content.
+                    if matches!(&part, MessageContent::Text(text) if text.trim().is_empty()) {
+                        continue;
+                    }
+                    new_messages.push(anthropic::Message {
+                        role: part_role,
+                        content: Vec::new(),
+                    });
+                }
+
+                let content = &mut new_messages.last_mut().unwrap().content;
+                match part {
+                    MessageContent::Text(text) => Self::push_anthropic_text(content, text),
+                    MessageContent::Image(image) => content.push(anthropic::Content::Image {
+                        source: anthropic::ImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: LanguageModelImage::MEDIA_TYPE.to_string(),
+                            data: image.source,
+                        },
+                        cache_control: None,
+                    }),
+                    MessageContent::ToolUse(tool_use) => {
+                        content.push(anthropic::Content::ToolUse {
+                            id: tool_use.id,
+                            name: tool_use.name,
+                            input: tool_use.input,
+                        })
+                    }
+                    MessageContent::ToolResult(result) => {
+                        content.push(anthropic::Content::ToolResult {
+                            tool_use_id: result.tool_use_id,
+                            content: result.content,
+                            is_error: result.is_error,
+                        })
+                    }
+                }
+            }
+            if cache {
+                if let Some(last_content) = new_messages
+                    .last_mut()
+                    .and_then(|last_message| last_message.content.last_mut())
+                {
+                    last_content.set_cache_control(anthropic::CacheControl::EPHEMERAL);
                 }
             }
         }
 
         anthropic::Request {
             model,
-            messages: new_messages
-                .into_iter()
-                .filter_map(|message| {
-                    Some(anthropic::Message {
-                        role: match message.role {
-                            Role::User => anthropic::Role::User,
-                            Role::Assistant => anthropic::Role::Assistant,
-                            Role::System => return None,
-                        },
-                        content: vec![anthropic::Content::Text {
-                            text: message.content,
-                        }],
-                    })
-                })
-                .collect(),
+            messages: new_messages,
             max_tokens: 4092,
             system: Some(system_message),
             tools: Vec::new(),
@@ -129,6 +399,42 @@ impl LanguageModelRequest {
             top_p: None,
         }
     }
+
+    /// Appends text to the content of a message, joining it with the last block when that's
+    /// text which doesn't end a cached prefix. Anthropic rejects blocks that are only
+    /// whitespace, so such text is dropped when it can't be joined.
+    fn push_anthropic_text(content: &mut Vec<anthropic::Content>, text: String) {
+        if let Some(anthropic::Content::Text {
+            text: last_text,
+            cache_control: None,
+        }) = content.last_mut()
+        {
+            last_text.push_str(&text);
+        } else if !text.trim().is_empty() {
+            content.push(anthropic::Content::Text {
+                text,
+                cache_control: None,
+            });
+        }
+    }
+}
+
+/// A tool the model can call, described to it along with the schema of its input.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct LanguageModelRequestTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+/// A complete reply of a model that was able to call tools.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+pub struct LanguageModelToolUseResponse {
+    pub text: String,
+    /// The calls the model made, which it expects the results of before it continues.
+    pub tool_uses: Vec<LanguageModelToolUse>,
+    /// The tokens used by the response, for the providers that report them.
+    pub usage: Option<TokenUsage>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -136,3 +442,134 @@ pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
     pub content: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_into_anthropic_cache_breakpoints() {
+        let message = |role, text: &str, cache| LanguageModelRequestMessage {
+            role,
+            content: vec![text.into()],
+            cache,
+        };
+        let request = LanguageModelRequest {
+            messages: vec![
+                message(Role::System, "You are a helpful assistant.", false),
+                message(Role::User, "a", true),
+                message(Role::User, "b", true),
+                message(Role::Assistant, "c", true),
+                message(Role::User, "d", true),
+                message(Role::Assistant, "e", true),
+                message(Role::Assistant, "f", false),
+                message(Role::User, "g", false),
+            ],
+            stop: Vec::new(),
+            temperature: 1.0,
+        };
+
+        // Only the last breakpoints are kept, and text isn't merged into a cached block.
+        let request = serde_json::to_value(request.into_anthropic("model".into())).unwrap();
+        let ephemeral = json!({ "type": "ephemeral" });
+        assert_eq!(
+            request["messages"],
+            json!([
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "text", "text": "a\n\nb", "cache_control": ephemeral },
+                    ],
+                },
+                {
+                    "role": "assistant",
+                    "content": [{ "type": "text", "text": "c", "cache_control": ephemeral }],
+                },
+                {
+                    "role": "user",
+                    "content": [{ "type": "text", "text": "d", "cache_control": ephemeral }],
+                },
+                {
+                    "role": "assistant",
+                    "content": [
+                        { "type": "text", "text": "e", "cache_control": ephemeral },
+                        { "type": "text", "text": "f" },
+                    ],
+                },
+                {
+                    "role": "user",
+                    "content": [{ "type": "text", "text": "g" }],
+                },
+            ])
+        );
+        assert_eq!(request["system"], json!("You are a helpful assistant."));
+    }
+
+    #[test]
+    fn test_into_anthropic_tool_results() {
+        let request = LanguageModelRequest {
+            messages: vec![
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: vec!["What's in a.txt?".into()],
+                    cache: false,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::Assistant,
+                    content: vec![
+                        "Let me read it.".into(),
+                        MessageContent::ToolUse(LanguageModelToolUse {
+                            id: "call-1".into(),
+                            name: "read_file".into(),
+                            input: json!({ "path": "a.txt" }),
+                        }),
+                        MessageContent::ToolResult(LanguageModelToolResult {
+                            tool_use_id: "call-1".into(),
+                            content: "hello".into(),
+                            is_error: false,
+                        }),
+                        "\n\nIt says hello.".into(),
+                    ],
+                    cache: false,
+                },
+            ],
+            stop: Vec::new(),
+            temperature: 1.0,
+        };
+
+        // The tool result splits the assistant's message, as it's sent by the user.
+        let request = serde_json::to_value(request.into_anthropic("model".into())).unwrap();
+        assert_eq!(
+            request["messages"],
+            json!([
+                {
+                    "role": "user",
+                    "content": [{ "type": "text", "text": "What's in a.txt?" }],
+                },
+                {
+                    "role": "assistant",
+                    "content": [
+                        { "type": "text", "text": "Let me read it." },
+                        {
+                            "type": "tool_use",
+                            "id": "call-1",
+                            "name": "read_file",
+                            "input": { "path": "a.txt" },
+                        },
+                    ],
+                },
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "tool_result", "tool_use_id": "call-1", "content": "hello" },
+                    ],
+                },
+                {
+                    "role": "assistant",
+                    "content": [{ "type": "text", "text": "\n\nIt says hello." }],
+                },
+            ])
+        );
+    }
+}
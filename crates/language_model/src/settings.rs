@@ -1,6 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use anyhow::Result;
+use collections::BTreeMap;
 use gpui::AppContext;
 use project::Fs;
 use schemars::JsonSchema;
@@ -16,6 +17,7 @@ use crate::provider::{
     ollama::OllamaSettings,
     open_ai::OpenAiSettings,
 };
+use crate::TokenUsage;
 
 /// Initializes the language model settings.
 pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
@@ -58,6 +60,35 @@ pub struct AllLanguageModelSettings {
     pub zed_dot_dev: ZedDotDevSettings,
     pub google: GoogleSettings,
     pub copilot_chat: CopilotChatSettings,
+    /// The prices of the models, by model ID, used to estimate the cost of conversations.
+    pub model_prices: BTreeMap<String, ModelPrice>,
+}
+
+/// The price of a model, in any currency, per million tokens.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ModelPrice {
+    pub input_per_million_tokens: f64,
+    pub output_per_million_tokens: f64,
+    /// The price of the input tokens written to the cache. Defaults to the input price.
+    pub cache_write_per_million_tokens: Option<f64>,
+    /// The price of the input tokens read from the cache. Defaults to the input price.
+    pub cache_read_per_million_tokens: Option<f64>,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cache_write_price = self
+            .cache_write_per_million_tokens
+            .unwrap_or(self.input_per_million_tokens);
+        let cache_read_price = self
+            .cache_read_per_million_tokens
+            .unwrap_or(self.input_per_million_tokens);
+        (usage.input_tokens as f64 * self.input_per_million_tokens
+            + usage.output_tokens as f64 * self.output_per_million_tokens
+            + usage.cache_creation_input_tokens as f64 * cache_write_price
+            + usage.cache_read_input_tokens as f64 * cache_read_price)
+            / 1_000_000.
+    }
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -69,6 +100,7 @@ pub struct AllLanguageModelSettingsContent {
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
     pub google: Option<GoogleSettingsContent>,
     pub copilot_chat: Option<CopilotChatSettingsContent>,
+    pub model_prices: Option<BTreeMap<String, ModelPrice>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -329,6 +361,10 @@ impl settings::Settings for AllLanguageModelSettings {
                 settings.copilot_chat.low_speed_timeout =
                     Some(Duration::from_secs(low_speed_timeout));
             }
+
+            if let Some(model_prices) = value.model_prices.clone() {
+                settings.model_prices.extend(model_prices);
+            }
         }
 
         Ok(settings)
@@ -3529,6 +3529,8 @@ impl MultiBufferSnapshot {
                         diff_base_byte_range: hunk.diff_base_byte_range.clone(),
                         buffer_range: hunk.buffer_range.clone(),
                         buffer_id: hunk.buffer_id,
+                        buffer_word_ranges: hunk.buffer_word_ranges.clone(),
+                        diff_base_word_ranges: hunk.diff_base_word_ranges.clone(),
                     }
                 });
 
@@ -3604,6 +3606,8 @@ impl MultiBufferSnapshot {
                         diff_base_byte_range: hunk.diff_base_byte_range.clone(),
                         buffer_range: hunk.buffer_range.clone(),
                         buffer_id: hunk.buffer_id,
+                        buffer_word_ranges: hunk.buffer_word_ranges.clone(),
+                        diff_base_word_ranges: hunk.diff_base_word_ranges.clone(),
                     }
                 });
 
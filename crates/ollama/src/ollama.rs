@@ -94,9 +94,18 @@ impl Model {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(tag = "role", rename_all = "lowercase")]
 pub enum ChatMessage {
-    Assistant { content: String },
-    User { content: String },
-    System { content: String },
+    Assistant {
+        content: String,
+    },
+    User {
+        content: String,
+        /// The base64-encoded images of the message, for multimodal models.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        images: Option<Vec<String>>,
+    },
+    System {
+        content: String,
+    },
 }
 
 #[derive(Serialize)]
@@ -7,7 +7,8 @@ use collections::{hash_map, HashMap};
 use futures::{channel::oneshot, stream::FuturesUnordered, StreamExt as _};
 use git::blame::Blame;
 use gpui::{
-    AppContext, AsyncAppContext, Context as _, EventEmitter, Model, ModelContext, Task, WeakModel,
+    AppContext, AsyncAppContext, Context as _, EntityId, EventEmitter, Model, ModelContext, Task,
+    WeakModel,
 };
 use http_client::Url;
 use language::{
@@ -42,6 +43,10 @@ pub struct BufferStore {
     loading_remote_buffers_by_id: HashMap<BufferId, Model<Buffer>>,
     remote_buffer_listeners:
         HashMap<BufferId, Vec<oneshot::Sender<Result<Model<Buffer>, anyhow::Error>>>>,
+    /// The git revision that local buffers are diffed against instead of the index.
+    diff_base_revision: Option<String>,
+    /// Revisions that individual buffers are diffed against, overriding `diff_base_revision`.
+    buffer_diff_base_revisions: HashMap<BufferId, String>,
 }
 
 enum OpenBuffer {
@@ -90,6 +95,8 @@ impl BufferStore {
             local_buffer_ids_by_path: Default::default(),
             local_buffer_ids_by_entry_id: Default::default(),
             loading_buffers_by_path: Default::default(),
+            diff_base_revision: None,
+            buffer_diff_base_revisions: Default::default(),
         }
     }
 
@@ -235,33 +242,64 @@ impl BufferStore {
             let future_buffers = future_buffers.collect::<Vec<_>>().await;
 
             // Reload the diff base for every buffer whose containing git repository has changed.
-            let snapshot =
-                worktree_handle.update(&mut cx, |tree, _| tree.as_local().unwrap().snapshot())?;
+            let buffers = future_buffers
+                .into_iter()
+                .flatten()
+                .chain(current_buffers)
+                .collect();
+            this.update(&mut cx, |this, cx| {
+                this.reload_diff_bases(worktree_handle, buffers, cx)
+            })?
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Loads the diff bases of buffers in a local worktree, either from the index or from the
+    /// revision that each buffer is diffed against, and assigns them to the buffers.
+    fn reload_diff_bases(
+        &self,
+        worktree: Model<Worktree>,
+        buffers: Vec<(Model<Buffer>, Arc<Path>)>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(snapshot) = worktree
+            .read(cx)
+            .as_local()
+            .map(|worktree| worktree.snapshot())
+        else {
+            return Task::ready(Ok(()));
+        };
+        let buffers = buffers
+            .into_iter()
+            .map(|(buffer, path)| {
+                let revision = self.diff_base_revision(buffer.read(cx).remote_id());
+                (buffer, path, revision)
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
             let diff_bases_by_buffer = cx
                 .background_executor()
                 .spawn(async move {
-                    let mut diff_base_tasks = future_buffers
+                    buffers
                         .into_iter()
-                        .flatten()
-                        .chain(current_buffers)
-                        .filter_map(|(buffer, path)| {
+                        .filter_map(|(buffer, path, revision)| {
                             let (repo_entry, local_repo_entry) = snapshot.repo_for_path(&path)?;
                             let relative_path = repo_entry.relativize(&snapshot, &path).ok()?;
-                            Some(async move {
-                                let base_text =
-                                    local_repo_entry.repo().load_index_text(&relative_path);
-                                Some((buffer, base_text))
-                            })
+                            let repo = local_repo_entry.repo();
+                            let base_text = match revision {
+                                // Files that did not exist at the revision are entirely added.
+                                Some(revision) => Some(
+                                    repo.load_revision_text(&revision, &relative_path)
+                                        .log_err()?
+                                        .unwrap_or_default(),
+                                ),
+                                None => repo.load_index_text(&relative_path),
+                            };
+                            Some((buffer, base_text))
                         })
-                        .collect::<FuturesUnordered<_>>();
-
-                    let mut diff_bases = Vec::with_capacity(diff_base_tasks.len());
-                    while let Some(diff_base) = diff_base_tasks.next().await {
-                        if let Some(diff_base) = diff_base {
-                            diff_bases.push(diff_base);
-                        }
-                    }
-                    diff_bases
+                        .collect::<Vec<_>>()
                 })
                 .await;
 
@@ -285,7 +323,74 @@ impl BufferStore {
                 }
             })
         })
-        .detach_and_log_err(cx);
+    }
+
+    /// Returns the git revision that a buffer is diffed against, or `None` when it is diffed
+    /// against the index.
+    pub fn diff_base_revision(&self, buffer_id: BufferId) -> Option<String> {
+        self.buffer_diff_base_revisions
+            .get(&buffer_id)
+            .or(self.diff_base_revision.as_ref())
+            .cloned()
+    }
+
+    /// Diffs all local buffers against the given revision, or against the index when it is
+    /// `None`, except for the buffers that have a revision of their own.
+    pub fn set_diff_base_revision(
+        &mut self,
+        revision: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.diff_base_revision = revision;
+        let buffers = self.buffers().collect::<Vec<_>>();
+        self.reload_buffer_diff_bases(buffers, cx)
+    }
+
+    /// Diffs a local buffer against the given revision, or against the project's diff base
+    /// when it is `None`.
+    pub fn set_buffer_diff_base_revision(
+        &mut self,
+        buffer: Model<Buffer>,
+        revision: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        match revision {
+            Some(revision) => self.buffer_diff_base_revisions.insert(buffer_id, revision),
+            None => self.buffer_diff_base_revisions.remove(&buffer_id),
+        };
+        self.reload_buffer_diff_bases(vec![buffer], cx)
+    }
+
+    fn reload_buffer_diff_bases(
+        &self,
+        buffers: Vec<Model<Buffer>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let mut buffers_by_worktree = HashMap::<EntityId, (Model<Worktree>, Vec<_>)>::default();
+        for buffer in buffers {
+            let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+                continue;
+            };
+            if !file.worktree.read(cx).is_local() {
+                continue;
+            }
+            buffers_by_worktree
+                .entry(file.worktree.entity_id())
+                .or_insert_with(|| (file.worktree.clone(), Vec::new()))
+                .1
+                .push((buffer.clone(), file.path.clone()));
+        }
+        let reloads = buffers_by_worktree
+            .into_values()
+            .map(|(worktree, buffers)| self.reload_diff_bases(worktree, buffers, cx))
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            for reload in reloads {
+                reload.await?;
+            }
+            Ok(())
+        })
     }
 
     fn open_local_buffer_internal(
@@ -325,8 +430,8 @@ impl BufferStore {
                         text_buffer,
                         None,
                         Some(Arc::new(File {
-                            worktree,
-                            path,
+                            worktree: worktree.clone(),
+                            path: path.clone(),
                             mtime: None,
                             entry_id: None,
                             is_local: true,
@@ -340,6 +445,11 @@ impl BufferStore {
             }?;
             this.update(&mut cx, |this, cx| {
                 this.add_buffer(buffer.clone(), cx).log_err();
+                // Buffers are loaded with the index as their diff base.
+                if this.diff_base_revision.is_some() {
+                    this.reload_diff_bases(worktree, vec![(buffer.clone(), path)], cx)
+                        .detach_and_log_err(cx);
+                }
             })?;
             Ok(buffer)
         })
@@ -653,6 +763,10 @@ impl BufferStore {
         }
 
         cx.subscribe(&buffer, Self::on_buffer_event).detach();
+        cx.observe_release(&buffer, move |this, _, _| {
+            this.buffer_diff_base_revisions.remove(&remote_id);
+        })
+        .detach();
         cx.emit(BufferStoreEvent::BufferAdded(buffer));
         Ok(())
     }
@@ -994,28 +1108,53 @@ impl BufferStore {
         envelope: TypedEnvelope<proto::UpdateBuffer>,
         mut cx: AsyncAppContext,
     ) -> Result<proto::Ack> {
-        let payload = envelope.payload.clone();
+        this.update(&mut cx, |this, cx| {
+            this.apply_remote_operations(envelope.payload, false, cx)
+        })?
+    }
+
+    /// Handles operations from the host of an SSH project. Unlike a collab host, it
+    /// also updates buffers that this store only holds weakly, such as when one of its
+    /// language servers reports diagnostics for a buffer that is open here.
+    pub async fn handle_update_ssh_buffer(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateBuffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            this.apply_remote_operations(envelope.payload, true, cx)
+        })?
+    }
+
+    fn apply_remote_operations(
+        &mut self,
+        payload: proto::UpdateBuffer,
+        update_weak_buffers: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<proto::Ack> {
         let buffer_id = BufferId::new(payload.buffer_id)?;
         let ops = payload
             .operations
             .into_iter()
             .map(language::proto::deserialize_operation)
             .collect::<Result<Vec<_>, _>>()?;
-        this.update(&mut cx, |this, cx| {
-            match this.opened_buffers.entry(buffer_id) {
-                hash_map::Entry::Occupied(mut e) => match e.get_mut() {
-                    OpenBuffer::Strong(buffer) => {
+        match self.opened_buffers.entry(buffer_id) {
+            hash_map::Entry::Occupied(mut e) => match e.get_mut() {
+                OpenBuffer::Strong(buffer) => {
+                    buffer.update(cx, |buffer, cx| buffer.apply_ops(ops, cx))?;
+                }
+                OpenBuffer::Operations(operations) => operations.extend_from_slice(&ops),
+                OpenBuffer::Weak(buffer) => {
+                    if let Some(buffer) = buffer.upgrade().filter(|_| update_weak_buffers) {
                         buffer.update(cx, |buffer, cx| buffer.apply_ops(ops, cx))?;
                     }
-                    OpenBuffer::Operations(operations) => operations.extend_from_slice(&ops),
-                    OpenBuffer::Weak(_) => {}
-                },
-                hash_map::Entry::Vacant(e) => {
-                    e.insert(OpenBuffer::Operations(ops));
                 }
+            },
+            hash_map::Entry::Vacant(e) => {
+                e.insert(OpenBuffer::Operations(ops));
             }
-            Ok(proto::Ack {})
-        })?
+        }
+        Ok(proto::Ack {})
     }
 
     pub fn handle_create_buffer_for_peer(
@@ -33,7 +33,10 @@ use futures::{
     AsyncWriteExt, Future, FutureExt, StreamExt,
 };
 use fuzzy::CharBag;
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    repository::{GitRepository, RepoPath},
+};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, BorrowAppContext, Context, Entity,
@@ -76,7 +79,10 @@ use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{DirenvSettings, LspSettings, ProjectSettings};
 use rand::prelude::*;
 use remote::SshSession;
-use rpc::{proto::AddWorktree, ErrorCode};
+use rpc::{
+    proto::{AddWorktree, AnyProtoClient},
+    ErrorCode,
+};
 use search::SearchQuery;
 use search_history::SearchHistory;
 use serde::Serialize;
@@ -140,6 +146,7 @@ const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
 const SERVER_REINSTALL_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(125);
 
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
 
@@ -177,11 +184,16 @@ pub struct Project {
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
     last_formatting_failure: Option<String>,
+    git_remote_operations: BTreeMap<usize, GitRemoteOperation>,
+    next_git_remote_operation_id: usize,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
     language_server_watcher_registrations:
         HashMap<LanguageServerId, HashMap<String, Vec<FileSystemWatcher>>>,
-    client: Arc<client::Client>,
+    /// The client that connects this project to collaborators, which a headless project
+    /// on an SSH host doesn't have.
+    client: Option<Arc<client::Client>>,
+    http_client: Arc<dyn HttpClient>,
     next_entry_id: Arc<AtomicUsize>,
     join_project_response_message_id: u32,
     next_diagnostic_group_id: usize,
@@ -197,7 +209,7 @@ pub struct Project {
             )>,
         >,
     >,
-    user_store: Model<UserStore>,
+    user_store: Option<Model<UserStore>>,
     fs: Arc<dyn Fs>,
     ssh_session: Option<Arc<SshSession>>,
     client_state: ProjectClientState,
@@ -211,6 +223,8 @@ pub struct Project {
     loading_worktrees:
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    semantic_tokens_results: HashMap<(BufferId, LanguageServerId), LspSemanticTokens>,
+    pulled_diagnostics: HashMap<LanguageServerId, PulledDiagnostics>,
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay<Self>,
@@ -242,6 +256,27 @@ struct LspBufferSnapshot {
     snapshot: TextBufferSnapshot,
 }
 
+/// The state of pulling diagnostics from a language server that uses the pull model.
+#[derive(Default)]
+struct PulledDiagnostics {
+    /// The result ID of the last report for each document, which lets the server answer
+    /// the next pull by saying that nothing changed.
+    result_ids: HashMap<lsp::Url, String>,
+    /// The diagnostics last pulled and pushed for each document. Both are reported together,
+    /// so that neither replaces the other.
+    pulled: HashMap<lsp::Url, Vec<lsp::Diagnostic>>,
+    pushed: HashMap<lsp::Url, Vec<lsp::Diagnostic>>,
+    document_pulls: HashMap<BufferId, Task<()>>,
+    workspace_pull: Option<Task<()>>,
+}
+
+/// The semantic tokens a language server last reported for a buffer, which its next
+/// response may describe as a delta against.
+struct LspSemanticTokens {
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+}
+
 /// Message ordered with respect to buffer operations
 #[derive(Debug)]
 enum BufferOrderedMessage {
@@ -346,6 +381,8 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
 }
@@ -369,6 +406,15 @@ pub struct LanguageServerStatus {
     progress_tokens: HashSet<String>,
 }
 
+/// An operation talking to a git remote, such as a push, that's running in the background.
+#[derive(Clone, Debug)]
+pub struct GitRemoteOperation {
+    /// What the operation does, such as "Pushing main".
+    pub title: String,
+    /// The latest progress that git reported.
+    pub message: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct LanguageServerProgress {
     pub is_disk_based_diagnostics_progress: bool,
@@ -402,6 +448,16 @@ impl ProjectPath {
     }
 }
 
+/// A range of a buffer that a language server classified with its semantic tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    /// The token type, as named by the language server's legend, e.g. `variable` or `macro`.
+    pub token_type: SharedString,
+    /// The token modifiers, as named by the language server's legend, e.g. `mutable` or `unsafe`.
+    pub modifiers: Vec<SharedString>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InlayHint {
     pub position: language::Anchor,
@@ -467,6 +523,37 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer that this code lens describes.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server, whose command is missing until the
+    /// lens is resolved.
+    pub lsp_lens: lsp::CodeLens,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FoldingRange {
+    /// The range of the buffer that can be folded, which starts at the end of its first
+    /// line and ends at the end of its last line unless the server says otherwise.
+    pub range: Range<Anchor>,
+    /// The kind of the folding range, such as a comment or a block of imports.
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentLink {
+    /// The id of the language server that produced this link.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer that links somewhere, such as a path or a dependency name.
+    pub range: Range<Anchor>,
+    /// The raw document link provided by the language server, whose target may be missing
+    /// until the link is resolved.
+    pub lsp_link: lsp::DocumentLink,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -520,6 +607,39 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+/// A symbol that can make or receive calls, such as a function or a method, as reported
+/// by a language server.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    /// The id of the language server that produced this item.
+    pub server_id: LanguageServerId,
+    /// The range of the item's name, where navigating to the item leads.
+    pub location: Location,
+    /// The raw item provided by the language server, sent back to it when expanding the item.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// A call between the queried item and another item of a call hierarchy.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges of the calls, which are always located in the caller.
+    pub call_sites: Vec<Location>,
+}
+
+/// A type that has supertypes or subtypes, such as a class or an interface, as reported by
+/// a language server.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    /// The id of the language server that produced this item.
+    pub server_id: LanguageServerId,
+    /// The range of the item's name, where navigating to the item leads.
+    pub location: Location,
+    /// The raw item provided by the language server, sent back to it when expanding the item.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -707,6 +827,9 @@ impl Project {
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_semantic_tokens);
+        client.add_model_request_handler(Self::handle_refresh_code_lens);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
@@ -717,6 +840,17 @@ impl Project {
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetReferences>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<ResolveCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_model_request_handler(Self::handle_lsp_command::<ResolveDocumentLink>);
         client.add_model_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_model_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_model_request_handler(Self::handle_search_project);
@@ -753,6 +887,39 @@ impl Project {
         languages: Arc<LanguageRegistry>,
         fs: Arc<dyn Fs>,
         cx: &mut AppContext,
+    ) -> Model<Self> {
+        let http_client = client.http_client();
+        Self::new_local(
+            Some(client),
+            Some(user_store),
+            http_client,
+            node,
+            languages,
+            fs,
+            cx,
+        )
+    }
+
+    /// Creates a local project on an SSH host, which runs the language servers for the
+    /// buffers that the SSH client opens. It can't be shared with collaborators.
+    pub fn headless(
+        http_client: Arc<dyn HttpClient>,
+        node: Arc<dyn NodeRuntime>,
+        languages: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        cx: &mut AppContext,
+    ) -> Model<Self> {
+        Self::new_local(None, None, http_client, node, languages, fs, cx)
+    }
+
+    fn new_local(
+        client: Option<Arc<Client>>,
+        user_store: Option<Model<UserStore>>,
+        http_client: Arc<dyn HttpClient>,
+        node: Arc<dyn NodeRuntime>,
+        languages: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        cx: &mut AppContext,
     ) -> Model<Self> {
         cx.new_model(|cx: &mut ModelContext<Self>| {
             let (tx, rx) = mpsc::unbounded();
@@ -782,6 +949,8 @@ impl Project {
                 shared_buffers: Default::default(),
                 loading_worktrees: Default::default(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens_results: Default::default(),
+                pulled_diagnostics: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 client_subscriptions: Vec::new(),
@@ -797,6 +966,7 @@ impl Project {
                 snippets,
                 languages,
                 client,
+                http_client,
                 user_store,
                 fs,
                 ssh_session: None,
@@ -809,6 +979,8 @@ impl Project {
                 language_server_ids: HashMap::default(),
                 language_server_statuses: Default::default(),
                 last_formatting_failure: None,
+                git_remote_operations: BTreeMap::default(),
+                next_git_remote_operation_id: 0,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
@@ -848,14 +1020,63 @@ impl Project {
 
             ssh.add_message_handler(cx.weak_model(), Self::handle_update_worktree);
             ssh.add_message_handler(cx.weak_model(), Self::handle_create_buffer_for_peer);
+            ssh.add_message_handler(cx.weak_model(), Self::handle_update_diagnostic_summary);
             ssh.add_message_handler(buffer_store.clone(), BufferStore::handle_update_buffer_file);
             ssh.add_message_handler(buffer_store.clone(), BufferStore::handle_update_diff_base);
+            ssh.add_request_handler(buffer_store.clone(), BufferStore::handle_update_ssh_buffer);
 
             this.ssh_session = Some(ssh);
         });
         this
     }
 
+    /// Serves the requests of an SSH client that need this project's language servers
+    /// or worktree contents, for a project running on the remote host.
+    pub fn add_ssh_request_handlers(this: &Model<Self>, ssh: &SshSession) {
+        let this = this.downgrade();
+        ssh.add_request_handler(this.clone(), Self::handle_multi_lsp_query);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetCodeActions>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetCompletions>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetHover>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetDefinition>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetTypeDefinition>);
+        ssh.add_request_handler(
+            this.clone(),
+            Self::handle_lsp_command::<GetDocumentHighlights>,
+        );
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetReferences>);
+        ssh.add_request_handler(
+            this.clone(),
+            Self::handle_lsp_command::<PrepareCallHierarchy>,
+        );
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetIncomingCalls>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetOutgoingCalls>);
+        ssh.add_request_handler(
+            this.clone(),
+            Self::handle_lsp_command::<PrepareTypeHierarchy>,
+        );
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetSupertypes>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetSubtypes>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetCodeLens>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<ResolveCodeLens>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetFoldingRanges>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<GetDocumentLinks>);
+        ssh.add_request_handler(
+            this.clone(),
+            Self::handle_lsp_command::<ResolveDocumentLink>,
+        );
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<PrepareRename>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<PerformRename>);
+        ssh.add_request_handler(this.clone(), Self::handle_lsp_command::<LinkedEditingRange>);
+        ssh.add_request_handler(
+            this.clone(),
+            Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>,
+        );
+        ssh.add_request_handler(this.clone(), Self::handle_semantic_tokens);
+        ssh.add_request_handler(this.clone(), Self::handle_task_context_for_location);
+        ssh.add_request_handler(this, Self::handle_task_templates);
+    }
+
     pub async fn remote(
         remote_id: u64,
         client: Arc<Client>,
@@ -958,7 +1179,7 @@ impl Project {
                 _maintain_buffer_languages: Self::maintain_buffer_languages(languages.clone(), cx),
                 _maintain_workspace_config: Self::maintain_workspace_config(cx),
                 languages,
-                user_store: user_store.clone(),
+                user_store: Some(user_store.clone()),
                 snippets,
                 yarn,
                 fs,
@@ -972,7 +1193,8 @@ impl Project {
                     cx.on_release(Self::release),
                     cx.on_app_quit(Self::shutdown_language_servers),
                 ],
-                client: client.clone(),
+                client: Some(client.clone()),
+                http_client: client.http_client(),
                 client_state: ProjectClientState::Remote {
                     sharing_has_stopped: false,
                     capability: Capability::ReadWrite,
@@ -1000,6 +1222,8 @@ impl Project {
                     })
                     .collect(),
                 last_formatting_failure: None,
+                git_remote_operations: BTreeMap::default(),
+                next_git_remote_operation_id: 0,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
@@ -1007,6 +1231,8 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens_results: Default::default(),
+                pulled_diagnostics: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
@@ -1101,9 +1327,11 @@ impl Project {
                 let _ = self.unshare_internal(cx);
             }
             ProjectClientState::Remote { remote_id, .. } => {
-                let _ = self.client.send(proto::LeaveProject {
-                    project_id: *remote_id,
-                });
+                if let Some(client) = &self.client {
+                    let _ = client.send(proto::LeaveProject {
+                        project_id: *remote_id,
+                    });
+                }
                 self.disconnected_from_host_internal(cx);
             }
         }
@@ -1329,11 +1557,19 @@ impl Project {
         &self.languages
     }
 
-    pub fn client(&self) -> Arc<Client> {
+    pub fn worktree_store(&self) -> Model<WorktreeStore> {
+        self.worktree_store.clone()
+    }
+
+    pub fn buffer_store(&self) -> Model<BufferStore> {
+        self.buffer_store.clone()
+    }
+
+    pub fn client(&self) -> Option<Arc<Client>> {
         self.client.clone()
     }
 
-    pub fn user_store(&self) -> Model<UserStore> {
+    pub fn user_store(&self) -> Option<Model<UserStore>> {
         self.user_store.clone()
     }
 
@@ -1386,7 +1622,7 @@ impl Project {
         server.ssh_connection_string.is_some()
     }
 
-    pub fn ssh_connection_string(&self, cx: &ModelContext<Self>) -> Option<SharedString> {
+    pub fn ssh_connection_string(&self, cx: &AppContext) -> Option<SharedString> {
         if self.is_local() {
             return None;
         }
@@ -1601,14 +1837,18 @@ impl Project {
                 return Err(anyhow!("project was already shared"));
             }
         }
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| anyhow!("project can't be shared without a client"))?;
         self.client_subscriptions.extend([
-            self.client
+            client
                 .subscribe_to_entity(project_id)?
                 .set_model(&cx.handle(), &mut cx.to_async()),
-            self.client
+            client
                 .subscribe_to_entity(project_id)?
                 .set_model(&self.worktree_store, &mut cx.to_async()),
-            self.client
+            client
                 .subscribe_to_entity(project_id)?
                 .set_model(&self.buffer_store, &mut cx.to_async()),
         ]);
@@ -1621,7 +1861,7 @@ impl Project {
         });
 
         for (server_id, status) in &self.language_server_statuses {
-            self.client
+            client
                 .send(proto::StartLanguageServer {
                     project_id,
                     server: Some(proto::LanguageServer {
@@ -1636,7 +1876,7 @@ impl Project {
         for worktree in self.worktrees(cx) {
             let worktree_id = worktree.read(cx).id().to_proto();
             for (path, content) in store.local_settings(worktree.entity_id().as_u64() as usize) {
-                self.client
+                client
                     .send(proto::UpdateWorktreeSettings {
                         project_id,
                         worktree_id,
@@ -1648,7 +1888,6 @@ impl Project {
         }
 
         let (updates_tx, mut updates_rx) = mpsc::unbounded();
-        let client = self.client.clone();
         self.client_state = ProjectClientState::Shared {
             remote_id: project_id,
             updates_tx,
@@ -1662,7 +1901,7 @@ impl Project {
 
                             let update_project = this
                                 .update(&mut cx, |this, cx| {
-                                    this.client.request(proto::UpdateProject {
+                                    client.request(proto::UpdateProject {
                                         project_id,
                                         worktrees: this.worktree_metadata_protos(cx),
                                     })
@@ -1680,7 +1919,7 @@ impl Project {
                                         {
                                             for (path, summaries) in summaries {
                                                 for (&server_id, summary) in summaries {
-                                                    this.client.send(
+                                                    client.send(
                                                         proto::UpdateDiagnosticSummary {
                                                             project_id,
                                                             worktree_id: worktree.id().to_proto(),
@@ -1826,11 +2065,13 @@ impl Project {
             });
             self.buffer_store
                 .update(cx, |buffer_store, cx| buffer_store.set_remote_id(None, cx));
-            self.client
-                .send(proto::UnshareProject {
-                    project_id: remote_id,
-                })
-                .ok();
+            if let Some(client) = &self.client {
+                client
+                    .send(proto::UnshareProject {
+                        project_id: remote_id,
+                    })
+                    .ok();
+            }
             Ok(())
         } else {
             Err(anyhow!("attempted to unshare an unshared project"))
@@ -1915,21 +2156,42 @@ impl Project {
     }
 
     pub fn is_ssh(&self) -> bool {
-        match &self.client_state {
-            ProjectClientState::Local | ProjectClientState::Shared { .. } => true,
-            ProjectClientState::Remote { .. } => false,
-        }
+        self.ssh_session.is_some()
     }
 
     pub fn is_remote(&self) -> bool {
         !self.is_local()
     }
 
+    /// Whether the language servers for this project's buffers run in this process,
+    /// as opposed to on a collaborator's machine or on an SSH remote server.
+    fn has_local_language_servers(&self) -> bool {
+        self.is_local() && !self.is_ssh()
+    }
+
+    /// The client through which requests are forwarded when this project's worktrees
+    /// live on another machine, along with the project id those requests should carry.
+    fn upstream_client(&self) -> Option<(AnyProtoClient, u64)> {
+        if let Some(ssh) = &self.ssh_session {
+            Some((ssh.clone().into(), 0))
+        } else {
+            let (client, project_id) = self.collab_client()?;
+            Some((client.into(), project_id))
+        }
+    }
+
+    /// The client that connects this project to its collaborators, along with the project's
+    /// id on the server, while it is shared or joined.
+    fn collab_client(&self) -> Option<(Arc<Client>, u64)> {
+        Some((self.client.clone()?, self.remote_id()?))
+    }
+
     pub fn create_buffer(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<Model<Buffer>>> {
         self.buffer_store.update(cx, |buffer_store, cx| {
             buffer_store.create_buffer(
                 if self.is_remote() {
-                    Some((self.client.clone().into(), self.remote_id().unwrap()))
+                    self.collab_client()
+                        .map(|(client, project_id)| (client.into(), project_id))
                 } else {
                     None
                 },
@@ -2077,8 +2339,8 @@ impl Project {
             Task::ready(Ok(buffer))
         } else if self.is_local() {
             Task::ready(Err(anyhow!("buffer {} does not exist", id)))
-        } else if let Some(project_id) = self.remote_id() {
-            let request = self.client.request(proto::OpenBufferById {
+        } else if let Some((client, project_id)) = self.collab_client() {
+            let request = client.request(proto::OpenBufferById {
                 project_id,
                 id: id.into(),
             });
@@ -2257,6 +2519,7 @@ impl Project {
                         .entry(buffer_id)
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
+                    self.pull_document_diagnostics(buffer_handle, server.server_id(), false, cx);
                 }
             }
         }
@@ -2286,6 +2549,13 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.semantic_tokens_results
+                .retain(|(buffer_id, _), _| *buffer_id != buffer.remote_id());
+            for pulled_diagnostics in self.pulled_diagnostics.values_mut() {
+                pulled_diagnostics
+                    .document_pulls
+                    .remove(&buffer.remote_id());
+            }
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
@@ -2316,8 +2586,8 @@ impl Project {
         ) -> Result<()> {
             for (buffer_id, operations) in operations_by_buffer_id.drain() {
                 let request = this.update(cx, |this, _| {
-                    let project_id = this.remote_id()?;
-                    Some(this.client.request(proto::UpdateBuffer {
+                    let (client, project_id) = this.collab_client()?;
+                    Some(client.request(proto::UpdateBuffer {
                         buffer_id: buffer_id.into(),
                         project_id,
                         operations,
@@ -2380,8 +2650,8 @@ impl Project {
                         .await?;
 
                         this.update(&mut cx, |this, _| {
-                            if let Some(project_id) = this.remote_id() {
-                                this.client
+                            if let Some((client, project_id)) = this.collab_client() {
+                                client
                                     .send(proto::UpdateLanguageServer {
                                         project_id,
                                         language_server_id: language_server_id.0 as u64,
@@ -2426,7 +2696,9 @@ impl Project {
                 self.register_buffer_with_language_servers(&buffer, cx);
             }
             BufferStoreEvent::MessageToReplicas(message) => {
-                self.client.send_dynamic(message.as_ref().clone()).log_err();
+                if let Some((client, _)) = self.collab_client() {
+                    client.send_dynamic(message.as_ref().clone()).log_err();
+                }
             }
         }
     }
@@ -2480,9 +2752,9 @@ impl Project {
 
             BufferEvent::Reloaded => {
                 if self.is_local() {
-                    if let Some(project_id) = self.remote_id() {
+                    if let Some((client, project_id)) = self.collab_client() {
                         let buffer = buffer.read(cx);
-                        self.client
+                        client
                             .send(proto::BufferReloaded {
                                 project_id,
                                 buffer_id: buffer.remote_id().to_proto(),
@@ -2496,6 +2768,7 @@ impl Project {
             }
 
             BufferEvent::Edited { .. } => {
+                let buffer_handle = buffer.clone();
                 let buffer = buffer.read(cx);
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2588,6 +2861,8 @@ impl Project {
                         )
                         .log_err();
                 }
+
+                self.pull_buffer_diagnostics(&buffer_handle, cx);
             }
 
             BufferEvent::Saved => {
@@ -2619,6 +2894,8 @@ impl Project {
                 for language_server_id in self.language_server_ids_for_buffer(buffer.read(cx), cx) {
                     self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
                 }
+
+                self.pull_buffer_diagnostics(&buffer, cx);
             }
 
             _ => {}
@@ -3239,7 +3516,7 @@ impl Project {
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
                         this.update(&mut cx, |this, cx| {
-                            this.update_diagnostics(
+                            this.update_pushed_diagnostics(
                                 server_id,
                                 params,
                                 &adapter.disk_based_diagnostic_sources,
@@ -3488,6 +3765,59 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = project.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshSemanticTokens);
+                            project.collab_client().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = project.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshCodeLens);
+                            project.collab_client().map(|(client, project_id)| {
+                                client.send(proto::RefreshCodeLens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = project.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            project.refresh_pulled_diagnostics(server_id, false, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::InlayHintRefreshRequest, _, _>({
                 let this = project.clone();
@@ -3496,8 +3826,8 @@ impl Project {
                     async move {
                         this.update(&mut cx, |project, cx| {
                             cx.emit(Event::RefreshInlayHints);
-                            project.remote_id().map(|project_id| {
-                                project.client.send(proto::RefreshInlayHints { project_id })
+                            project.collab_client().map(|(client, project_id)| {
+                                client.send(proto::RefreshInlayHints { project_id })
                             })
                         })?
                         .transpose()?;
@@ -3698,8 +4028,8 @@ impl Project {
 
         cx.emit(Event::LanguageServerAdded(server_id));
 
-        if let Some(project_id) = self.remote_id() {
-            self.client.send(proto::StartLanguageServer {
+        if let Some((client, project_id)) = self.collab_client() {
+            client.send(proto::StartLanguageServer {
                 project_id,
                 server: Some(proto::LanguageServer {
                     id: server_id.0 as u64,
@@ -3777,6 +4107,7 @@ impl Project {
             }
             anyhow::Ok(())
         })?;
+        self.refresh_pulled_diagnostics(server_id, false, cx);
 
         cx.notify();
         Ok(())
@@ -3813,14 +4144,14 @@ impl Project {
                 }
             });
 
-            let project_id = self.remote_id();
+            let collab_client = self.collab_client();
             for (worktree_id, summaries) in self.diagnostic_summaries.iter_mut() {
                 summaries.retain(|path, summaries_by_server_id| {
                     if summaries_by_server_id.remove(&server_id).is_some() {
-                        if let Some(project_id) = project_id {
-                            self.client
+                        if let Some((client, project_id)) = &collab_client {
+                            client
                                 .send(proto::UpdateDiagnosticSummary {
-                                    project_id,
+                                    project_id: *project_id,
                                     worktree_id: worktree_id.to_proto(),
                                     summary: Some(proto::DiagnosticSummary {
                                         path: path.to_string_lossy().to_string(),
@@ -3853,6 +4184,9 @@ impl Project {
 
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
+            self.pulled_diagnostics.remove(&server_id);
+            self.semantic_tokens_results
+                .retain(|(_, token_server_id), _| *token_server_id != server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -3928,16 +4262,18 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) {
         if self.is_remote() {
-            let request = self.client.request(proto::RestartLanguageServers {
-                project_id: self.remote_id().unwrap(),
-                buffer_ids: buffers
-                    .into_iter()
-                    .map(|b| b.read(cx).remote_id().to_proto())
-                    .collect(),
-            });
-            cx.background_executor()
-                .spawn(request)
-                .detach_and_log_err(cx);
+            if let Some((client, project_id)) = self.collab_client() {
+                let request = client.request(proto::RestartLanguageServers {
+                    project_id,
+                    buffer_ids: buffers
+                        .into_iter()
+                        .map(|b| b.read(cx).remote_id().to_proto())
+                        .collect(),
+                });
+                cx.background_executor()
+                    .spawn(request)
+                    .detach_and_log_err(cx);
+            }
             return;
         }
 
@@ -4481,6 +4817,47 @@ impl Project {
         self.last_formatting_failure.as_deref()
     }
 
+    /// Records an operation talking to a git remote, so that its progress can be shown
+    /// while it runs. Returns the id to report its progress with.
+    pub fn start_git_remote_operation(
+        &mut self,
+        title: String,
+        cx: &mut ModelContext<Self>,
+    ) -> usize {
+        let id = post_inc(&mut self.next_git_remote_operation_id);
+        self.git_remote_operations.insert(
+            id,
+            GitRemoteOperation {
+                title,
+                message: None,
+            },
+        );
+        cx.notify();
+        id
+    }
+
+    pub fn update_git_remote_operation(
+        &mut self,
+        id: usize,
+        message: String,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(operation) = self.git_remote_operations.get_mut(&id) {
+            operation.message = Some(message);
+            cx.notify();
+        }
+    }
+
+    pub fn finish_git_remote_operation(&mut self, id: usize, cx: &mut ModelContext<Self>) {
+        if self.git_remote_operations.remove(&id).is_some() {
+            cx.notify();
+        }
+    }
+
+    pub fn git_remote_operations(&self) -> impl Iterator<Item = &GitRemoteOperation> {
+        self.git_remote_operations.values()
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -4592,15 +4969,303 @@ impl Project {
                 }
             }
         }
-
-        self.update_diagnostic_entries(
-            language_server_id,
-            abs_path,
-            params.version,
-            diagnostics,
-            cx,
-        )?;
-        Ok(())
+
+        self.update_diagnostic_entries(
+            language_server_id,
+            abs_path,
+            params.version,
+            diagnostics,
+            cx,
+        )?;
+        Ok(())
+    }
+
+    /// Pulls the diagnostics of a buffer that was changed or saved from each of its language
+    /// servers that use the pull model, after a short delay. Servers whose diagnostics have
+    /// inter-file dependencies are asked again for every open buffer and the workspace.
+    fn pull_buffer_diagnostics(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let servers = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .filter_map(|(_, server)| {
+                let options = pull_diagnostic_options(&server.capabilities())?;
+                Some((server.server_id(), options.inter_file_dependencies))
+            })
+            .collect::<Vec<_>>();
+        for (server_id, inter_file_dependencies) in servers {
+            if inter_file_dependencies {
+                self.refresh_pulled_diagnostics(server_id, true, cx);
+            } else {
+                self.pull_document_diagnostics(buffer, server_id, true, cx);
+            }
+        }
+    }
+
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        server_id: LanguageServerId,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { server, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(options) = pull_diagnostic_options(&server.capabilities()) else {
+            return;
+        };
+        let server = server.clone();
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(uri) = File::from_dyn(buffer.file())
+            .and_then(|file| file.as_local())
+            .and_then(|file| lsp::Url::from_file_path(file.abs_path(cx)).log_err())
+        else {
+            return;
+        };
+        let version = self
+            .buffer_snapshots
+            .get(&buffer_id)
+            .and_then(|snapshots| snapshots.get(&server_id)?.last())
+            .map(|snapshot| snapshot.version);
+
+        let pulled_diagnostics = self.pulled_diagnostics.entry(server_id).or_default();
+        let params = lsp::DocumentDiagnosticParams {
+            text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+            identifier: options.identifier,
+            previous_result_id: pulled_diagnostics.result_ids.get(&uri).cloned(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let task = cx.spawn(move |this, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+            let Some(result) = server
+                .request::<lsp::request::DocumentDiagnosticRequest>(params)
+                .await
+                .log_err()
+            else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                let related_documents = match result {
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Full(report),
+                    ) => {
+                        this.update_pulled_diagnostics(
+                            server_id,
+                            uri,
+                            version,
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                            cx,
+                        );
+                        report.related_documents
+                    }
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Unchanged(report),
+                    ) => {
+                        this.update_pulled_diagnostics(
+                            server_id,
+                            uri,
+                            version,
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                            cx,
+                        );
+                        report.related_documents
+                    }
+                    lsp::DocumentDiagnosticReportResult::Partial(report) => {
+                        report.related_documents
+                    }
+                };
+                for (uri, report) in related_documents.into_iter().flatten() {
+                    this.update_pulled_diagnostics(server_id, uri, None, report, cx);
+                }
+            })
+            .ok();
+        });
+        pulled_diagnostics.document_pulls.insert(buffer_id, task);
+    }
+
+    /// Pulls the diagnostics of the whole workspace from a language server that supports
+    /// it, reporting the result IDs of the documents it previously described.
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { server, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(options) = pull_diagnostic_options(&server.capabilities())
+            .filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        let server = server.clone();
+
+        let pulled_diagnostics = self.pulled_diagnostics.entry(server_id).or_default();
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier,
+            previous_result_ids: pulled_diagnostics
+                .result_ids
+                .iter()
+                .map(|(uri, result_id)| lsp::PreviousResultId {
+                    uri: uri.clone(),
+                    value: result_id.clone(),
+                })
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        pulled_diagnostics.workspace_pull = Some(cx.spawn(move |this, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE_TIMEOUT)
+                    .await;
+            }
+            let Some(result) = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                .await
+                .log_err()
+            else {
+                return;
+            };
+            let items = match result {
+                lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+            };
+            this.update(&mut cx, |this, cx| {
+                for item in items {
+                    let (uri, version, report) = match item {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    let version = version.and_then(|version| i32::try_from(version).ok());
+                    this.update_pulled_diagnostics(server_id, uri, version, report, cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Pulls the diagnostics of every open buffer from a language server, and those of the
+    /// workspace when the server supports it, after a short delay when `debounce` is set.
+    fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self
+            .buffer_snapshots
+            .iter()
+            .filter(|(_, snapshots)| snapshots.contains_key(&server_id))
+            .filter_map(|(buffer_id, _)| self.buffer_store.read(cx).get(*buffer_id))
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(&buffer, server_id, debounce, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, debounce, cx);
+    }
+
+    /// Stores the diagnostics that a language server pushed for a document. Those of servers
+    /// that use the pull model too are reported along with the ones last pulled.
+    fn update_pushed_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        mut params: lsp::PublishDiagnosticsParams,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let pulls_diagnostics = match self.language_servers.get(&server_id) {
+            Some(LanguageServerState::Running { server, .. }) => {
+                pull_diagnostic_options(&server.capabilities()).is_some()
+            }
+            _ => false,
+        };
+        if pulls_diagnostics {
+            let pulled_diagnostics = self.pulled_diagnostics.entry(server_id).or_default();
+            pulled_diagnostics
+                .pushed
+                .insert(params.uri.clone(), params.diagnostics.clone());
+            if let Some(pulled) = pulled_diagnostics.pulled.get(&params.uri) {
+                params.diagnostics.extend(pulled.iter().cloned());
+            }
+        }
+        self.update_diagnostics(server_id, params, disk_based_sources, cx)
+    }
+
+    /// Stores the diagnostics that a language server reported for a document when they were
+    /// pulled, along with the ones it last pushed for it.
+    fn update_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let adapter = adapter.clone();
+        let pulled_diagnostics = self.pulled_diagnostics.entry(server_id).or_default();
+        match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                let result_ids = &mut pulled_diagnostics.result_ids;
+                match report.result_id {
+                    Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                    None => result_ids.remove(&uri),
+                };
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                pulled_diagnostics
+                    .pulled
+                    .insert(params.uri.clone(), params.diagnostics.clone());
+                if let Some(pushed) = pulled_diagnostics.pushed.get(&params.uri) {
+                    params.diagnostics.extend(pushed.iter().cloned());
+                }
+                self.update_diagnostics(
+                    server_id,
+                    params,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+                .log_err();
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                pulled_diagnostics.result_ids.insert(uri, report.result_id);
+            }
+        }
     }
 
     pub fn update_diagnostic_entries(
@@ -4684,8 +5349,8 @@ impl Project {
         }
 
         if !old_summary.is_empty() || !new_summary.is_empty() {
-            if let Some(project_id) = self.remote_id() {
-                self.client
+            if let Some((client, project_id)) = self.collab_client() {
+                client
                     .send(proto::UpdateDiagnosticSummary {
                         project_id,
                         worktree_id: worktree_id.to_proto(),
@@ -4797,13 +5462,12 @@ impl Project {
             }
         }
 
-        let remote_buffers = self.remote_id().zip(remote_buffers);
-        let client = self.client.clone();
+        let remote_buffers = self.collab_client().zip(remote_buffers);
 
         cx.spawn(move |this, mut cx| async move {
             let mut project_transaction = ProjectTransaction::default();
 
-            if let Some((project_id, remote_buffers)) = remote_buffers {
+            if let Some(((client, project_id), remote_buffers)) = remote_buffers {
                 let response = client
                     .request(proto::ReloadBuffers {
                         project_id,
@@ -4879,10 +5543,9 @@ impl Project {
                 result
             })
         } else {
-            let remote_id = self.remote_id();
-            let client = self.client.clone();
+            let collab_client = self.collab_client();
             cx.spawn(move |this, mut cx| async move {
-                if let Some(project_id) = remote_id {
+                if let Some((client, project_id)) = collab_client {
                     let response = client
                         .request(proto::FormatBuffers {
                             project_id,
@@ -5502,6 +6165,49 @@ impl Project {
         self.implementation_impl(buffer, position, cx)
     }
 
+    /// Resolves the type hierarchy items at the given position, which can then be expanded
+    /// with [`Project::supertypes`] and [`Project::subtypes`].
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     fn references_impl(
         &self,
         buffer: &Model<Buffer>,
@@ -5525,6 +6231,49 @@ impl Project {
         self.references_impl(buffer, position, cx)
     }
 
+    /// Resolves the call hierarchy items at the given position, which can then be expanded
+    /// with [`Project::incoming_calls`] and [`Project::outgoing_calls`].
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &self,
         buffer: &Model<Buffer>,
@@ -5676,8 +6425,8 @@ impl Project {
 
                 Ok(symbols)
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let request = self.client.request(proto::GetProjectSymbols {
+        } else if let Some((client, project_id)) = self.collab_client() {
+            let request = client.request(proto::GetProjectSymbols {
                 project_id,
                 query: query.to_string(),
             });
@@ -5743,8 +6492,8 @@ impl Project {
                 symbol.language_server_name.clone(),
                 cx,
             )
-        } else if let Some(project_id) = self.remote_id() {
-            let request = self.client.request(proto::OpenBufferForSymbol {
+        } else if let Some((client, project_id)) = self.collab_client() {
+            let request = client.request(proto::OpenBufferForSymbol {
                 project_id,
                 symbol: Some(serialize_symbol(symbol)),
             });
@@ -5768,7 +6517,7 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Vec<SignatureHelp>> {
         let position = position.to_point_utf16(buffer.read(cx));
-        if self.is_local() {
+        if self.has_local_language_servers() {
             let all_actions_task = self.request_multiple_lsp_locally(
                 buffer,
                 Some(position),
@@ -5783,8 +6532,8 @@ impl Project {
                     .filter(|help| !help.markdown.is_empty())
                     .collect::<Vec<_>>()
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let request_task = self.client().request(proto::MultiLspQuery {
+        } else if let Some((client, project_id)) = self.upstream_client() {
+            let request_task = client.request(proto::MultiLspQuery {
                 buffer_id: buffer.read(cx).remote_id().into(),
                 version: serialize_version(&buffer.read(cx).version()),
                 project_id,
@@ -5842,7 +6591,7 @@ impl Project {
         position: PointUtf16,
         cx: &mut ModelContext<Self>,
     ) -> Task<Vec<Hover>> {
-        if self.is_local() {
+        if self.has_local_language_servers() {
             let all_actions_task = self.request_multiple_lsp_locally(
                 &buffer,
                 Some(position),
@@ -5856,8 +6605,8 @@ impl Project {
                     .filter_map(|hover| remove_empty_hover_blocks(hover?))
                     .collect::<Vec<Hover>>()
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let request_task = self.client().request(proto::MultiLspQuery {
+        } else if let Some((client, project_id)) = self.upstream_client() {
+            let request_task = client.request(proto::MultiLspQuery {
                 buffer_id: buffer.read(cx).remote_id().into(),
                 version: serialize_version(&buffer.read(cx).version()),
                 project_id,
@@ -5992,7 +6741,7 @@ impl Project {
     ) -> Task<Result<Vec<Completion>>> {
         let language_registry = self.languages.clone();
 
-        if self.is_local() {
+        if self.has_local_language_servers() {
             let snapshot = buffer.read(cx).snapshot();
             let offset = position.to_offset(&snapshot);
             let scope = snapshot.language_scope_at(offset);
@@ -6047,9 +6796,10 @@ impl Project {
 
                 Ok(completions)
             })
-        } else if let Some(project_id) = self.remote_id() {
+        } else if let Some((client, project_id)) = self.upstream_client() {
             let task = self.send_lsp_proto_request(
                 buffer.clone(),
+                client,
                 project_id,
                 GetCompletions { position, context },
                 cx,
@@ -6099,11 +6849,10 @@ impl Project {
         completions: Arc<RwLock<Box<[Completion]>>>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<bool>> {
-        let client = self.client();
         let language_registry = self.languages().clone();
 
         let is_remote = self.is_remote();
-        let project_id = self.remote_id();
+        let collab_client = self.collab_client();
 
         let buffer_id = buffer.read(cx).remote_id();
         let buffer_snapshot = buffer.read(cx).snapshot();
@@ -6111,8 +6860,8 @@ impl Project {
         cx.spawn(move |this, mut cx| async move {
             let mut did_resolve = false;
             if is_remote {
-                let project_id =
-                    project_id.ok_or_else(|| anyhow!("Remote project without remote_id"))?;
+                let (client, project_id) =
+                    collab_client.ok_or_else(|| anyhow!("Remote project without remote_id"))?;
 
                 for completion_index in completion_indices {
                     let (server_id, completion) = {
@@ -6383,8 +7132,7 @@ impl Project {
                     Ok(None)
                 }
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let client = self.client.clone();
+        } else if let Some((client, project_id)) = self.collab_client() {
             cx.spawn(move |_, mut cx| async move {
                 let response = client
                     .request(proto::ApplyCompletionAdditionalEdits {
@@ -6427,7 +7175,7 @@ impl Project {
         range: Range<Anchor>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Vec<CodeAction>> {
-        if self.is_local() {
+        if self.has_local_language_servers() {
             let all_actions_task = self.request_multiple_lsp_locally(
                 &buffer_handle,
                 Some(range.start),
@@ -6438,8 +7186,8 @@ impl Project {
                 cx,
             );
             cx.spawn(|_, _| async move { all_actions_task.await.into_iter().flatten().collect() })
-        } else if let Some(project_id) = self.remote_id() {
-            let request_task = self.client().request(proto::MultiLspQuery {
+        } else if let Some((client, project_id)) = self.upstream_client() {
+            let request_task = client.request(proto::MultiLspQuery {
                 buffer_id: buffer_handle.read(cx).remote_id().into(),
                 version: serialize_version(&buffer_handle.read(cx).version()),
                 project_id,
@@ -6573,8 +7321,7 @@ impl Project {
 
                 Ok(ProjectTransaction::default())
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let client = self.client.clone();
+        } else if let Some((client, project_id)) = self.collab_client() {
             let request = proto::ApplyCodeAction {
                 project_id,
                 buffer_id: buffer_handle.read(cx).remote_id().into(),
@@ -6593,6 +7340,30 @@ impl Project {
         }
     }
 
+    /// Runs the command of a code lens on the language server that produced it.
+    pub fn apply_code_lens(
+        &self,
+        buffer: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        // A lens runs its command the same way as a code action with no edit, which also
+        // forwards it to the host in remote projects.
+        let action = CodeAction {
+            server_id: lens.server_id,
+            range: lens.range,
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..Default::default()
+            },
+        };
+        self.apply_code_action(buffer, action, true, cx)
+    }
+
     fn apply_on_type_formatting(
         &self,
         buffer: Model<Buffer>,
@@ -6633,8 +7404,7 @@ impl Project {
                 })?
                 .await
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let client = self.client.clone();
+        } else if let Some((client, project_id)) = self.collab_client() {
             let request = proto::OnTypeFormatting {
                 project_id,
                 buffer_id: buffer.read(cx).remote_id().into(),
@@ -7004,6 +7774,145 @@ impl Project {
         self.on_type_format_impl(buffer, position, trigger, push_to_history, cx)
     }
 
+    /// Requests the semantic tokens of the whole buffer from its primary language server,
+    /// as a delta against the previous response when the server supports it.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<SemanticToken>>> {
+        let buffer = buffer_handle.read(cx);
+        if !self.has_local_language_servers() {
+            return self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Primary,
+                GetSemanticTokens,
+                cx,
+            );
+        }
+
+        let Some((_, server)) = self.primary_language_server_for_buffer(buffer, cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let server_id = server.server_id();
+        let previous_result_id = self
+            .semantic_tokens_results
+            .get(&(buffer.remote_id(), server_id))
+            .and_then(|previous| previous.result_id.clone())
+            .filter(|_| GetSemanticTokensDelta::supported(&server.capabilities()));
+        match previous_result_id {
+            Some(previous_result_id) => self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Other(server_id),
+                GetSemanticTokensDelta { previous_result_id },
+                cx,
+            ),
+            None => self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Other(server_id),
+                GetSemanticTokens,
+                cx,
+            ),
+        }
+    }
+
+    /// Requests the code lenses of a buffer from its primary language server. Servers may
+    /// leave out the commands of the lenses, which are then filled in by `resolve_code_lens`.
+    pub fn code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Requests the command of a code lens that arrived without one from the language server
+    /// that produced it. The lens is returned unchanged when the server can't resolve it.
+    pub fn resolve_code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CodeLens>> {
+        if lens.lsp_lens.command.is_some() {
+            return Task::ready(Ok(lens));
+        }
+        let request = self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(lens.server_id),
+            ResolveCodeLens {
+                lens: lens.lsp_lens.clone(),
+            },
+            cx,
+        );
+        cx.background_executor().spawn(async move {
+            let lsp_lens = request.await?.unwrap_or(lens.lsp_lens);
+            Ok(CodeLens { lsp_lens, ..lens })
+        })
+    }
+
+    /// Requests the folding ranges of a buffer from its primary language server, or `None`
+    /// when the server does not provide them.
+    pub fn folding_ranges(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Vec<FoldingRange>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    /// Requests the document links of a buffer from its primary language server. Servers may
+    /// leave out the targets of the links, which are then filled in by
+    /// `resolve_document_link`.
+    pub fn document_links(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Requests the target of a document link that arrived without one from the language
+    /// server that produced it. The link is returned unchanged when the server can't resolve
+    /// it.
+    pub fn resolve_document_link(
+        &self,
+        buffer: &Model<Buffer>,
+        link: DocumentLink,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.lsp_link.target.is_some() {
+            return Task::ready(Ok(link));
+        }
+        let request = self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(link.server_id),
+            ResolveDocumentLink {
+                link: link.lsp_link.clone(),
+            },
+            cx,
+        );
+        cx.background_executor().spawn(async move {
+            let lsp_link = request.await?.unwrap_or(link.lsp_link);
+            Ok(DocumentLink { lsp_link, ..link })
+        })
+    }
+
     pub fn inlay_hints<T: ToOffset>(
         &mut self,
         buffer_handle: Model<Buffer>,
@@ -7042,8 +7951,7 @@ impl Project {
                     .context("waiting for inlay hint request range edits")?;
                 lsp_request_task.await.context("inlay hints LSP request")
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let client = self.client.clone();
+        } else if let Some((client, project_id)) = self.collab_client() {
             let request = proto::InlayHints {
                 project_id,
                 buffer_id,
@@ -7110,8 +8018,7 @@ impl Project {
                 .await?;
                 Ok(resolved_hint)
             })
-        } else if let Some(project_id) = self.remote_id() {
-            let client = self.client.clone();
+        } else if let Some((client, project_id)) = self.collab_client() {
             let request = proto::ResolveInlayHint {
                 project_id,
                 buffer_id: buffer_handle.read(cx).remote_id().into(),
@@ -7140,11 +8047,11 @@ impl Project {
         query: SearchQuery,
         cx: &mut ModelContext<Self>,
     ) -> Receiver<SearchResult> {
-        if self.is_local() {
+        if self.is_local() && !self.is_ssh() {
             self.search_local(query, cx)
-        } else if let Some(project_id) = self.remote_id() {
+        } else if let Some((client, project_id)) = self.upstream_client() {
             let (tx, rx) = smol::channel::unbounded();
-            let request = self.client.request(query.to_proto(project_id));
+            let request = client.request(query.to_proto(project_id));
             cx.spawn(move |this, mut cx| async move {
                 let response = request.await?;
                 let mut result = HashMap::default();
@@ -7482,7 +8389,7 @@ impl Project {
         <R::LspRequest as lsp::request::Request>::Params: Send,
     {
         let buffer = buffer_handle.read(cx);
-        if self.is_local() {
+        if self.has_local_language_servers() {
             let language_server = match server {
                 LanguageServerToQuery::Primary => {
                     match self.primary_language_server_for_buffer(buffer, cx) {
@@ -7564,8 +8471,8 @@ impl Project {
                         .await
                 });
             }
-        } else if let Some(project_id) = self.remote_id() {
-            return self.send_lsp_proto_request(buffer_handle, project_id, request, cx);
+        } else if let Some((client, project_id)) = self.upstream_client() {
+            return self.send_lsp_proto_request(buffer_handle, client, project_id, request, cx);
         }
 
         Task::ready(Ok(Default::default()))
@@ -7623,11 +8530,11 @@ impl Project {
     fn send_lsp_proto_request<R: LspCommand>(
         &self,
         buffer: Model<Buffer>,
+        rpc: AnyProtoClient,
         project_id: u64,
         request: R,
         cx: &mut ModelContext<'_, Project>,
     ) -> Task<anyhow::Result<<R as LspCommand>::Response>> {
-        let rpc = self.client.clone();
         let message = request.to_proto(project_id, buffer.read(cx));
         cx.spawn(move |this, mut cx| async move {
             // Ensure the project is still alive by the time the task
@@ -7717,16 +8624,20 @@ impl Project {
     ) -> Task<Result<Vec<PathBuf>>> {
         if self.is_local() {
             DirectoryLister::Local(self.fs.clone()).list_directory(query, cx)
-        } else if let Some(dev_server) = self.dev_server_project_id().and_then(|id| {
-            dev_server_projects::Store::global(cx)
-                .read(cx)
-                .dev_server_for_project(id)
-        }) {
+        } else if let Some((client, dev_server)) =
+            self.client
+                .clone()
+                .zip(self.dev_server_project_id().and_then(|id| {
+                    dev_server_projects::Store::global(cx)
+                        .read(cx)
+                        .dev_server_for_project(id)
+                }))
+        {
             let request = proto::ListRemoteDirectory {
                 dev_server_id: dev_server.id.0,
                 path: query,
             };
-            let response = self.client.request(request);
+            let response = client.request(request);
             cx.background_executor().spawn(async move {
                 let response = response.await?;
                 Ok(response.entries.into_iter().map(PathBuf::from).collect())
@@ -7834,7 +8745,11 @@ impl Project {
         abs_path: impl AsRef<Path>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Worktree>, Arc<anyhow::Error>>> {
-        let client = self.client.clone();
+        let Some(client) = self.client.clone() else {
+            return Task::ready(Err(Arc::new(anyhow!(
+                "dev server project without a client"
+            ))));
+        };
         let path: Arc<Path> = abs_path.as_ref().into();
         let mut paths: Vec<String> = self
             .visible_worktrees(cx)
@@ -7888,8 +8803,8 @@ impl Project {
                     }
                 })
                 .collect();
-            if paths.len() > 0 {
-                let request = self.client.request(proto::UpdateDevServerProject {
+            if let Some(client) = self.client.as_ref().filter(|_| paths.len() > 0) {
+                let request = client.request(proto::UpdateDevServerProject {
                     dev_server_project_id: dev_server_project_id.0,
                     paths,
                 });
@@ -7921,6 +8836,9 @@ impl Project {
                 .remove(&server_id_to_remove);
             self.last_workspace_edits_by_language_server
                 .remove(&server_id_to_remove);
+            self.pulled_diagnostics.remove(&server_id_to_remove);
+            self.semantic_tokens_results
+                .retain(|(_, server_id), _| *server_id != server_id_to_remove);
             self.language_servers.remove(&server_id_to_remove);
             cx.emit(Event::LanguageServerRemoved(server_id_to_remove));
         }
@@ -7982,10 +8900,12 @@ impl Project {
                         changes.clone(),
                     ));
 
-                    let worktree_id = worktree.update(cx, |worktree, _| worktree.id());
-                    this.client()
-                        .telemetry()
-                        .report_discovered_project_events(worktree_id, changes);
+                    if let Some(client) = &this.client {
+                        let worktree_id = worktree.update(cx, |worktree, _| worktree.id());
+                        client
+                            .telemetry()
+                            .report_discovered_project_events(worktree_id, changes);
+                    }
                 }
                 worktree::Event::UpdatedGitRepositories(_) => {
                     cx.emit(Event::WorktreeUpdatedGitRepositories);
@@ -8072,7 +8992,7 @@ impl Project {
         if worktree.read(cx).is_remote() {
             return;
         }
-        let project_id = self.remote_id();
+        let collab_client = self.collab_client();
         let worktree_id = worktree.entity_id();
         let remote_worktree_id = worktree.read(cx).id();
 
@@ -8157,7 +9077,6 @@ impl Project {
             return;
         }
 
-        let client = self.client.clone();
         cx.spawn(move |_, cx| async move {
             let settings_contents: Vec<(Arc<Path>, _)> =
                 futures::future::join_all(settings_contents).await;
@@ -8173,10 +9092,10 @@ impl Project {
                                 cx,
                             )
                             .log_err();
-                        if let Some(remote_id) = project_id {
+                        if let Some((client, project_id)) = &collab_client {
                             client
                                 .send(proto::UpdateWorktreeSettings {
-                                    project_id: remote_id,
+                                    project_id: *project_id,
                                     worktree_id: remote_worktree_id.to_proto(),
                                     path: directory.to_string_lossy().into_owned(),
                                     content: file_content,
@@ -8414,6 +9333,90 @@ impl Project {
         worktree.get_local_repo(&root_entry)?.repo().clone().into()
     }
 
+    /// Returns the git repository containing the given project path, along with the path
+    /// relative to the repository's working directory.
+    pub fn repository_for_project_path(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Option<(Arc<dyn GitRepository>, RepoPath)> {
+        let worktree = self
+            .worktree_for_id(project_path.worktree_id, cx)?
+            .read(cx)
+            .as_local()?;
+        let (repo_entry, local_repo_entry) = worktree.repo_for_path(&project_path.path)?;
+        let repo_path = repo_entry
+            .relativize(worktree, &project_path.path)
+            .log_err()?;
+        Some((local_repo_entry.repo().clone(), repo_path))
+    }
+
+    /// Returns the git repository containing the active entry, or the first one at the root
+    /// of a visible worktree when there's no active entry or it isn't in a repository. The
+    /// repository is returned along with the project path of its working directory.
+    pub fn active_repository(
+        &self,
+        cx: &AppContext,
+    ) -> Option<(Arc<dyn GitRepository>, ProjectPath)> {
+        let active_path = self
+            .active_entry()
+            .and_then(|entry_id| self.path_for_entry(entry_id, cx));
+        let worktree_roots = self.visible_worktrees(cx).map(|worktree| ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: Path::new("").into(),
+        });
+        active_path
+            .into_iter()
+            .chain(worktree_roots)
+            .find_map(|project_path| {
+                let (repo, repo_path) = self.repository_for_project_path(&project_path, cx)?;
+                let work_directory = project_path
+                    .path
+                    .ancestors()
+                    .nth(repo_path.components().count())
+                    .unwrap_or(Path::new(""))
+                    .into();
+                Some((
+                    repo,
+                    ProjectPath {
+                        worktree_id: project_path.worktree_id,
+                        path: work_directory,
+                    },
+                ))
+            })
+    }
+
+    /// Returns the git revision that a buffer is diffed against, or `None` when it is diffed
+    /// against the index.
+    pub fn diff_base_revision(&self, buffer_id: BufferId, cx: &AppContext) -> Option<String> {
+        self.buffer_store.read(cx).diff_base_revision(buffer_id)
+    }
+
+    /// Diffs the project's local buffers against a git revision, such as a branch, a tag or a
+    /// commit SHA, or against the index when `revision` is `None`.
+    pub fn set_diff_base_revision(
+        &mut self,
+        revision: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.set_diff_base_revision(revision, cx)
+        })
+    }
+
+    /// Diffs a local buffer against a git revision, or against the project's diff base when
+    /// `revision` is `None`.
+    pub fn set_buffer_diff_base_revision(
+        &mut self,
+        buffer: Model<Buffer>,
+        revision: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.set_buffer_diff_base_revision(buffer, revision, cx)
+        })
+    }
+
     pub fn blame_buffer(
         &self,
         buffer: &Model<Buffer>,
@@ -8959,7 +9962,10 @@ impl Project {
                     });
 
                     let operations = buffer.serialize_ops(Some(remote_version), cx);
-                    let client = this.client.clone();
+                    let client = this
+                        .client
+                        .clone()
+                        .ok_or_else(|| anyhow!("project has no client"))?;
                     if let Some(file) = buffer.file() {
                         client
                             .send(proto::UpdateBufferFile {
@@ -9251,6 +10257,39 @@ impl Project {
         })
     }
 
+    async fn handle_semantic_tokens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        let tokens = this
+            .update(&mut cx, |project, cx| project.semantic_tokens(&buffer, cx))?
+            .await
+            .context("semantic tokens fetch")?;
+
+        this.update(&mut cx, |project, cx| {
+            GetSemanticTokens::response_to_proto(
+                tokens,
+                project,
+                sender_id,
+                &buffer.read(cx).version(),
+                cx,
+            )
+        })
+    }
+
     async fn handle_resolve_inlay_hint(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::ResolveInlayHint>,
@@ -9517,6 +10556,28 @@ impl Project {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_code_lens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshCodeLens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshCodeLens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_lsp_command<T: LspCommand>(
         this: Model<Self>,
         envelope: TypedEnvelope<T::ProtoRequest>,
@@ -9849,7 +10910,9 @@ impl Project {
             }
         };
 
-        let client = self.client.clone();
+        let Some(client) = self.client.clone() else {
+            return Task::ready(Err(anyhow!("remote project without a client")));
+        };
         cx.spawn(move |this, mut cx| async move {
             let (buffers, incomplete_buffer_ids) = this.update(&mut cx, |this, cx| {
                 this.buffer_store.read(cx).buffer_version_info(cx)
@@ -9936,11 +10999,14 @@ impl Project {
     ) -> Result<()> {
         self.metadata_changed(cx);
         self.worktree_store.update(cx, |worktree_store, cx| {
+            let (client, project_id) = self
+                .collab_client()
+                .ok_or_else(|| anyhow!("invalid project"))?;
             worktree_store.set_worktrees_from_proto(
                 worktrees,
                 self.replica_id(),
-                self.remote_id().ok_or_else(|| anyhow!("invalid project"))?,
-                self.client.clone().into(),
+                project_id,
+                client.into(),
                 cx,
             )
         })
@@ -10287,7 +11353,7 @@ impl Project {
         location: Location,
         cx: &mut ModelContext<'_, Project>,
     ) -> Task<Option<TaskContext>> {
-        if self.is_local() {
+        if self.is_local() && !self.is_ssh() {
             let (worktree_id, cwd) = if let Some(worktree) = self.task_worktree(cx) {
                 (
                     Some(worktree.read(cx).id()),
@@ -10327,11 +11393,8 @@ impl Project {
                     task_variables,
                 })
             })
-        } else if let Some(project_id) = self
-            .remote_id()
-            .filter(|_| self.ssh_connection_string(cx).is_some())
-        {
-            let task_context = self.client().request(proto::TaskContextForLocation {
+        } else if let Some((client, project_id)) = self.task_upstream_client(cx) {
+            let task_context = client.request(proto::TaskContextForLocation {
                 project_id,
                 location: Some(proto::Location {
                     buffer_id: location.buffer.read(cx).remote_id().into(),
@@ -10414,7 +11477,7 @@ impl Project {
         location: Option<Location>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<(TaskSourceKind, TaskTemplate)>>> {
-        if self.is_local() {
+        if self.is_local() && !self.is_ssh() {
             let (file, language) = location
                 .map(|location| {
                     let buffer = location.buffer.read(cx);
@@ -10428,28 +11491,39 @@ impl Project {
                 .task_inventory()
                 .read(cx)
                 .list_tasks(file, language, worktree, cx)))
-        } else if let Some(project_id) = self
-            .remote_id()
-            .filter(|_| self.ssh_connection_string(cx).is_some())
+        } else if let Some(remote_templates) =
+            self.query_remote_task_templates(worktree, location.as_ref(), cx)
         {
-            let remote_templates =
-                self.query_remote_task_templates(project_id, worktree, location.as_ref(), cx);
             cx.background_executor().spawn(remote_templates)
         } else {
             Task::ready(Ok(Vec::new()))
         }
     }
 
+    /// The client through which tasks are resolved when they have to run on another host:
+    /// either this project's SSH server, or the dev server behind an SSH connection string.
+    fn task_upstream_client(&self, cx: &AppContext) -> Option<(AnyProtoClient, u64)> {
+        if self.is_ssh() {
+            self.upstream_client()
+        } else {
+            let (client, project_id) = self
+                .collab_client()
+                .filter(|_| self.ssh_connection_string(cx).is_some())?;
+            Some((client.into(), project_id))
+        }
+    }
+
+    /// Queries the host that runs this project's tasks for their templates.
+    /// Returns `None` if the tasks of this project are resolved locally.
     pub fn query_remote_task_templates(
         &self,
-        project_id: u64,
         worktree: Option<WorktreeId>,
         location: Option<&Location>,
         cx: &AppContext,
-    ) -> Task<Result<Vec<(TaskSourceKind, TaskTemplate)>>> {
-        let client = self.client();
+    ) -> Option<Task<Result<Vec<(TaskSourceKind, TaskTemplate)>>>> {
+        let (client, project_id) = self.task_upstream_client(cx)?;
         let location = location.map(|location| serialize_location(location, cx));
-        cx.spawn(|_| async move {
+        Some(cx.spawn(|_| async move {
             let response = client
                 .request(proto::TaskTemplates {
                     project_id,
@@ -10528,7 +11602,7 @@ impl Project {
                     Some((task_source_kind, task_template))
                 })
                 .collect())
-        })
+        }))
     }
 
     fn task_worktree(&self, cx: &AppContext) -> Option<Model<Worktree>> {
@@ -11028,7 +12102,7 @@ impl ProjectLspAdapterDelegate {
             project: cx.weak_model(),
             worktree: worktree.read(cx).snapshot(),
             fs: project.fs.clone(),
-            http_client: project.client.http_client(),
+            http_client: project.http_client.clone(),
             language_registry: project.languages.clone(),
             shell_env: Default::default(),
             load_direnv,
@@ -11237,6 +12311,17 @@ fn include_text(server: &lsp::LanguageServer) -> Option<bool> {
     }
 }
 
+fn pull_diagnostic_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<lsp::DiagnosticOptions> {
+    match capabilities.diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options.clone()),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options.clone())
+        }
+    }
+}
+
 async fn load_direnv_environment(dir: &Path) -> Result<Option<HashMap<String, String>>> {
     let Ok(direnv_path) = which::which("direnv") else {
         return Ok(None);
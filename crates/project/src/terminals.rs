@@ -66,19 +66,99 @@ impl Project {
         }
     }
 
-    fn ssh_command(&self, cx: &AppContext) -> Option<SshCommand> {
+    /// Returns how to reach the host that runs this project's terminals, or `None` when
+    /// they run on this machine.
+    fn ssh_command(&self, cx: &AppContext) -> anyhow::Result<Option<SshCommand>> {
         if let Some(ssh_session) = self.ssh_session.as_ref() {
-            return Some(SshCommand::Direct(ssh_session.ssh_args()));
+            let ssh_args = ssh_session
+                .ssh_args()
+                .context("the SSH session has no connection to start terminals over")?;
+            return Ok(Some(SshCommand::Direct(ssh_args)));
         }
 
-        let dev_server_project_id = self.dev_server_project_id()?;
+        let Some(dev_server_project_id) = self.dev_server_project_id() else {
+            return Ok(None);
+        };
         let projects_store = dev_server_projects::Store::global(cx).read(cx);
         let ssh_command = projects_store
-            .dev_server_for_project(dev_server_project_id)?
-            .ssh_connection_string
-            .as_ref()?
-            .to_string();
-        Some(SshCommand::DevServer(ssh_command))
+            .dev_server_for_project(dev_server_project_id)
+            .and_then(|dev_server| dev_server.ssh_connection_string.as_ref())
+            .map(|ssh_command| SshCommand::DevServer(ssh_command.to_string()));
+        Ok(ssh_command)
+    }
+
+    /// Returns the directory a terminal starts in. For remote projects, this is a path on
+    /// the remote host, as are the paths of their worktrees.
+    fn terminal_directory(&self, kind: &TerminalKind, cx: &AppContext) -> Option<PathBuf> {
+        match kind {
+            TerminalKind::Shell(path) => path.clone(),
+            TerminalKind::Task(spawn_task) => spawn_task
+                .cwd
+                .clone()
+                .or_else(|| self.active_project_directory(cx)),
+        }
+    }
+
+    fn terminal_settings<'a>(
+        &self,
+        path: Option<&Path>,
+        cx: &'a AppContext,
+    ) -> &'a TerminalSettings {
+        let settings_location = path.and_then(|path| {
+            let (worktree, _) = self.find_worktree(path, cx)?;
+            Some(SettingsLocation {
+                worktree_id: worktree.read(cx).id().to_usize(),
+                path,
+            })
+        });
+        TerminalSettings::get(settings_location, cx)
+    }
+
+    /// Returns the program and arguments that start a terminal's shell, or run its task, on
+    /// the host of an SSH project, through the connection of its session. Returns `None`
+    /// for projects whose terminals run on this machine.
+    pub fn ssh_terminal_command(
+        &self,
+        kind: &TerminalKind,
+        cx: &AppContext,
+    ) -> anyhow::Result<Option<(String, Vec<String>)>> {
+        let Some(ssh_command) = self.ssh_command(cx)? else {
+            return Ok(None);
+        };
+        log::debug!("Connecting to a remote server: {ssh_command:?}");
+        let path = self.terminal_directory(kind, cx);
+        let settings = self.terminal_settings(path.as_deref(), cx);
+        let mut env = settings.env.clone();
+        // Alacritty sets its terminfo to `alacritty`, this requiring hosts to have it installed
+        // to properly display colors.
+        // We do not have the luxury of assuming the host has it installed,
+        // so we set it to a default that does not break the highlighting via ssh.
+        env.entry("TERM".to_string())
+            .or_insert_with(|| "xterm-256color".to_string());
+
+        let command = match kind {
+            TerminalKind::Shell(_) => wrap_for_ssh(&ssh_command, None, path.as_deref(), env, None),
+            TerminalKind::Task(spawn_task) => {
+                env.extend(spawn_task.env.clone());
+                let python_venv_directory = path
+                    .as_ref()
+                    .and_then(|path| self.python_venv_directory(path, settings, cx));
+                if let Some(venv_path) = &python_venv_directory {
+                    env.insert(
+                        "VIRTUAL_ENV".to_string(),
+                        venv_path.to_string_lossy().to_string(),
+                    );
+                }
+                wrap_for_ssh(
+                    &ssh_command,
+                    Some((&spawn_task.command, &spawn_task.args)),
+                    path.as_deref(),
+                    env,
+                    python_venv_directory,
+                )
+            }
+        };
+        Ok(Some(command))
     }
 
     pub fn create_terminal(
@@ -87,69 +167,27 @@ impl Project {
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
-        let path = match &kind {
-            TerminalKind::Shell(path) => path.as_ref().map(|path| path.to_path_buf()),
-            TerminalKind::Task(spawn_task) => {
-                if let Some(cwd) = &spawn_task.cwd {
-                    Some(cwd.clone())
-                } else {
-                    self.active_project_directory(cx)
-                }
-            }
-        };
-        let ssh_command = self.ssh_command(cx);
-
-        let mut settings_location = None;
-        if let Some(path) = path.as_ref() {
-            if let Some((worktree, _)) = self.find_worktree(path, cx) {
-                settings_location = Some(SettingsLocation {
-                    worktree_id: worktree.read(cx).id().to_usize(),
-                    path,
-                });
-            }
-        }
-        let settings = TerminalSettings::get(settings_location, cx);
+        let path = self.terminal_directory(&kind, cx);
+        let ssh_terminal_command = self.ssh_terminal_command(&kind, cx)?;
+        let settings = self.terminal_settings(path.as_deref(), cx);
 
         let (completion_tx, completion_rx) = bounded(1);
 
         let mut env = settings.env.clone();
 
-        let local_path = if ssh_command.is_none() {
-            path.clone()
-        } else {
-            None
-        };
         let python_venv_directory = path
             .as_ref()
             .and_then(|path| self.python_venv_directory(path, settings, cx));
         let mut python_venv_activate_command = None;
-
-        let (spawn_task, shell) = match kind {
-            TerminalKind::Shell(_) => {
-                if let Some(python_venv_directory) = python_venv_directory {
-                    python_venv_activate_command =
-                        self.python_activate_command(&python_venv_directory, settings);
-                }
-
-                match &ssh_command {
-                    Some(ssh_command) => {
-                        log::debug!("Connecting to a remote server: {ssh_command:?}");
-
-                        // Alacritty sets its terminfo to `alacritty`, this requiring hosts to have it installed
-                        // to properly display colors.
-                        // We do not have the luxury of assuming the host has it installed,
-                        // so we set it to a default that does not break the highlighting via ssh.
-                        env.entry("TERM".to_string())
-                            .or_insert_with(|| "xterm-256color".to_string());
-
-                        let (program, args) =
-                            wrap_for_ssh(ssh_command, None, path.as_deref(), env, None);
-                        env = HashMap::default();
-                        (None, Shell::WithArguments { program, args })
-                    }
-                    None => (None, settings.shell.clone()),
-                }
+        if let TerminalKind::Shell(_) = &kind {
+            if let Some(python_venv_directory) = &python_venv_directory {
+                python_venv_activate_command =
+                    self.python_activate_command(python_venv_directory, settings);
             }
+        }
+
+        let (spawn_task, local_shell) = match kind {
+            TerminalKind::Shell(_) => (None, settings.shell.clone()),
             TerminalKind::Task(spawn_task) => {
                 let task_state = Some(TaskState {
                     id: spawn_task.id,
@@ -168,40 +206,30 @@ impl Project {
                         "VIRTUAL_ENV".to_string(),
                         venv_path.to_string_lossy().to_string(),
                     );
+                    add_environment_path(&mut env, &venv_path.join("bin")).log_err();
                 }
 
-                match &ssh_command {
-                    Some(ssh_command) => {
-                        log::debug!("Connecting to a remote server: {ssh_command:?}");
-                        env.entry("TERM".to_string())
-                            .or_insert_with(|| "xterm-256color".to_string());
-                        let (program, args) = wrap_for_ssh(
-                            ssh_command,
-                            Some((&spawn_task.command, &spawn_task.args)),
-                            path.as_deref(),
-                            env,
-                            python_venv_directory,
-                        );
-                        env = HashMap::default();
-                        (task_state, Shell::WithArguments { program, args })
-                    }
-                    None => {
-                        if let Some(venv_path) = &python_venv_directory {
-                            add_environment_path(&mut env, &venv_path.join("bin")).log_err();
-                        }
-
-                        (
-                            task_state,
-                            Shell::WithArguments {
-                                program: spawn_task.command,
-                                args: spawn_task.args,
-                            },
-                        )
-                    }
-                }
+                (
+                    task_state,
+                    Shell::WithArguments {
+                        program: spawn_task.command,
+                        args: spawn_task.args,
+                    },
+                )
             }
         };
 
+        // The PTY of a remote terminal runs ssh on this machine, which starts the shell or
+        // the task in the remote directory, with the environment passed along in its command.
+        let (local_path, shell, env) = match ssh_terminal_command {
+            Some((program, args)) => (
+                None,
+                Shell::WithArguments { program, args },
+                HashMap::default(),
+            ),
+            None => (path, local_shell, env),
+        };
+
         let terminal = TerminalBuilder::new(
             local_path,
             spawn_task,
@@ -326,13 +354,17 @@ pub fn wrap_for_ssh(
         }
     }
     if let Some(venv_directory) = venv_directory {
-        if let Some(str) = shlex::try_quote(venv_directory.to_string_lossy().as_ref()).ok() {
+        let bin_directory = venv_directory.join("bin");
+        if let Some(str) = shlex::try_quote(bin_directory.to_string_lossy().as_ref()).ok() {
             env_changes.push_str(&format!("PATH={}:$PATH ", str));
         }
     }
 
+    // The path is quoted for the remote shell, which doesn't understand the escapes that
+    // `Debug` formatting would add.
+    let path = path.and_then(|path| shlex::try_quote(path.to_str()?).ok());
     let commands = if let Some(path) = path {
-        format!("cd {:?}; {} {}", path, env_changes, to_run)
+        format!("cd {}; {} {}", path, env_changes, to_run)
     } else {
         format!("cd; {env_changes} {to_run}")
     };
@@ -106,6 +106,9 @@ pub struct EntryDetails {
     is_processing: bool,
     is_cut: bool,
     git_status: Option<GitFileStatus>,
+    /// The branch of the repository whose work directory is this entry, for repositories
+    /// nested in the worktree, such as submodules.
+    branch: Option<Arc<str>>,
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Box<Path>>,
@@ -1871,6 +1874,16 @@ impl ProjectPanel {
                 });
                 for entry in visible_worktree_entries[entry_range].iter() {
                     let status = git_status_setting.then(|| entry.git_status).flatten();
+                    let branch = if git_status_setting
+                        && entry.is_dir()
+                        && !entry.path.as_os_str().is_empty()
+                    {
+                        snapshot
+                            .repository_for_work_directory(&entry.path)
+                            .and_then(|repository| repository.branch())
+                    } else {
+                        None
+                    };
                     let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
                     let icon = match entry.kind {
                         EntryKind::File(_) => {
@@ -1928,6 +1941,7 @@ impl ProjectPanel {
                             .as_ref()
                             .map_or(false, |e| e.is_cut() && e.items().contains(&selection)),
                         git_status: status,
+                        branch,
                         is_private: entry.is_private,
                         worktree_id: *worktree_id,
                         canonical_path: entry.canonical_path.clone(),
@@ -2010,6 +2024,7 @@ impl ProjectPanel {
         let filename_text_color =
             entry_git_aware_label_color(details.git_status, details.is_ignored, is_marked);
         let file_name = details.filename.clone();
+        let branch = details.branch.clone();
         let mut icon = details.icon.clone();
         if settings.file_icons && show_editor && details.kind.is_file() {
             let filename = self.filename_editor.read(cx).text(cx);
@@ -2132,11 +2147,22 @@ impl ProjectPanel {
                         if let (Some(editor), true) = (Some(&self.filename_editor), show_editor) {
                             h_flex().h_6().w_full().child(editor.clone())
                         } else {
-                            h_flex().h_6().child(
-                                Label::new(file_name)
-                                    .single_line()
-                                    .color(filename_text_color),
-                            )
+                            h_flex()
+                                .h_6()
+                                .gap_2()
+                                .child(
+                                    Label::new(file_name)
+                                        .single_line()
+                                        .color(filename_text_color),
+                                )
+                                .when_some(branch, |this, branch| {
+                                    this.child(
+                                        Label::new(branch)
+                                            .single_line()
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                })
                         }
                         .ml_1(),
                     )
@@ -406,6 +406,32 @@ messages!(
     (SynchronizeContextsResponse, Foreground),
     (AddWorktree, Foreground),
     (AddWorktreeResponse, Foreground),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
+    (RefreshCodeLens, Foreground),
+    (RefreshSemanticTokens, Foreground),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
 );
 
 request_messages!(
@@ -442,6 +468,20 @@ request_messages!(
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetCodeLens, GetCodeLensResponse),
+    (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
+    (ResolveCodeLens, ResolveCodeLensResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
@@ -553,6 +593,20 @@ entity_messages!(
     GetHover,
     GetProjectSymbols,
     GetReferences,
+    GetSemanticTokens,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetCodeLens,
+    RefreshCodeLens,
+    RefreshSemanticTokens,
+    ResolveCodeLens,
+    GetFoldingRanges,
+    GetDocumentLinks,
+    ResolveDocumentLink,
     GetSignatureHelp,
     GetTypeDefinition,
     InlayHints,
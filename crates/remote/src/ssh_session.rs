@@ -301,7 +301,15 @@ impl SshSession {
                     server_to_client_rx,
                     client_to_server_tx,
                     tx.clone(),
-                    None, // todo()
+                    Some(SshSocket {
+                        connection_options: SshConnectionOptions {
+                            host: "fake-host".to_string(),
+                            username: None,
+                            port: None,
+                            password: None,
+                        },
+                        socket_path: PathBuf::from("/fake-ssh-socket"),
+                    }),
                     cx,
                 )
             }),
@@ -423,8 +431,10 @@ impl SshSession {
         process_rx.await.unwrap()
     }
 
-    pub fn ssh_args(&self) -> Vec<String> {
-        self.client_socket.as_ref().unwrap().ssh_args()
+    /// Returns the arguments with which `ssh` runs a command on the host over this session's
+    /// connection, or `None` for sessions without one, such as the server's end.
+    pub fn ssh_args(&self) -> Option<Vec<String>> {
+        Some(self.client_socket.as_ref()?.ssh_args())
     }
 
     pub fn add_message_handler<M, E, H, F>(&self, entity: WeakModel<E>, handler: H)
@@ -457,7 +467,12 @@ impl SshSession {
             message_type_id,
             Arc::new(move |envelope, this, cx| {
                 let entity = entity.upgrade()?;
-                let envelope = envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+                let mut envelope = envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+                // Requests come straight from the other end of the session rather than being
+                // forwarded on behalf of another peer, so that end is their original sender.
+                if envelope.original_sender_id.is_none() {
+                    envelope.original_sender_id = Some(envelope.sender_id);
+                }
                 let request_id = envelope.message_id();
                 Some(
                     handler(entity, *envelope, cx)
@@ -1,24 +1,29 @@
 use anyhow::Result;
 use fs::Fs;
 use gpui::{AppContext, AsyncAppContext, Context, Model, ModelContext};
+use http_client::HttpClient;
+use language::{proto::serialize_anchor, LanguageRegistry};
+use node_runtime::NodeRuntime;
 use project::{
     buffer_store::{BufferStore, BufferStoreEvent},
+    search::SearchQuery,
     worktree_store::WorktreeStore,
-    ProjectPath, WorktreeId, WorktreeSettings,
+    Project, ProjectPath, SearchResult, WorktreeId,
 };
 use remote::SshSession;
 use rpc::{
     proto::{self, AnyProtoClient, PeerId},
     TypedEnvelope,
 };
-use settings::{Settings as _, SettingsStore};
+use settings::SettingsStore;
 use smol::stream::StreamExt;
 use std::{
+    collections::HashSet,
     path::{Path, PathBuf},
-    sync::{atomic::AtomicUsize, Arc},
+    sync::Arc,
 };
+use text::BufferId;
 use util::ResultExt as _;
-use worktree::Worktree;
 
 const PEER_ID: PeerId = PeerId { owner_id: 0, id: 0 };
 const PROJECT_ID: u64 = 0;
@@ -26,29 +31,43 @@ const PROJECT_ID: u64 = 0;
 pub struct HeadlessProject {
     pub fs: Arc<dyn Fs>,
     pub session: AnyProtoClient,
+    pub project: Model<Project>,
     pub worktree_store: Model<WorktreeStore>,
     pub buffer_store: Model<BufferStore>,
-    pub next_entry_id: Arc<AtomicUsize>,
 }
 
 impl HeadlessProject {
     pub fn init(cx: &mut AppContext) {
         cx.set_global(SettingsStore::new(cx));
-        WorktreeSettings::register(cx);
+        Project::init_settings(cx);
+        language::init(cx);
     }
 
-    pub fn new(session: Arc<SshSession>, fs: Arc<dyn Fs>, cx: &mut ModelContext<Self>) -> Self {
+    pub fn new(
+        session: Arc<SshSession>,
+        fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
+        node_runtime: Arc<dyn NodeRuntime>,
+        languages: Arc<LanguageRegistry>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
         let this = cx.weak_model();
 
-        let worktree_store = cx.new_model(|_| WorktreeStore::new(true));
-        let buffer_store =
-            cx.new_model(|cx| BufferStore::new(worktree_store.clone(), Some(PROJECT_ID), cx));
+        let project = Project::headless(http_client, node_runtime, languages, fs.clone(), cx);
+        let (worktree_store, buffer_store) = project.update(cx, |project, _| {
+            (project.worktree_store(), project.buffer_store())
+        });
+        buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.set_remote_id(Some(PROJECT_ID), cx)
+        });
         cx.subscribe(&buffer_store, Self::on_buffer_store_event)
             .detach();
+        cx.subscribe(&project, Self::on_project_event).detach();
 
         session.add_request_handler(this.clone(), Self::handle_list_remote_directory);
         session.add_request_handler(this.clone(), Self::handle_add_worktree);
         session.add_request_handler(this.clone(), Self::handle_open_buffer_by_path);
+        session.add_request_handler(this.clone(), Self::handle_search_project);
 
         session.add_request_handler(buffer_store.downgrade(), BufferStore::handle_blame_buffer);
         session.add_request_handler(buffer_store.downgrade(), BufferStore::handle_update_buffer);
@@ -75,12 +94,14 @@ impl HeadlessProject {
             WorktreeStore::handle_expand_project_entry,
         );
 
+        Project::add_ssh_request_handlers(&project, &session);
+
         HeadlessProject {
             session: session.into(),
             fs,
+            project,
             worktree_store,
             buffer_store,
-            next_entry_id: Default::default(),
         }
     }
 
@@ -90,23 +111,16 @@ impl HeadlessProject {
         mut cx: AsyncAppContext,
     ) -> Result<proto::AddWorktreeResponse> {
         let path = shellexpand::tilde(&message.payload.path).to_string();
-        let worktree = this
-            .update(&mut cx.clone(), |this, _| {
-                Worktree::local(
-                    Path::new(&path),
-                    true,
-                    this.fs.clone(),
-                    this.next_entry_id.clone(),
-                    &mut cx,
-                )
+        let (worktree, _) = this
+            .update(&mut cx, |this, cx| {
+                this.project.update(cx, |project, cx| {
+                    project.find_or_create_worktree(Path::new(&path), true, cx)
+                })
             })?
             .await?;
 
         this.update(&mut cx, |this, cx| {
             let session = this.session.clone();
-            this.worktree_store.update(cx, |worktree_store, cx| {
-                worktree_store.add(&worktree, cx);
-            });
             worktree.update(cx, |worktree, cx| {
                 worktree.observe_updates(0, cx, move |update| {
                     session.send(update).ok();
@@ -125,41 +139,87 @@ impl HeadlessProject {
         mut cx: AsyncAppContext,
     ) -> Result<proto::OpenBufferResponse> {
         let worktree_id = WorktreeId::from_proto(message.payload.worktree_id);
-        let (buffer_store, buffer, session) = this.update(&mut cx, |this, cx| {
-            let buffer_store = this.buffer_store.clone();
-            let buffer = this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store.open_buffer(
-                    ProjectPath {
-                        worktree_id,
-                        path: PathBuf::from(message.payload.path).into(),
-                    },
-                    cx,
-                )
-            });
-            anyhow::Ok((buffer_store, buffer, this.session.clone()))
-        })??;
+        let project_path = ProjectPath {
+            worktree_id,
+            path: PathBuf::from(message.payload.path).into(),
+        };
+        let (buffer, was_open) = this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                let was_open = buffer_store.get_by_path(&project_path, cx).is_some();
+                (buffer_store.open_buffer(project_path, cx), was_open)
+            })
+        })?;
 
         let buffer = buffer.await?;
         let buffer_id = buffer.read_with(&cx, |b, _| b.remote_id())?;
 
-        cx.spawn(|mut cx| async move {
-            BufferStore::create_buffer_for_peer(
-                buffer_store,
-                PEER_ID,
-                buffer_id,
-                PROJECT_ID,
-                session,
-                &mut cx,
-            )
-            .await
-        })
-        .detach();
+        // Newly opened buffers are sent to the client when they're added to the
+        // buffer store, but the client may have since released a buffer that
+        // was already open here.
+        if was_open {
+            this.update(&mut cx, |this, cx| {
+                this.send_buffer_to_client(buffer_id, cx)
+            })?;
+        }
 
         Ok(proto::OpenBufferResponse {
             buffer_id: buffer_id.to_proto(),
         })
     }
 
+    pub async fn handle_search_project(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SearchProject>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::SearchProjectResponse> {
+        let query = SearchQuery::from_proto(envelope.payload)?;
+        let (mut results, open_buffer_ids) = this.update(&mut cx, |this, cx| {
+            let open_buffer_ids = this
+                .buffer_store
+                .read(cx)
+                .buffers()
+                .map(|buffer| buffer.read(cx).remote_id())
+                .collect::<HashSet<_>>();
+            let results = this
+                .project
+                .update(cx, |project, cx| project.search(query, cx));
+            (results, open_buffer_ids)
+        })?;
+
+        let mut locations = Vec::new();
+        let mut limit_reached = false;
+        while let Some(result) = results.next().await {
+            match result {
+                SearchResult::Buffer { buffer, ranges } => {
+                    let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+
+                    // As when opening a buffer, the buffers opened by the search are
+                    // sent when they're added to the buffer store, but the client may
+                    // have since released a buffer that was already open here.
+                    if open_buffer_ids.contains(&buffer_id) {
+                        this.update(&mut cx, |this, cx| {
+                            this.send_buffer_to_client(buffer_id, cx)
+                        })?;
+                    }
+
+                    for range in ranges {
+                        locations.push(proto::Location {
+                            buffer_id: buffer_id.to_proto(),
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                        });
+                    }
+                }
+                SearchResult::LimitReached => limit_reached = true,
+            }
+        }
+
+        Ok(proto::SearchProjectResponse {
+            locations,
+            limit_reached,
+        })
+    }
+
     pub async fn handle_list_remote_directory(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::ListRemoteDirectory>,
@@ -182,9 +242,32 @@ impl HeadlessProject {
         &mut self,
         _: Model<BufferStore>,
         event: &BufferStoreEvent,
-        _: &mut ModelContext<Self>,
+        cx: &mut ModelContext<Self>,
     ) {
         match event {
+            BufferStoreEvent::BufferAdded(buffer) => {
+                // Buffers may be opened on this host without the client asking for
+                // them, such as when a language server returns a location in another
+                // file. Send all of them, so that responses can refer to them by id.
+                let buffer_id = buffer.read(cx).remote_id();
+                self.send_buffer_to_client(buffer_id, cx);
+
+                // Operations produced on this host, such as diagnostics reported by
+                // a language server, are replicated to the client.
+                let session = self.session.clone();
+                cx.subscribe(buffer, move |_, buffer, event, cx| {
+                    if let language::Event::Operation(operation) = event {
+                        session
+                            .send(proto::UpdateBuffer {
+                                project_id: PROJECT_ID,
+                                buffer_id: buffer.read(cx).remote_id().to_proto(),
+                                operations: vec![language::proto::serialize_operation(operation)],
+                            })
+                            .log_err();
+                    }
+                })
+                .detach();
+            }
             BufferStoreEvent::MessageToReplicas(message) => {
                 self.session
                     .send_dynamic(message.as_ref().clone())
@@ -193,4 +276,52 @@ impl HeadlessProject {
             _ => {}
         }
     }
+
+    pub fn on_project_event(
+        &mut self,
+        project: Model<Project>,
+        event: &project::Event,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match event {
+            project::Event::DiagnosticsUpdated {
+                language_server_id,
+                path,
+            } => {
+                let summary = project
+                    .read(cx)
+                    .diagnostic_summaries(true, cx)
+                    .find(|(summary_path, server_id, _)| {
+                        summary_path == path && server_id == language_server_id
+                    })
+                    .map(|(_, _, summary)| summary)
+                    .unwrap_or_default();
+                self.session
+                    .send(proto::UpdateDiagnosticSummary {
+                        project_id: PROJECT_ID,
+                        worktree_id: path.worktree_id.to_proto(),
+                        summary: Some(summary.to_proto(*language_server_id, &path.path)),
+                    })
+                    .log_err();
+            }
+            _ => {}
+        }
+    }
+
+    fn send_buffer_to_client(&self, buffer_id: BufferId, cx: &mut ModelContext<Self>) {
+        let buffer_store = self.buffer_store.clone();
+        let session = self.session.clone();
+        cx.spawn(|_, mut cx| async move {
+            BufferStore::create_buffer_for_peer(
+                buffer_store,
+                PEER_ID,
+                buffer_id,
+                PROJECT_ID,
+                session,
+                &mut cx,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
 }
@@ -2,7 +2,10 @@
 
 use fs::RealFs;
 use futures::channel::mpsc;
-use gpui::Context as _;
+use gpui::{Context as _, Task};
+use http_client::{HttpClient, HttpClientWithProxy};
+use language::LanguageRegistry;
+use node_runtime::RealNodeRuntime;
 use remote::{
     json_log::LogRecord,
     protocol::{read_message, write_message},
@@ -55,11 +58,22 @@ fn main() {
         let mut stdin = Async::new(io::stdin()).unwrap();
         let mut stdout = Async::new(io::stdout()).unwrap();
 
+        let http_client: Arc<dyn HttpClient> = Arc::new(HttpClientWithProxy::new(None, None));
+        let node_runtime = RealNodeRuntime::new(http_client.clone());
+        let mut languages =
+            LanguageRegistry::new(Task::ready(()), cx.background_executor().clone());
+        languages.set_language_server_download_dir(paths::languages_dir().clone());
+        let languages = Arc::new(languages);
+        languages::init(languages.clone(), node_runtime.clone(), cx);
+
         let session = SshSession::server(incoming_rx, outgoing_tx, cx);
         let project = cx.new_model(|cx| {
             HeadlessProject::new(
                 session.clone(),
                 Arc::new(RealFs::new(Default::default(), None)),
+                http_client,
+                node_runtime,
+                languages,
                 cx,
             )
         });
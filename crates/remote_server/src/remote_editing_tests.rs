@@ -1,16 +1,30 @@
 use crate::headless_project::HeadlessProject;
 use client::{Client, UserStore};
 use clock::FakeSystemClock;
+use collections::HashMap;
 use fs::{FakeFs, Fs as _};
+use futures::StreamExt as _;
 use gpui::{Context, Model, TestAppContext};
 use http_client::FakeHttpClient;
-use language::LanguageRegistry;
+use language::{
+    DiagnosticSeverity, FakeLspAdapter, Language, LanguageConfig, LanguageMatcher,
+    LanguageRegistry, Point, ToOffset as _,
+};
+use lsp::Url;
 use node_runtime::FakeNodeRuntime;
-use project::Project;
+use project::{
+    search::SearchQuery, terminals::TerminalKind, Location, Project, SearchResult, TaskSourceKind,
+};
 use remote::SshSession;
 use serde_json::json;
 use settings::SettingsStore;
-use std::{path::Path, sync::Arc};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use task::{TaskVariables, VariableName};
+use util::paths::PathMatcher;
 
 fn init_logger() {
     if std::env::var("RUST_LOG").is_ok() {
@@ -45,9 +59,8 @@ async fn test_remote_editing(cx: &mut TestAppContext, server_cx: &mut TestAppCon
         &[(Path::new("src/lib.rs"), "fn one() -> usize { 0 }".into())],
     );
 
-    server_cx.update(HeadlessProject::init);
-    let _headless_project =
-        server_cx.new_model(|cx| HeadlessProject::new(server_ssh, fs.clone(), cx));
+    let languages = Arc::new(LanguageRegistry::test(server_cx.executor()));
+    let _headless_project = build_headless_project(server_ssh, fs.clone(), languages, server_cx);
 
     let project = build_project(client_ssh, cx);
     let (worktree, _) = project
@@ -150,6 +163,504 @@ async fn test_remote_editing(cx: &mut TestAppContext, server_cx: &mut TestAppCon
     });
 }
 
+#[gpui::test]
+async fn test_remote_language_server(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (client_ssh, server_ssh) = SshSession::fake(cx, server_cx);
+    init_logger();
+
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }\nfn two() -> usize { one() + one() }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let languages = Arc::new(LanguageRegistry::test(server_cx.executor()));
+    languages.add(rust_lang());
+    let mut fake_servers = languages.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-language-server",
+            capabilities: lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let _headless_project = build_headless_project(server_ssh, fs.clone(), languages, server_cx);
+
+    let project = build_project(client_ssh, cx);
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+
+    // Opening a buffer on the client starts the language server on the remote host.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // Definitions are resolved by the remote language server.
+    fake_server.handle_request::<lsp::request::GotoDefinition, _, _>(|params, _| async move {
+        let params = params.text_document_position_params;
+        assert_eq!(
+            params.text_document.uri.to_file_path().unwrap(),
+            Path::new("/code/project1/src/lib.rs"),
+        );
+        assert_eq!(params.position, lsp::Position::new(1, 20));
+        Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+            lsp::Location::new(
+                Url::from_file_path("/code/project1/src/lib.rs").unwrap(),
+                lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+            ),
+        )))
+    });
+    let definitions = project
+        .update(cx, |project, cx| {
+            project.definition(&buffer, Point::new(1, 20), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(definitions.len(), 1);
+    cx.update(|cx| {
+        let target_buffer = definitions[0].target.buffer.read(cx);
+        assert_eq!(
+            &**target_buffer.file().unwrap().path(),
+            Path::new("src/lib.rs")
+        );
+        assert_eq!(
+            definitions[0].target.range.to_point(target_buffer),
+            Point::new(0, 3)..Point::new(0, 6)
+        );
+    });
+
+    // Hovers are forwarded to the remote language server.
+    fake_server.handle_request::<lsp::request::HoverRequest, _, _>(|_, _| async move {
+        Ok(Some(lsp::Hover {
+            contents: lsp::HoverContents::Scalar(lsp::MarkedString::String(
+                "fn one() -> usize".to_string(),
+            )),
+            range: None,
+        }))
+    });
+    let hovers = project
+        .update(cx, |project, cx| {
+            project.hover(&buffer, Point::new(1, 20), cx)
+        })
+        .await;
+    assert_eq!(
+        hovers
+            .iter()
+            .flat_map(|hover| hover.contents.iter().map(|block| block.text.as_str()))
+            .collect::<Vec<_>>(),
+        vec!["fn one() -> usize"]
+    );
+
+    // Diagnostics published by the remote language server reach the client's buffer.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path("/code/project1/src/lib.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 20), lsp::Position::new(0, 21)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "expected usize".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| {
+                (
+                    entry.range,
+                    entry.diagnostic.severity,
+                    entry.diagnostic.message,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![(
+                Point::new(0, 20)..Point::new(0, 21),
+                DiagnosticSeverity::ERROR,
+                "expected usize".to_string()
+            )]
+        );
+    });
+    project.update(cx, |project, cx| {
+        assert_eq!(project.diagnostic_summary(false, cx).error_count, 1);
+    });
+}
+
+#[gpui::test]
+async fn test_remote_project_search(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (client_ssh, server_ssh) = SshSession::fake(cx, server_cx);
+    init_logger();
+
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                ".gitignore": "target",
+                "README.md": "# project 1, the one() project",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                    "main.rs": "fn main() { one(); }",
+                },
+                "target": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                },
+            },
+        }),
+    )
+    .await;
+
+    let languages = Arc::new(LanguageRegistry::test(server_cx.executor()));
+    let _headless_project = build_headless_project(server_ssh, fs.clone(), languages, server_cx);
+
+    let project = build_project(client_ssh, cx);
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let query = SearchQuery::text(
+        "one()",
+        false,
+        true,
+        false,
+        PathMatcher::new(&["*.rs".to_owned()]).unwrap(),
+        PathMatcher::new(&["src/main.rs".to_owned()]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await,
+        HashMap::from_iter([("project1/src/lib.rs".to_string(), vec![3..8])])
+    );
+
+    let query = SearchQuery::text(
+        "one()",
+        false,
+        true,
+        true,
+        PathMatcher::new(&["*.rs".to_owned()]).unwrap(),
+        PathMatcher::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await,
+        HashMap::from_iter([
+            ("project1/src/lib.rs".to_string(), vec![3..8]),
+            ("project1/src/main.rs".to_string(), vec![12..17]),
+            ("project1/target/lib.rs".to_string(), vec![3..8]),
+        ])
+    );
+
+    // The results are sent again after the client released the buffers of the previous
+    // search, even though they're still open on the server.
+    cx.run_until_parked();
+    project.read_with(cx, |project, cx| {
+        assert!(project.opened_buffers(cx).is_empty());
+    });
+    let query = SearchQuery::text(
+        "one()",
+        false,
+        true,
+        true,
+        PathMatcher::new(&["*.rs".to_owned()]).unwrap(),
+        PathMatcher::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await,
+        HashMap::from_iter([
+            ("project1/src/lib.rs".to_string(), vec![3..8]),
+            ("project1/src/main.rs".to_string(), vec![12..17]),
+            ("project1/target/lib.rs".to_string(), vec![3..8]),
+        ])
+    );
+
+    // Replacing a match edits the buffer that was shared by the remote search.
+    let mut search_rx = project.update(cx, |project, cx| {
+        project.search(
+            SearchQuery::text(
+                "the one()",
+                false,
+                true,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+            )
+            .unwrap(),
+            cx,
+        )
+    });
+    let Some(SearchResult::Buffer { buffer, ranges }) = search_rx.next().await else {
+        panic!("expected a search result");
+    };
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(
+            ranges.into_iter().map(|range| (range, "the two()")),
+            None,
+            cx,
+        );
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load("/code/project1/README.md".as_ref()).await.unwrap(),
+        "# project 1, the two() project"
+    );
+}
+
+#[gpui::test]
+async fn test_remote_task_templates(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (client_ssh, server_ssh) = SshSession::fake(cx, server_cx);
+    init_logger();
+
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".zed": {
+                    "tasks.json": r#"[{
+                        "label": "cargo check",
+                        "command": "cargo",
+                        "args": ["check", "--all"],
+                        "cwd": "$ZED_WORKTREE_ROOT"
+                    }]"#,
+                },
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let languages = Arc::new(LanguageRegistry::test(server_cx.executor()));
+    let _headless_project = build_headless_project(server_ssh, fs.clone(), languages, server_cx);
+
+    let project = build_project(client_ssh, cx);
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+
+    // Task templates are read from the tasks file on the remote host.
+    let templates = project
+        .update(cx, |project, cx| {
+            project.task_templates(Some(worktree_id), None, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(templates.len(), 1);
+    let (source_kind, template) = &templates[0];
+    assert_eq!(
+        source_kind,
+        &TaskSourceKind::Worktree {
+            id: worktree_id,
+            abs_path: PathBuf::from("/code/project1/.zed/tasks.json"),
+            id_base: "local_tasks_for_worktree".into(),
+        }
+    );
+    assert_eq!(template.label, "cargo check");
+    assert_eq!(template.command, "cargo");
+    assert_eq!(template.args, vec!["check", "--all"]);
+    assert_eq!(template.cwd.as_deref(), Some("$ZED_WORKTREE_ROOT"));
+}
+
+#[gpui::test]
+async fn test_remote_terminal_command(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let (client_ssh, server_ssh) = SshSession::fake(cx, server_cx);
+    init_logger();
+    // The server loads the shell environment of the worktree for the task context.
+    cx.executor().allow_parking();
+
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".zed": {
+                    "tasks.json": r#"[{
+                        "label": "cargo check",
+                        "command": "cargo",
+                        "args": ["check"],
+                        "cwd": "$ZED_WORKTREE_ROOT/src"
+                    }]"#,
+                },
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let languages = Arc::new(LanguageRegistry::test(server_cx.executor()));
+    let _headless_project = build_headless_project(server_ssh, fs.clone(), languages, server_cx);
+
+    let project = build_project(client_ssh, cx);
+    cx.update(terminal::init);
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    // The task is resolved against the paths of the remote host.
+    let anchor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(0));
+    let location = Location {
+        buffer,
+        range: anchor..anchor,
+    };
+    let task_context = project
+        .update(cx, |project, cx| {
+            project.task_context_for_location(TaskVariables::default(), location, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        task_context.task_variables.get(&VariableName::WorktreeRoot),
+        Some("/code/project1")
+    );
+    let templates = project
+        .update(cx, |project, cx| {
+            project.task_templates(Some(worktree_id), None, cx)
+        })
+        .await
+        .unwrap();
+    let spawn_task = templates[0]
+        .1
+        .resolve_task("test", &task_context)
+        .and_then(|task| task.resolved)
+        .unwrap();
+    assert_eq!(spawn_task.cwd, Some(PathBuf::from("/code/project1/src")));
+
+    // Both the task and the shell of a terminal start in their remote directory, over the
+    // connection of the session.
+    let (program, args) = project
+        .read_with(cx, |project, cx| {
+            project.ssh_terminal_command(&TerminalKind::Task(spawn_task), cx)
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(program, "ssh");
+    assert!(args.contains(&"ssh://fake-host".to_string()));
+    let remote_command = args.last().unwrap();
+    assert!(
+        remote_command.contains("cd /code/project1/src;")
+            && remote_command.contains(" cargo check"),
+        "unexpected command: {remote_command}"
+    );
+
+    let (_, args) = project
+        .read_with(cx, |project, cx| {
+            let directory = project.active_project_directory(cx);
+            project.ssh_terminal_command(&TerminalKind::Shell(directory), cx)
+        })
+        .unwrap()
+        .unwrap();
+    let remote_command = args.last().unwrap();
+    assert!(
+        remote_command.contains("cd /code/project1;")
+            && remote_command.contains("exec ${SHELL:-sh} -l"),
+        "unexpected command: {remote_command}"
+    );
+}
+
+async fn search(
+    project: &Model<Project>,
+    query: SearchQuery,
+    cx: &mut TestAppContext,
+) -> HashMap<String, Vec<Range<usize>>> {
+    let mut search_rx = project.update(cx, |project, cx| project.search(query, cx));
+    let mut results = HashMap::default();
+    while let Some(search_result) = search_rx.next().await {
+        if let SearchResult::Buffer { buffer, ranges } = search_result {
+            buffer.update(cx, |buffer, cx| {
+                let path = buffer
+                    .file()
+                    .unwrap()
+                    .full_path(cx)
+                    .to_string_lossy()
+                    .to_string();
+                let ranges = ranges
+                    .into_iter()
+                    .map(|range| range.to_offset(buffer))
+                    .collect::<Vec<_>>();
+                results.insert(path, ranges);
+            });
+        }
+    }
+    results
+}
+
+fn build_headless_project(
+    ssh: Arc<SshSession>,
+    fs: Arc<FakeFs>,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut TestAppContext,
+) -> Model<HeadlessProject> {
+    cx.update(HeadlessProject::init);
+    let http_client = FakeHttpClient::with_404_response();
+    let node = FakeNodeRuntime::new();
+    cx.new_model(|cx| HeadlessProject::new(ssh, fs, http_client, node, languages, cx))
+}
+
+fn rust_lang() -> Arc<Language> {
+    Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    ))
+}
+
 fn build_project(ssh: Arc<SshSession>, cx: &mut TestAppContext) -> Model<Project> {
     cx.update(|cx| {
         let settings_store = SettingsStore::test(cx);
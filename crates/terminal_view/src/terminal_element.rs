@@ -1013,8 +1013,9 @@ impl InputHandler for TerminalInputHandler {
 
         self.workspace
             .update(cx, |this, cx| {
-                let telemetry = this.project().read(cx).client().telemetry().clone();
-                telemetry.log_edit_event("terminal");
+                if let Some(client) = this.project().read(cx).client() {
+                    client.telemetry().log_edit_event("terminal");
+                }
             })
             .ok();
     }
@@ -102,6 +102,8 @@ pub(crate) fn one_dark() -> Theme {
                     0.2,
                 ),
                 editor_document_highlight_write_background: gpui::red(),
+                editor_diff_word_added_background: hsla(95. / 360., 38. / 100., 62. / 100., 0.3),
+                editor_diff_word_deleted_background: hsla(355. / 360., 65. / 100., 65. / 100., 0.3),
 
                 terminal_background: bg,
                 // todo("Use one colors for terminal")
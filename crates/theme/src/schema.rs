@@ -417,6 +417,16 @@ pub struct ThemeColorsContent {
     #[serde(rename = "editor.document_highlight.write_background")]
     pub editor_document_highlight_write_background: Option<String>,
 
+    /// Highlighted background color for the words that were added within a modified diff
+    /// hunk, on top of the hunk's own background.
+    #[serde(rename = "editor.diff_word_added.background")]
+    pub editor_diff_word_added_background: Option<String>,
+
+    /// Highlighted background color for the words that were deleted within a modified diff
+    /// hunk, on top of the hunk's own background.
+    #[serde(rename = "editor.diff_word_deleted.background")]
+    pub editor_diff_word_deleted_background: Option<String>,
+
     /// Terminal background color.
     #[serde(rename = "terminal.background")]
     pub terminal_background: Option<String>,
@@ -792,6 +802,14 @@ impl ThemeColorsContent {
                 .editor_document_highlight_write_background
                 .as_ref()
                 .and_then(|color| try_parse_color(color).ok()),
+            editor_diff_word_added_background: self
+                .editor_diff_word_added_background
+                .as_ref()
+                .and_then(|color| try_parse_color(color).ok()),
+            editor_diff_word_deleted_background: self
+                .editor_diff_word_deleted_background
+                .as_ref()
+                .and_then(|color| try_parse_color(color).ok()),
             terminal_background: self
                 .terminal_background
                 .as_ref()
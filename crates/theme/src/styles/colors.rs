@@ -171,6 +171,12 @@ pub struct ThemeColors {
     /// special attention. Usually a document highlight is visualized by changing
     /// the background color of its range.
     pub editor_document_highlight_write_background: Hsla,
+    /// Highlighted background color for the words that were added within a modified diff
+    /// hunk, on top of the hunk's own background.
+    pub editor_diff_word_added_background: Hsla,
+    /// Highlighted background color for the words that were deleted within a modified diff
+    /// hunk, on top of the hunk's own background.
+    pub editor_diff_word_deleted_background: Hsla,
 
     // ===
     // Terminal
@@ -1,6 +1,46 @@
 use std::sync::Arc;
 
-use gpui::{HighlightStyle, Hsla};
+use gpui::{px, HighlightStyle, Hsla, StrikethroughStyle, UnderlineStyle};
+
+/// The syntax highlights that can style each semantic token type, in order of preference.
+///
+/// Besides the standard LSP token types, this covers custom types of common language servers.
+/// Token types not listed here are styled by the syntax highlight of the same name, if any.
+const SEMANTIC_TOKEN_HIGHLIGHTS: &[(&str, &[&str])] = &[
+    ("namespace", &["namespace", "module"]),
+    ("type", &["type"]),
+    ("class", &["type"]),
+    ("enum", &["enum", "type"]),
+    ("interface", &["type"]),
+    ("struct", &["type"]),
+    ("typeParameter", &["type"]),
+    ("parameter", &["variable.parameter", "variable"]),
+    ("variable", &["variable"]),
+    ("property", &["property"]),
+    ("enumMember", &["variant", "constant"]),
+    ("event", &["property"]),
+    ("function", &["function"]),
+    ("method", &["function.method", "function"]),
+    ("macro", &["function.macro", "function.special", "function"]),
+    ("keyword", &["keyword"]),
+    ("modifier", &["keyword"]),
+    ("comment", &["comment"]),
+    ("string", &["string"]),
+    ("number", &["number"]),
+    ("regexp", &["string.regex", "string"]),
+    ("operator", &["operator"]),
+    ("decorator", &["attribute"]),
+    ("builtinType", &["type.builtin", "type"]),
+    ("typeAlias", &["type"]),
+    ("union", &["type"]),
+    ("concept", &["type"]),
+    ("selfKeyword", &["variable.special", "keyword"]),
+    ("selfTypeKeyword", &["type"]),
+    ("lifetime", &["lifetime", "label"]),
+    ("builtinAttribute", &["attribute"]),
+    ("escapeSequence", &["string.escape"]),
+    ("formatSpecifier", &["string.special"]),
+];
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct SyntaxTheme {
@@ -49,6 +89,57 @@ impl SyntaxTheme {
         Some(ix as u32)
     }
 
+    /// Returns the style for a semantic token of the given type and modifiers, as named by a
+    /// language server's legend, or `None` if this theme has nothing to highlight it with.
+    pub fn semantic_token_style(
+        &self,
+        token_type: &str,
+        modifiers: &[impl AsRef<str>],
+    ) -> Option<HighlightStyle> {
+        let mut style = if token_type == "unresolvedReference" {
+            Some(HighlightStyle {
+                underline: Some(UnderlineStyle {
+                    thickness: px(1.),
+                    color: None,
+                    wavy: true,
+                }),
+                ..Default::default()
+            })
+        } else {
+            let same_name = [token_type];
+            let highlight_names = SEMANTIC_TOKEN_HIGHLIGHTS
+                .iter()
+                .find(|(name, _)| *name == token_type)
+                .map_or(&same_name[..], |(_, highlight_names)| *highlight_names);
+            highlight_names.iter().find_map(|name| {
+                let ix = self.highlight_id(name)?;
+                Some(self.highlights[ix as usize].1)
+            })
+        };
+
+        for modifier in modifiers {
+            match modifier.as_ref() {
+                "deprecated" => {
+                    style.get_or_insert_with(Default::default).strikethrough =
+                        Some(StrikethroughStyle {
+                            thickness: px(1.),
+                            color: None,
+                        });
+                }
+                "mutable" => {
+                    style.get_or_insert_with(Default::default).underline = Some(UnderlineStyle {
+                        thickness: px(1.),
+                        color: None,
+                        wavy: false,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        style
+    }
+
     /// Returns a new [`Arc<SyntaxTheme>`] with the given syntax styles merged in.
     pub fn merge(base: Arc<Self>, user_syntax_styles: Vec<(String, HighlightStyle)>) -> Arc<Self> {
         if user_syntax_styles.is_empty() {
@@ -192,4 +283,55 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn test_semantic_token_style() {
+        let syntax_theme = SyntaxTheme::new_test([
+            ("type", gpui::red()),
+            ("variable", gpui::green()),
+            ("function", gpui::blue()),
+            ("lifetime", gpui::yellow()),
+        ]);
+
+        // Token types map to the most specific highlight the theme has.
+        assert_eq!(
+            syntax_theme
+                .semantic_token_style("struct", &[] as &[&str])
+                .and_then(|style| style.color),
+            Some(gpui::red())
+        );
+        assert_eq!(
+            syntax_theme
+                .semantic_token_style("method", &[] as &[&str])
+                .and_then(|style| style.color),
+            Some(gpui::blue())
+        );
+        assert_eq!(
+            syntax_theme
+                .semantic_token_style("lifetime", &[] as &[&str])
+                .and_then(|style| style.color),
+            Some(gpui::yellow())
+        );
+        assert_eq!(
+            syntax_theme.semantic_token_style("namespace", &[] as &[&str]),
+            None
+        );
+
+        // Modifiers are layered over the token type's highlight.
+        let style = syntax_theme
+            .semantic_token_style("variable", &["mutable"])
+            .unwrap();
+        assert_eq!(style.color, Some(gpui::green()));
+        assert!(style.underline.is_some());
+        let style = syntax_theme
+            .semantic_token_style("namespace", &["deprecated"])
+            .unwrap();
+        assert_eq!(style.color, None);
+        assert!(style.strikethrough.is_some());
+
+        // Unresolved references are underlined even without a matching highlight.
+        assert!(syntax_theme
+            .semantic_token_style("unresolvedReference", &[] as &[&str])
+            .is_some_and(|style| style.underline.is_some_and(|underline| underline.wavy)));
+    }
 }
@@ -385,24 +385,44 @@ impl TitleBar {
             .as_ref()
             .and_then(RepositoryEntry::branch)
             .map(|branch| util::truncate_and_trailoff(&branch, MAX_BRANCH_NAME_LENGTH))?;
-        Some(
-            Button::new("project_branch_trigger", branch_name)
-                .color(Color::Muted)
-                .style(ButtonStyle::Subtle)
-                .label_size(LabelSize::Small)
-                .tooltip(move |cx| {
-                    Tooltip::with_meta(
-                        "Recent Branches",
-                        Some(&ToggleVcsMenu),
-                        "Local branches only",
-                        cx,
+        let upstream_status = entry
+            .as_ref()
+            .and_then(RepositoryEntry::upstream_status)
+            .unwrap_or_default();
+        let render_count = |icon: IconName, count: usize| {
+            (count > 0).then(|| {
+                h_flex()
+                    .child(Icon::new(icon).size(IconSize::XSmall).color(Color::Muted))
+                    .child(
+                        Label::new(count.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
                     )
-                })
-                .on_click(move |_, cx| {
-                    let _ = workspace.update(cx, |this, cx| {
-                        BranchList::open(this, &Default::default(), cx)
-                    });
-                }),
+            })
+        };
+        let branch_button = Button::new("project_branch_trigger", branch_name)
+            .color(Color::Muted)
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .tooltip(move |cx| {
+                Tooltip::with_meta(
+                    "Recent Branches",
+                    Some(&ToggleVcsMenu),
+                    "Local branches only",
+                    cx,
+                )
+            })
+            .on_click(move |_, cx| {
+                let _ = workspace.update(cx, |this, cx| {
+                    BranchList::open(this, &Default::default(), cx)
+                });
+            });
+        Some(
+            h_flex()
+                .gap_1()
+                .child(branch_button)
+                .children(render_count(IconName::ArrowUp, upstream_status.ahead))
+                .children(render_count(IconName::ArrowDown, upstream_status.behind)),
         )
     }
 
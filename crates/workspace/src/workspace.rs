@@ -691,6 +691,14 @@ type PromptForOpenPath = Box<
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>>,
 >;
 
+type PromptForCredential = Box<
+    dyn Fn(
+        &mut Workspace,
+        String,
+        &mut ViewContext<Workspace>,
+    ) -> oneshot::Receiver<Option<String>>,
+>;
+
 /// Collects everything project-related for a certain window opened.
 /// In some way, is a counterpart of a window, as the [`WindowHandle`] could be downcast into `Workspace`.
 ///
@@ -734,6 +742,7 @@ pub struct Workspace {
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
+    on_prompt_for_credential: Option<PromptForCredential>,
     render_disconnected_overlay:
         Option<Box<dyn Fn(&mut Self, &mut ViewContext<Self>) -> AnyElement>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
@@ -1031,6 +1040,7 @@ impl Workspace {
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
+            on_prompt_for_credential: None,
             render_disconnected_overlay: None,
             serializable_items_tx,
             _items_serializer,
@@ -1419,6 +1429,10 @@ impl Workspace {
         self.on_prompt_for_open_path = Some(prompt)
     }
 
+    pub fn set_prompt_for_credential(&mut self, prompt: PromptForCredential) {
+        self.on_prompt_for_credential = Some(prompt)
+    }
+
     pub fn set_render_disconnected_overlay(
         &mut self,
         render: impl Fn(&mut Self, &mut ViewContext<Self>) -> AnyElement + 'static,
@@ -1471,6 +1485,24 @@ impl Workspace {
         }
     }
 
+    /// Asks the user to answer `prompt` with a credential, such as a password or a
+    /// passphrase. The answer is `None` when the prompt is dismissed, or when there's no way
+    /// to prompt for credentials.
+    pub fn prompt_for_credential(
+        &mut self,
+        prompt: String,
+        cx: &mut ViewContext<Self>,
+    ) -> oneshot::Receiver<Option<String>> {
+        let Some(on_prompt) = self.on_prompt_for_credential.take() else {
+            let (tx, rx) = oneshot::channel();
+            tx.send(None).ok();
+            return rx;
+        };
+        let rx = on_prompt(self, prompt, cx);
+        self.on_prompt_for_credential = Some(on_prompt);
+        rx
+    }
+
     pub fn prompt_for_new_path(
         &mut self,
         cx: &mut ViewContext<Self>,
@@ -4289,8 +4321,8 @@ impl Workspace {
         use node_runtime::FakeNodeRuntime;
         use session::Session;
 
-        let client = project.read(cx).client();
-        let user_store = project.read(cx).user_store();
+        let client = project.read(cx).client().unwrap();
+        let user_store = project.read(cx).user_store().unwrap();
 
         let workspace_store = cx.new_model(|cx| WorkspaceStore::new(client.clone(), cx));
         let session = cx.new_model(|cx| AppSession::new(Session::test(), cx));
@@ -20,6 +20,7 @@ use futures::{
 };
 use fuzzy::CharBag;
 use git::{
+    remote::UpstreamStatus,
     repository::{GitFileStatus, GitRepository, RepoPath},
     status::GitStatus,
     DOT_GIT, GITIGNORE,
@@ -166,6 +167,9 @@ pub struct Snapshot {
 pub struct RepositoryEntry {
     pub(crate) work_directory: WorkDirectoryEntry,
     pub(crate) branch: Option<Arc<str>>,
+    /// How far the branch has diverged from its upstream. This isn't synced with peers,
+    /// so it's only known for local worktrees.
+    pub(crate) upstream_status: Option<UpstreamStatus>,
 
     /// If location_in_repo is set, it means the .git folder is external
     /// and in a parent folder of the project root.
@@ -193,6 +197,10 @@ impl RepositoryEntry {
         self.branch.clone()
     }
 
+    pub fn upstream_status(&self) -> Option<UpstreamStatus> {
+        self.upstream_status
+    }
+
     pub fn work_directory_id(&self) -> ProjectEntryId {
         *self.work_directory
     }
@@ -302,15 +310,23 @@ struct BackgroundScannerState {
     removed_entry_ids: HashMap<(u64, SystemTime), ProjectEntryId>,
     changed_paths: Vec<Arc<Path>>,
     prev_snapshot: Snapshot,
+    /// The directories outside of the worktree's `.git` entries that hold the metadata of its
+    /// repositories, which have been watched for changes.
+    watched_git_dirs: HashSet<PathBuf>,
+    /// The directories added to `watched_git_dirs` that the scanner doesn't watch yet.
+    git_dirs_to_watch: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LocalRepositoryEntry {
     pub(crate) git_dir_scan_id: usize,
     pub(crate) repo_ptr: Arc<dyn GitRepository>,
-    /// Path to the actual .git folder.
-    /// Note: if .git is a file, this points to the folder indicated by the .git file
+    /// Path to the .git entry of the repository.
+    /// Note: in linked worktrees and submodules, .git is a file pointing to the folder
+    /// holding the repository's metadata, which is listed in `linked_git_dirs`.
     pub(crate) git_dir_path: Arc<Path>,
+    /// Absolute paths of the folders holding the repository's metadata when .git is a file.
+    pub(crate) linked_git_dirs: Arc<[PathBuf]>,
 }
 
 impl LocalRepositoryEntry {
@@ -1003,6 +1019,8 @@ impl LocalWorktree {
                         paths_to_scan: Default::default(),
                         removed_entry_ids: Default::default(),
                         changed_paths: Default::default(),
+                        watched_git_dirs: Default::default(),
+                        git_dirs_to_watch: Default::default(),
                     }),
                     phase: BackgroundScannerPhase::InitialScan,
                     share_private_files,
@@ -2098,6 +2116,7 @@ impl Snapshot {
                         RepositoryEntry {
                             work_directory: work_directory_entry,
                             branch: repository.branch.map(Into::into),
+                            upstream_status: None,
                             // When syncing repository entries from a peer, we don't need
                             // the location_in_repo field, since git operations don't happen locally
                             // anyway.
@@ -2515,7 +2534,7 @@ impl LocalSnapshot {
                     new_ignores.push((ancestor, None));
                 }
             }
-            if ancestor.join(&*DOT_GIT).is_dir() {
+            if ancestor.join(&*DOT_GIT).exists() {
                 break;
             }
         }
@@ -2858,12 +2877,19 @@ impl BackgroundScannerState {
         let repository = fs.open_repo(&abs_path)?;
         log::trace!("constructed libgit2 repo in {:?}", t0.elapsed());
         let work_directory = RepositoryWorkDirectory(work_dir_path.clone());
+        let linked_git_dirs = repository.linked_git_dirs();
+        for git_dir in &linked_git_dirs {
+            if self.watched_git_dirs.insert(git_dir.clone()) {
+                self.git_dirs_to_watch.push(git_dir.clone());
+            }
+        }
 
         self.snapshot.repository_entries.insert(
             work_directory.clone(),
             RepositoryEntry {
                 work_directory: work_dir_id.into(),
                 branch: repository.branch_name().map(Into::into),
+                upstream_status: repository.upstream_status(),
                 location_in_repo,
             },
         );
@@ -2873,6 +2899,7 @@ impl BackgroundScannerState {
                 git_dir_scan_id: 0,
                 repo_ptr: repository.clone(),
                 git_dir_path: dot_git_path.clone(),
+                linked_git_dirs: linked_git_dirs.into(),
             },
         );
 
@@ -3282,7 +3309,7 @@ impl sum_tree::Item for Entry {
         match self.git_status {
             Some(status) => match status {
                 GitFileStatus::Added => statuses.added = 1,
-                GitFileStatus::Modified => statuses.modified = 1,
+                GitFileStatus::Modified | GitFileStatus::Deleted => statuses.modified = 1,
                 GitFileStatus::Conflict => statuses.conflict = 1,
             },
             None => {}
@@ -3444,8 +3471,9 @@ impl BackgroundScanner {
                 }
             }
 
+            // In linked worktrees and submodules, .git is a file rather than a folder.
             let ancestor_dot_git = ancestor.join(&*DOT_GIT);
-            if ancestor_dot_git.is_dir() {
+            if ancestor_dot_git.exists() {
                 if index != 0 {
                     // We canonicalize, since the FS events use the canonicalized path.
                     if let Some(ancestor_dot_git) =
@@ -3496,6 +3524,7 @@ impl BackgroundScanner {
         }
 
         self.send_status_update(false, None);
+        self.watch_linked_git_dirs(&mut fs_events_rx).await;
 
         // Process any any FS events that occurred while performing the initial scan.
         // For these events, update events cannot be as precise, because we didn't
@@ -3549,6 +3578,23 @@ impl BackgroundScanner {
                     self.process_events(paths.clone()).await;
                 }
             }
+
+            self.watch_linked_git_dirs(&mut fs_events_rx).await;
+        }
+    }
+
+    /// Starts watching the folders holding the metadata of the repositories found since the
+    /// last call, whose .git is a file pointing to a folder that may be outside of the
+    /// worktree.
+    async fn watch_linked_git_dirs(
+        &self,
+        fs_events_rx: &mut Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>>,
+    ) {
+        let git_dirs = mem::take(&mut self.state.lock().git_dirs_to_watch);
+        for git_dir in git_dirs {
+            let (git_dir_events, _) = self.fs.watch(&git_dir, FS_WATCH_LATENCY).await;
+            let events = mem::replace(fs_events_rx, futures::stream::pending().boxed());
+            *fs_events_rx = select(events, git_dir_events).boxed();
         }
     }
 
@@ -3617,7 +3663,24 @@ impl BackgroundScanner {
             let snapshot = &self.state.lock().snapshot;
             {
                 let mut is_git_related = false;
-                if let Some(dot_git_dir) = abs_path
+                let linked_dot_git_paths = snapshot
+                    .git_repositories
+                    .values()
+                    .filter(|repo| {
+                        repo.linked_git_dirs
+                            .iter()
+                            .any(|git_dir| abs_path.starts_with(git_dir))
+                    })
+                    .map(|repo| repo.git_dir_path.to_path_buf())
+                    .collect::<Vec<_>>();
+                if !linked_dot_git_paths.is_empty() {
+                    for dot_git_path in linked_dot_git_paths {
+                        if !dot_git_paths.contains(&dot_git_path) {
+                            dot_git_paths.push(dot_git_path);
+                        }
+                    }
+                    is_git_related = true;
+                } else if let Some(dot_git_dir) = abs_path
                     .ancestors()
                     .find(|ancestor| ancestor.file_name() == Some(*DOT_GIT))
                 {
@@ -4373,6 +4436,7 @@ impl BackgroundScanner {
 
                         let repo = &repository.repo_ptr;
                         let branch = repo.branch_name();
+                        let upstream_status = repo.upstream_status();
                         repo.reload_index();
 
                         state
@@ -4383,7 +4447,10 @@ impl BackgroundScanner {
                             .snapshot
                             .snapshot
                             .repository_entries
-                            .update(&work_dir, |entry| entry.branch = branch.map(Into::into));
+                            .update(&work_dir, |entry| {
+                                entry.branch = branch.map(Into::into);
+                                entry.upstream_status = upstream_status;
+                            });
                         (work_dir, repository.repo_ptr.clone())
                     }
                 };
@@ -4478,10 +4545,25 @@ impl BackgroundScanner {
         let t0 = Instant::now();
         let mut changes = Vec::new();
         let snapshot = self.state.lock().snapshot.snapshot.clone();
+        // The files of nested repositories, such as submodules, get their statuses from them.
+        let nested_work_directories = snapshot
+            .repositories()
+            .map(|(work_directory, _)| work_directory)
+            .filter(|work_directory| {
+                work_directory.starts_with(&job.work_directory.0)
+                    && **work_directory != job.work_directory.0
+            })
+            .collect::<Vec<_>>();
         for file in snapshot.traverse_from_path(true, false, false, job.work_directory.0.as_ref()) {
             let Ok(repo_path) = file.path.strip_prefix(&job.work_directory.0) else {
                 break;
             };
+            if nested_work_directories
+                .iter()
+                .any(|work_directory| file.path.starts_with(work_directory))
+            {
+                continue;
+            }
             let git_status = if let Some(location) = &job.location_in_repo {
                 statuses.get(&location.join(repo_path))
             } else {
@@ -5120,6 +5202,7 @@ fn git_status_from_proto(git_status: Option<i32>) -> Option<GitFileStatus> {
             proto::GitStatus::Added => GitFileStatus::Added,
             proto::GitStatus::Modified => GitFileStatus::Modified,
             proto::GitStatus::Conflict => GitFileStatus::Conflict,
+            proto::GitStatus::Deleted => GitFileStatus::Deleted,
         })
     })
 }
@@ -5129,6 +5212,7 @@ fn git_status_to_proto(status: GitFileStatus) -> i32 {
         GitFileStatus::Added => proto::GitStatus::Added as i32,
         GitFileStatus::Modified => proto::GitStatus::Modified as i32,
         GitFileStatus::Conflict => proto::GitStatus::Conflict as i32,
+        GitFileStatus::Deleted => proto::GitStatus::Deleted as i32,
     }
 }
 
@@ -2311,6 +2311,224 @@ async fn test_repository_subfolder_git_status(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_linked_worktree_git_status(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let root = temp_tree(json!({
+        "main": {
+            "a.txt": "a",
+        },
+    }));
+
+    // Set up a repository with a linked worktree before creating the worktree.
+    let repo = git_init(root.path().join("main").as_path());
+    git_add("a.txt", &repo);
+    git_commit("Initial commit", &repo);
+    let linked_path = root.path().join("linked");
+    repo.worktree("linked", &linked_path, None).unwrap();
+    std::fs::write(linked_path.join("b.txt"), "b").unwrap();
+
+    let tree = Worktree::local(
+        linked_path.as_path(),
+        true,
+        Arc::new(RealFs::default()),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    tree.flush_fs_events(cx).await;
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    cx.executor().run_until_parked();
+
+    // The linked worktree's `.git` is a file pointing to the main repository.
+    tree.read_with(cx, |tree, _cx| {
+        let snapshot = tree.snapshot();
+        assert_eq!(snapshot.repositories().count(), 1);
+        let (dir, repo_entry) = snapshot.repositories().next().unwrap();
+        assert_eq!(dir.as_ref(), Path::new(""));
+        assert_eq!(repo_entry.branch().as_deref(), Some("linked"));
+        assert_eq!(snapshot.status_for_file("a.txt"), None);
+        assert_eq!(
+            snapshot.status_for_file("b.txt"),
+            Some(GitFileStatus::Added)
+        );
+    });
+
+    // Committing only changes the metadata in the main repository's `.git` folder, which is
+    // outside of the worktree.
+    let linked_repo = git2::Repository::open(&linked_path).unwrap();
+    git_add("b.txt", &linked_repo);
+    git_commit("Add b.txt", &linked_repo);
+    cx.condition(&tree, |tree, _| tree.status_for_file("b.txt").is_none())
+        .await;
+}
+
+#[gpui::test]
+async fn test_submodule_git_status(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let root = temp_tree(json!({
+        "project": {
+            "a.txt": "a",
+        },
+        "library": {
+            "b.txt": "b",
+        },
+    }));
+
+    // Set up a repository with a submodule before creating the worktree.
+    let library_repo = git_init(root.path().join("library").as_path());
+    git_add("b.txt", &library_repo);
+    git_commit("Initial commit", &library_repo);
+
+    let work_dir = root.path().join("project");
+    let repo = git_init(work_dir.as_path());
+    git_add("a.txt", &repo);
+    let library_url = root.path().join("library").to_string_lossy().to_string();
+    let mut submodule = repo
+        .submodule(&library_url, Path::new("library"), true)
+        .unwrap();
+    submodule.clone(None).unwrap();
+    submodule.add_finalize().unwrap();
+    git_commit("Initial commit", &repo);
+
+    let tree = Worktree::local(
+        work_dir.as_path(),
+        true,
+        Arc::new(RealFs::default()),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    tree.flush_fs_events(cx).await;
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    cx.executor().run_until_parked();
+
+    // The submodule is a repository of its own.
+    let library_branch = library_repo
+        .head()
+        .unwrap()
+        .shorthand()
+        .map(Arc::<str>::from);
+    tree.read_with(cx, |tree, _cx| {
+        let snapshot = tree.snapshot();
+        let repositories = snapshot
+            .repositories()
+            .map(|(dir, repo_entry)| (dir.as_ref(), repo_entry.branch()))
+            .collect::<Vec<_>>();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(repositories[0].0, Path::new(""));
+        assert_eq!(repositories[1], (Path::new("library"), library_branch));
+    });
+
+    // Statuses of the submodule's files come from the submodule.
+    std::fs::write(work_dir.join("library/b.txt"), "bb").unwrap();
+    tree.flush_fs_events(cx).await;
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _cx| {
+        let snapshot = tree.snapshot();
+        assert_eq!(
+            snapshot.status_for_file("library/b.txt"),
+            Some(GitFileStatus::Modified)
+        );
+    });
+
+    // Reloading the parent repository keeps them.
+    let repo_updates = Arc::new(Mutex::new(Vec::new()));
+    tree.update(cx, |_, cx| {
+        let repo_updates = repo_updates.clone();
+        cx.subscribe(&tree, move |_, _, event, _| {
+            if let Event::UpdatedGitRepositories(update) = event {
+                repo_updates
+                    .lock()
+                    .extend(update.iter().map(|(path, _)| path.clone()));
+            }
+        })
+        .detach();
+    });
+    git_commit("Second commit", &repo);
+    cx.condition(&tree, |_, _| {
+        repo_updates
+            .lock()
+            .iter()
+            .any(|path| path.as_ref() == Path::new(""))
+    })
+    .await;
+    tree.read_with(cx, |tree, _cx| {
+        let snapshot = tree.snapshot();
+        assert_eq!(
+            snapshot.status_for_file("library/b.txt"),
+            Some(GitFileStatus::Modified)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_outer_gitignore_in_submodule(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let root = temp_tree(json!({
+        "project": {
+            ".gitignore": "*.log\n",
+            "a.txt": "a",
+        },
+        "library": {
+            "b.txt": "b",
+        },
+    }));
+
+    let library_repo = git_init(root.path().join("library").as_path());
+    git_add("b.txt", &library_repo);
+    git_commit("Initial commit", &library_repo);
+
+    let work_dir = root.path().join("project");
+    let repo = git_init(work_dir.as_path());
+    git_add(".gitignore", &repo);
+    git_add("a.txt", &repo);
+    let library_url = root.path().join("library").to_string_lossy().to_string();
+    let mut submodule = repo
+        .submodule(&library_url, Path::new("library"), true)
+        .unwrap();
+    submodule.clone(None).unwrap();
+    submodule.add_finalize().unwrap();
+    git_commit("Initial commit", &repo);
+
+    let tree = Worktree::local(
+        work_dir.as_path(),
+        true,
+        Arc::new(RealFs::default()),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    tree.flush_fs_events(cx).await;
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    cx.executor().run_until_parked();
+
+    // The submodule's `.git` is a file, and the rules of the outer repository stop there.
+    std::fs::write(work_dir.join("debug.log"), "").unwrap();
+    std::fs::write(work_dir.join("library/debug.log"), "").unwrap();
+    tree.flush_fs_events(cx).await;
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _cx| {
+        assert!(tree.entry_for_path("debug.log").unwrap().is_ignored);
+        assert!(!tree.entry_for_path("library/debug.log").unwrap().is_ignored);
+    });
+}
+
 #[gpui::test]
 async fn test_propagate_git_statuses(cx: &mut TestAppContext) {
     init_test(cx);
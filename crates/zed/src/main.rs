@@ -227,8 +227,12 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     dev_server_projects::init(app_state.client.clone(), cx);
     outline::init(cx);
     project_symbols::init(cx);
+    hierarchy_view::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
+    git_panel::init(cx);
+    git_history::init(cx);
+    diff_view::init(cx);
     tasks_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
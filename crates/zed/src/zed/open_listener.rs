@@ -10,8 +10,9 @@ use editor::scroll::Autoscroll;
 use editor::Editor;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
-use futures::{FutureExt, SinkExt, StreamExt};
-use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
+use futures::future::LocalBoxFuture;
+use futures::{Future, FutureExt, SinkExt, StreamExt};
+use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Global, WindowHandle};
 use language::{Bias, Point};
 use remote::SshConnectionOptions;
 use std::sync::Arc;
@@ -252,6 +253,7 @@ pub async fn handle_cli_connection(
             CliRequest::Open {
                 urls,
                 paths,
+                diff_paths,
                 wait,
                 open_new_workspace,
                 dev_server_token,
@@ -319,17 +321,44 @@ pub async fn handle_cli_connection(
                     return;
                 }
 
-                let open_workspace_result = open_workspaces(
-                    paths,
+                // Everything is opened before waiting, so that the diffs don't wait for the
+                // workspaces to be closed.
+                let mut release_futures = Vec::new();
+                let open_workspace_result = if paths.is_empty() && !diff_paths.is_empty() {
+                    Ok(())
+                } else {
+                    open_workspaces(
+                        paths,
+                        open_new_workspace,
+                        &responses,
+                        wait,
+                        &mut release_futures,
+                        app_state.clone(),
+                        &mut cx,
+                    )
+                    .await
+                };
+                let open_diffs_result = open_diffs(
+                    diff_paths,
                     open_new_workspace,
                     &responses,
                     wait,
+                    &mut release_futures,
                     app_state.clone(),
                     &mut cx,
                 )
                 .await;
 
-                let status = if open_workspace_result.is_err() { 1 } else { 0 };
+                if !release_futures.is_empty() {
+                    let released = futures::future::join_all(release_futures).map(|_| ());
+                    wait_while_cli_is_open(released, &responses, cx.background_executor()).await;
+                }
+
+                let status = if open_workspace_result.is_err() || open_diffs_result.is_err() {
+                    1
+                } else {
+                    0
+                };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
         }
@@ -341,6 +370,7 @@ async fn open_workspaces(
     open_new_workspace: Option<bool>,
     responses: &IpcSender<CliResponse>,
     wait: bool,
+    release_futures: &mut Vec<LocalBoxFuture<'static, ()>>,
     app_state: Arc<AppState>,
     mut cx: &mut AsyncAppContext,
 ) -> Result<()> {
@@ -406,6 +436,7 @@ async fn open_workspaces(
                 workspace_paths,
                 open_new_workspace,
                 wait,
+                release_futures,
                 responses,
                 &app_state,
                 &mut cx,
@@ -425,10 +456,95 @@ async fn open_workspaces(
     Ok(())
 }
 
+/// Opens a side-by-side diff for each pair of paths, in the active workspace or in a new one
+/// when there's no active workspace or a new one is requested. With `wait`, adds a future to
+/// `release_futures` that resolves once all the diffs are closed.
+async fn open_diffs(
+    diff_paths: Vec<[String; 2]>,
+    open_new_workspace: Option<bool>,
+    responses: &IpcSender<CliResponse>,
+    wait: bool,
+    release_futures: &mut Vec<LocalBoxFuture<'static, ()>>,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    if diff_paths.is_empty() {
+        return Ok(());
+    }
+
+    let active_workspace = if open_new_workspace == Some(true) {
+        None
+    } else {
+        cx.update(|cx| {
+            cx.active_window()
+                .and_then(|window| window.downcast::<Workspace>())
+        })?
+    };
+    let workspace = match active_workspace {
+        Some(workspace) => workspace,
+        None => {
+            cx.update(|cx| Workspace::new_local(Vec::new(), app_state, None, cx))?
+                .await?
+                .0
+        }
+    };
+
+    let tasks = workspace.update(cx, |workspace, cx| {
+        diff_paths
+            .into_iter()
+            .map(|[old_path, new_path]| {
+                diff_view::open_file_diff(old_path.into(), new_path.into(), workspace, cx)
+            })
+            .collect::<Vec<_>>()
+    })?;
+    let mut errored = false;
+    let mut diff_release_futures = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(diff) => {
+                cx.update(|cx| {
+                    let released = oneshot::channel();
+                    diff.on_release(
+                        cx,
+                        Box::new(move |_| {
+                            let _ = released.0.send(());
+                        }),
+                    )
+                    .detach();
+                    diff_release_futures.push(released.1);
+                })
+                .log_err();
+            }
+            Err(error) => {
+                responses
+                    .send(CliResponse::Stderr {
+                        message: format!("error opening diff: {error:#}"),
+                    })
+                    .log_err();
+                errored = true;
+            }
+        }
+    }
+
+    if wait && !diff_release_futures.is_empty() {
+        release_futures.push(
+            futures::future::try_join_all(diff_release_futures)
+                .map(|_| ())
+                .boxed_local(),
+        );
+    }
+
+    if errored {
+        return Err(anyhow!("failed to open a diff"));
+    }
+    Ok(())
+}
+
 async fn open_workspace(
     workspace_paths: Vec<PathWithPosition>,
     open_new_workspace: Option<bool>,
     wait: bool,
+    release_futures: &mut Vec<LocalBoxFuture<'static, ()>>,
     responses: &IpcSender<CliResponse>,
     app_state: &Arc<AppState>,
     cx: &mut AsyncAppContext,
@@ -478,36 +594,26 @@ async fn open_workspace(
             }
 
             if wait {
-                let background = cx.background_executor().clone();
-                let wait = async move {
-                    if workspace_paths.is_empty() {
-                        let (done_tx, done_rx) = oneshot::channel();
-                        let _subscription = workspace.update(cx, |_, cx| {
-                            cx.on_release(move |_, _, _| {
-                                let _ = done_tx.send(());
-                            })
-                        });
-                        let _ = done_rx.await;
-                    } else {
-                        let _ = futures::future::try_join_all(item_release_futures).await;
-                    };
-                }
-                .fuse();
-
-                futures::pin_mut!(wait);
-
-                loop {
-                    // Repeatedly check if CLI is still open to avoid wasting resources
-                    // waiting for files or workspaces to close.
-                    let mut timer = background.timer(Duration::from_secs(1)).fuse();
-                    futures::select_biased! {
-                        _ = wait => break,
-                        _ = timer => {
-                            if responses.send(CliResponse::Ping).is_err() {
-                                break;
-                            }
+                if workspace_paths.is_empty() {
+                    let (done_tx, done_rx) = oneshot::channel();
+                    let subscription = workspace.update(cx, |_, cx| {
+                        cx.on_release(move |_, _, _| {
+                            let _ = done_tx.send(());
+                        })
+                    });
+                    release_futures.push(
+                        async move {
+                            let _subscription = subscription;
+                            let _ = done_rx.await;
                         }
-                    }
+                        .boxed_local(),
+                    );
+                } else {
+                    release_futures.push(
+                        futures::future::try_join_all(item_release_futures)
+                            .map(|_| ())
+                            .boxed_local(),
+                    );
                 }
             }
         }
@@ -523,6 +629,30 @@ async fn open_workspace(
     errored
 }
 
+/// Waits for `done`, unless the CLI that is waiting for it exits first.
+async fn wait_while_cli_is_open(
+    done: impl Future<Output = ()>,
+    responses: &IpcSender<CliResponse>,
+    background: &BackgroundExecutor,
+) {
+    let done = done.fuse();
+    futures::pin_mut!(done);
+
+    loop {
+        // Repeatedly check if CLI is still open to avoid wasting resources
+        // waiting for files or workspaces to close.
+        let mut timer = background.timer(Duration::from_secs(1)).fuse();
+        futures::select_biased! {
+            _ = done => break,
+            _ = timer => {
+                if responses.send(CliResponse::Ping).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{path::PathBuf, sync::Arc};
@@ -661,6 +791,7 @@ mod tests {
                     workspace_paths,
                     open_new_workspace,
                     false,
+                    &mut Vec::new(),
                     &response_tx,
                     &app_state,
                     &mut cx,